@@ -0,0 +1,92 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Cofre segregado de um projeto (RF-002).
+///
+/// Instanciado pelo core do Safeguard em `register_project`; mantém os ativos do
+/// projeto e só movimenta fundos por ordem do core, que concentra governança e claims.
+#[ink::contract]
+pub mod project_vault {
+    use ink::codegen::TraitCallBuilder;
+    use ink::prelude::vec::Vec;
+    use psp22::{PSP22, PSP22Error};
+
+    type Psp22Ref = ink::contract_ref!(PSP22, ink::env::DefaultEnvironment);
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum VaultError {
+        /// Apenas o core pode movimentar o cofre
+        NotCore,
+        ZeroAddress,
+        /// O token recusou a transferência
+        Token(PSP22Error),
+        /// A chamada ao token falhou antes de retornar
+        CallFailed,
+    }
+
+    // Sem eventos próprios: o core emite `TokenTransferExecuted` com o cofre como origem
+    #[ink(storage)]
+    pub struct ProjectVault {
+        core: AccountId, // Contrato Safeguard; upgrades do core preservam o endereço (set_code_hash)
+        project_id: u64,
+    }
+
+    impl ProjectVault {
+        #[ink(constructor)]
+        pub fn new(core: AccountId, project_id: u64) -> Self {
+            Self { core, project_id }
+        }
+
+        /// Transfere `amount` de `token` para `to` (somente o core)
+        #[ink(message)]
+        pub fn transfer_token(&mut self, token: AccountId, to: AccountId, amount: Balance) -> Result<(), VaultError> {
+            self._ensure_core()?;
+            if to == AccountId::from([0u8; 32]) {
+                return Err(VaultError::ZeroAddress);
+            }
+            let mut token_ref: Psp22Ref = token.into();
+            match token_ref.call_mut().transfer(to, amount, Vec::new()).gas_limit(0).try_invoke() {
+                Ok(Ok(Ok(()))) => {}
+                Ok(Ok(Err(error))) => return Err(VaultError::Token(error)),
+                _ => return Err(VaultError::CallFailed),
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_core(&self) -> AccountId {
+            self.core
+        }
+
+        #[ink(message)]
+        pub fn get_project_id(&self) -> u64 {
+            self.project_id
+        }
+
+        fn _ensure_core(&self) -> Result<(), VaultError> {
+            if self.env().caller() != self.core {
+                return Err(VaultError::NotCore);
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{default_accounts, set_caller};
+
+        #[ink::test]
+        fn test_only_core_moves_funds() {
+            let accounts = default_accounts::<ink::env::DefaultEnvironment>();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let mut vault = ProjectVault::new(accounts.alice, 7);
+            assert_eq!(vault.get_project_id(), 7);
+            assert_eq!(vault.transfer_token(accounts.django, accounts.bob, 1), Err(VaultError::NotCore));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(vault.transfer_token(accounts.django, AccountId::from([0u8; 32]), 1), Err(VaultError::ZeroAddress));
+            assert_eq!(vault.get_core(), accounts.alice);
+        }
+    }
+}
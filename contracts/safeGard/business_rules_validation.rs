@@ -4,6 +4,7 @@ mod tests {
     use ink::env::test::{default_accounts, set_caller};
     use crate::safeguard::*;
 
+    #[cfg(feature = "legacy-api")]
     #[ink::test]
     fn test_project_registration_with_metadata() {
         let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
@@ -38,6 +39,7 @@ mod tests {
         assert_eq!(info.status, true); // Active
     }
 
+    #[cfg(feature = "legacy-api")]
     #[ink::test]
     fn test_multiple_registrations_and_iteration() {
         let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
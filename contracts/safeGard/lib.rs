@@ -11,6 +11,9 @@ pub mod security {
     use scale::{Decode, Encode};
 
     /// Custom error types
+    ///
+    /// A ordem das variantes define o índice SCALE e o código numérico (`code`);
+    /// novas variantes entram sempre no final.
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum SafeguardError {
@@ -56,11 +59,102 @@ pub mod security {
         // Cross-contract errors
         TransferFailed,
         ApprovalFailed,
+        // Governance errors
+        GovernanceRequired,
+        TimelockRequired,
+        NotGuardian,
+        VetoWindowClosed,
+        // Council errors
+        CouncilApprovalRequired,
+        ProposalNotFound,
+        ProposalAlreadyExecuted,
+        MissingRole,
+        // Ownership transfer errors
+        NoPendingOwnership,
+        OwnershipTransferExpired,
+        // Circuit breaker errors
+        ModulePaused,
+        // Rate limit errors
+        OutflowLimitExceeded,
+        QueuedOutflowNotFound,
+        // Escape hatch errors
+        EscapeHatchActive,
+        EscapeHatchNotActive,
+        // Structured cross-contract errors
+        TokenCallFailed {
+            token: AccountId,
+            stage: CallStage,
+            cause: CallFailure,
+        },
+        TokenError(psp22::PSP22Error),
+        // Compliance errors
+        ComplianceDenied(Option<ink::prelude::string::String>),
+        ComplianceHookFailed,
+        // Upgrade errors
+        MigrationPending,
+        NoMigrationPending,
+        CodeUpgradeFailed,
+        // State import errors
+        ImportInProgress,
+        ImportNotActive,
+        ImportLocked,
+        ImportBalanceMismatch,
+        // Factory errors
+        VaultInstantiationFailed,
+        VaultAlreadyExists,
+        // Legacy migration errors
+        ProjectAlreadyMigrated,
+        // Subsistema fora desta build (features `legacy-api`, `nft-collateral`, `timelock`, `builtin-score`)
+        FeatureDisabled,
+        // Registros importados não fecham com os totais dos cofres
+        ImportTotalsMismatch,
+        // O motor de score configurado não respondeu
+        ScoreEngineCallFailed,
+        // Id de operação já usado (mesma chamada e salt); ids nunca são reaproveitados
+        OperationAlreadyExists,
+        // NFT já custodiado como garantia (de qualquer projeto)
+        NFTAlreadyDeposited,
+        // O cofre dedicado só movimenta tokens com os seletores namespaced do trait PSP22
+        VaultTokenSchemeUnsupported,
     }
 
-    impl From<SafeguardError> for ink::LangError {
-        fn from(_error: SafeguardError) -> Self {
-            ink::LangError::CouldNotReadInput
+    /// Etapa da chamada ao token que falhou
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum CallStage {
+        /// `transfer_from` rejeitado por falta de allowance
+        Allowance,
+        /// Leitura de saldo (atual ou histórico)
+        Balance,
+        Transfer,
+    }
+
+    /// Causa de uma falha em chamada cross-contract
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum CallFailure {
+        /// O token respondeu com um erro PSP22
+        Psp22(psp22::PSP22Error),
+        /// O token não reconheceu o seletor ou não decodificou os argumentos
+        Dispatch,
+        CalleeTrapped,
+        CalleeReverted,
+        /// O endereço não é um contrato
+        NotCallable,
+        /// A resposta não pôde ser decodificada
+        Decode,
+        Environment,
+    }
+
+    impl From<ink::env::Error> for CallFailure {
+        fn from(error: ink::env::Error) -> Self {
+            match error {
+                ink::env::Error::CalleeTrapped => CallFailure::CalleeTrapped,
+                ink::env::Error::CalleeReverted => CallFailure::CalleeReverted,
+                ink::env::Error::NotCallable | ink::env::Error::CodeNotFound => CallFailure::NotCallable,
+                ink::env::Error::Decode(_) => CallFailure::Decode,
+                _ => CallFailure::Environment,
+            }
         }
     }
 
@@ -71,8 +165,89 @@ pub mod security {
                 psp22::PSP22Error::InsufficientAllowance => SafeguardError::InsufficientAllowance,
                 psp22::PSP22Error::ZeroRecipientAddress => SafeguardError::ZeroAddress,
                 psp22::PSP22Error::ZeroSenderAddress => SafeguardError::ZeroAddress,
-                psp22::PSP22Error::Custom(_) => SafeguardError::InvalidInput,
-                psp22::PSP22Error::SafeTransferCheckFailed(_) => SafeguardError::InvalidInput,
+                // Mensagens customizadas do token são preservadas
+                other => SafeguardError::TokenError(other),
+            }
+        }
+    }
+
+    impl SafeguardError {
+        /// Código numérico estável exposto aos clientes; códigos nunca são reutilizados
+        pub fn code(&self) -> u16 {
+            match self {
+                SafeguardError::NotOwner => 1,
+                SafeguardError::ProjectNotFound => 2,
+                SafeguardError::ProjectPaused => 3,
+                SafeguardError::VotingInProgress => 4,
+                SafeguardError::VotingNotActive => 5,
+                SafeguardError::AlreadyVoted => 6,
+                SafeguardError::WithdrawalInProgress => 7,
+                SafeguardError::InsufficientBalance => 8,
+                SafeguardError::InvalidAmount => 9,
+                SafeguardError::ZeroAddress => 10,
+                SafeguardError::TokenNotSupported => 11,
+                SafeguardError::NFTNotSupported => 12,
+                SafeguardError::VestingPeriodNotMet => 13,
+                SafeguardError::ReentrancyDetected => 14,
+                SafeguardError::InvalidInput => 15,
+                SafeguardError::ProjectNotActive => 16,
+                SafeguardError::NoActiveVoting => 17,
+                SafeguardError::VotingNotFound => 18,
+                SafeguardError::TooEarlyForVoting => 19,
+                SafeguardError::VotingExpired => 20,
+                SafeguardError::VotingStillActive => 21,
+                SafeguardError::NotEligibleToVote => 22,
+                SafeguardError::ClaimsNotOpen => 23,
+                SafeguardError::ClaimsExpired => 24,
+                SafeguardError::ClaimsStillOpen => 25,
+                SafeguardError::AlreadyClaimed => 26,
+                SafeguardError::NoClaimableAmount => 27,
+                SafeguardError::InsufficientAllowance => 28,
+                SafeguardError::ArithmeticOverflow => 29,
+                SafeguardError::ArithmeticUnderflow => 30,
+                SafeguardError::ContractPaused => 31,
+                SafeguardError::ContractNotPaused => 32,
+                SafeguardError::OperationPending => 33,
+                SafeguardError::OperationNotReady => 34,
+                SafeguardError::OperationNotFound => 35,
+                SafeguardError::OperationExpired => 36,
+                SafeguardError::TransferFailed => 37,
+                SafeguardError::ApprovalFailed => 38,
+                SafeguardError::GovernanceRequired => 39,
+                SafeguardError::TimelockRequired => 40,
+                SafeguardError::NotGuardian => 41,
+                SafeguardError::VetoWindowClosed => 42,
+                SafeguardError::CouncilApprovalRequired => 43,
+                SafeguardError::ProposalNotFound => 44,
+                SafeguardError::ProposalAlreadyExecuted => 45,
+                SafeguardError::MissingRole => 46,
+                SafeguardError::NoPendingOwnership => 47,
+                SafeguardError::OwnershipTransferExpired => 48,
+                SafeguardError::ModulePaused => 49,
+                SafeguardError::OutflowLimitExceeded => 50,
+                SafeguardError::QueuedOutflowNotFound => 51,
+                SafeguardError::EscapeHatchActive => 52,
+                SafeguardError::EscapeHatchNotActive => 53,
+                SafeguardError::TokenCallFailed { .. } => 54,
+                SafeguardError::TokenError(_) => 55,
+                SafeguardError::ComplianceDenied(_) => 56,
+                SafeguardError::ComplianceHookFailed => 57,
+                SafeguardError::MigrationPending => 58,
+                SafeguardError::NoMigrationPending => 59,
+                SafeguardError::CodeUpgradeFailed => 60,
+                SafeguardError::ImportInProgress => 61,
+                SafeguardError::ImportNotActive => 62,
+                SafeguardError::ImportLocked => 63,
+                SafeguardError::ImportBalanceMismatch => 64,
+                SafeguardError::VaultInstantiationFailed => 65,
+                SafeguardError::VaultAlreadyExists => 66,
+                SafeguardError::ProjectAlreadyMigrated => 67,
+                SafeguardError::FeatureDisabled => 68,
+                SafeguardError::ImportTotalsMismatch => 69,
+                SafeguardError::ScoreEngineCallFailed => 70,
+                SafeguardError::OperationAlreadyExists => 71,
+                SafeguardError::NFTAlreadyDeposited => 72,
+                SafeguardError::VaultTokenSchemeUnsupported => 73,
             }
         }
     }
@@ -117,6 +292,67 @@ pub mod security {
         pub fn safe_sub_balance(a: Balance, b: Balance) -> Result<Balance, SafeguardError> {
             a.checked_sub(b).ok_or(SafeguardError::ArithmeticUnderflow)
         }
+
+        /// Integer square root (floor) using Newton's method
+        pub fn isqrt(value: Balance) -> Balance {
+            if value < 2 {
+                return value;
+            }
+            let mut x = value;
+            let mut y = x.saturating_add(1).saturating_div(2);
+            while y < x {
+                x = y;
+                y = x.saturating_add(value.checked_div(x).unwrap_or(0)).saturating_div(2);
+            }
+            x
+        }
+
+        /// `a * b / c` (floor) com o produto em 256 bits; satura se o resultado não cabe em u128
+        pub fn mul_div(a: Balance, b: Balance, c: Balance) -> Balance {
+            if c == 0 {
+                return 0;
+            }
+            if let Some(product) = a.checked_mul(b) {
+                return product.checked_div(c).unwrap_or(0);
+            }
+            let (high, low) = mul_wide(a, b);
+            if high >= c {
+                return Balance::MAX;
+            }
+            // Divisão longa bit a bit de (high, low) por c; o resto sempre fica abaixo de c
+            let mut remainder = high;
+            let mut quotient: Balance = 0;
+            for bit in (0..128u32).rev() {
+                let carry = remainder.wrapping_shr(127) == 1;
+                remainder = remainder.wrapping_shl(1) | (low.wrapping_shr(bit) & 1);
+                quotient = quotient.wrapping_shl(1);
+                if carry || remainder >= c {
+                    remainder = remainder.wrapping_sub(c);
+                    quotient |= 1;
+                }
+            }
+            quotient
+        }
+
+        /// Produto completo de dois u128 como (parte alta, parte baixa)
+        fn mul_wide(a: Balance, b: Balance) -> (Balance, Balance) {
+            const MASK: Balance = u64::MAX as Balance;
+            let (a_high, a_low) = (a.wrapping_shr(64), a & MASK);
+            let (b_high, b_low) = (b.wrapping_shr(64), b & MASK);
+            let low_low = a_low.wrapping_mul(b_low);
+            let low_high = a_low.wrapping_mul(b_high);
+            let high_low = a_high.wrapping_mul(b_low);
+            let high_high = a_high.wrapping_mul(b_high);
+            let middle = low_low.wrapping_shr(64)
+                .wrapping_add(low_high & MASK)
+                .wrapping_add(high_low & MASK);
+            let low = (low_low & MASK) | middle.wrapping_shl(64);
+            let high = high_high
+                .wrapping_add(low_high.wrapping_shr(64))
+                .wrapping_add(high_low.wrapping_shr(64))
+                .wrapping_add(middle.wrapping_shr(64));
+            (high, low)
+        }
     }
 
     /// Input validation utilities
@@ -139,10 +375,604 @@ pub mod security {
     }
 }
 
+/// Ponto único das chamadas entre contratos (tokens, cofres, motor de score, hooks de compliance).
+/// On-chain repassa ao ambiente; nos testes off-chain, que não executam contratos, os mesmos
+/// bytes SCALE (seletor + argumentos) são atendidos pelos contratos simulados de `mock_env`.
+pub mod cross_call {
+    use ink::env::call::{Call, CallParams, ConstructorReturnType, CreateParams, FromAccountId};
+    use ink::env::DefaultEnvironment;
+
+    pub fn invoke<Args, R>(
+        params: &CallParams<DefaultEnvironment, Call<DefaultEnvironment>, Args, R>,
+    ) -> ink::env::Result<ink::MessageResult<R>>
+    where
+        Args: scale::Encode,
+        R: scale::Decode,
+    {
+        #[cfg(not(test))]
+        return params.try_invoke();
+        #[cfg(test)]
+        {
+            let output = mock_env::call(*params.callee(), &scale::Encode::encode(params.exec_input()))?;
+            scale::Decode::decode(&mut &output[..]).map_err(ink::env::Error::Decode)
+        }
+    }
+
+    pub fn instantiate<ContractRef, Args, Salt, R>(
+        params: &CreateParams<DefaultEnvironment, ContractRef, Args, Salt, R>,
+    ) -> ink::env::Result<ink::ConstructorResult<R::Output>>
+    where
+        ContractRef: FromAccountId<DefaultEnvironment>,
+        Args: scale::Encode,
+        Salt: AsRef<[u8]>,
+        R: ConstructorReturnType<ContractRef>,
+    {
+        #[cfg(not(test))]
+        return params.try_instantiate();
+        #[cfg(test)]
+        {
+            let account = mock_env::instantiate(
+                *params.code_hash(),
+                &scale::Encode::encode(params.exec_input()),
+                params.salt_bytes().as_ref(),
+            )?;
+            Ok(Ok(R::ok(ContractRef::from_account_id(account))))
+        }
+    }
+
+    /// Contratos simulados para os testes off-chain. Cada conta responde como o contrato
+    /// registrado nela; contas sem registro respondem como um token PSP22 com os seletores
+    /// namespaced do trait. Seletores desconhecidos devolvem `LangError::CouldNotReadInput`,
+    /// como o dispatcher do ink!.
+    #[cfg(test)]
+    pub mod mock_env {
+        use crate::psp22_client::{Psp22Selectors, SelectorScheme};
+        use crate::{AccountId, Balance};
+        use ink::env::DefaultEnvironment;
+        use ink::primitives::{Hash, LangError};
+        use project_vault::project_vault::VaultError;
+        use psp22::PSP22Error;
+        use scale::{Decode, Encode};
+        use score_engine::{ScoreInputs, ScoreParams};
+        use std::cell::RefCell;
+        use std::collections::BTreeMap;
+
+        #[derive(Debug, Clone, Copy)]
+        enum MockContract {
+            Psp22(SelectorScheme),
+            #[cfg(feature = "nft-collateral")]
+            Psp34,
+            ProjectVault { core: AccountId },
+            ScoreEngine { score: u8, version: u32 },
+        }
+
+        thread_local! {
+            static CONTRACTS: RefCell<BTreeMap<AccountId, MockContract>> = const { RefCell::new(BTreeMap::new()) };
+        }
+
+        fn contract_at(account: AccountId) -> MockContract {
+            CONTRACTS
+                .with(|contracts| contracts.borrow().get(&account).copied())
+                .unwrap_or(MockContract::Psp22(SelectorScheme::TraitNamespaced))
+        }
+
+        fn register(account: AccountId, contract: MockContract) {
+            CONTRACTS.with(|contracts| contracts.borrow_mut().insert(account, contract));
+        }
+
+        /// Token PSP22 que só entende os seletores de `scheme`
+        pub fn register_psp22(token: AccountId, scheme: SelectorScheme) {
+            register(token, MockContract::Psp22(scheme));
+        }
+
+        /// Motor de score que devolve sempre `score` e `version`
+        pub fn register_score_engine(engine: AccountId, score: u8, version: u32) {
+            register(engine, MockContract::ScoreEngine { score, version });
+        }
+
+        /// Retorno de mensagem codificado como o de `seal_call`
+        fn reply<T: Encode>(value: T) -> Vec<u8> {
+            Ok::<T, LangError>(value).encode()
+        }
+
+        fn unknown_message() -> Vec<u8> {
+            Err::<(), LangError>(LangError::CouldNotReadInput).encode()
+        }
+
+        pub(super) fn call(callee: AccountId, input: &[u8]) -> ink::env::Result<Vec<u8>> {
+            Ok(dispatch(ink::env::account_id::<DefaultEnvironment>(), callee, input))
+        }
+
+        fn dispatch(caller: AccountId, callee: AccountId, input: &[u8]) -> Vec<u8> {
+            let Some((selector, mut args)) = input.split_first_chunk::<4>() else {
+                return unknown_message();
+            };
+            let output = match contract_at(callee) {
+                MockContract::Psp22(scheme) => psp22_message(callee, scheme, caller, *selector, &mut args),
+                #[cfg(feature = "nft-collateral")]
+                MockContract::Psp34 => psp34_message(callee, *selector, &mut args),
+                MockContract::ProjectVault { core } => vault_message(callee, core, caller, *selector, &mut args),
+                MockContract::ScoreEngine { score, version } => score_engine_message(score, version, *selector, &mut args),
+            };
+            output.unwrap_or_else(unknown_message)
+        }
+
+        fn psp22_message(
+            token: AccountId,
+            scheme: SelectorScheme,
+            caller: AccountId,
+            selector: [u8; 4],
+            args: &mut &[u8],
+        ) -> Option<Vec<u8>> {
+            let selectors = match scheme {
+                SelectorScheme::TraitNamespaced => Psp22Selectors {
+                    transfer: ink::selector_bytes!("PSP22::transfer"),
+                    transfer_from: ink::selector_bytes!("PSP22::transfer_from"),
+                    balance_of: ink::selector_bytes!("PSP22::balance_of"),
+                },
+                SelectorScheme::Bare => Psp22Selectors {
+                    transfer: ink::selector_bytes!("transfer"),
+                    transfer_from: ink::selector_bytes!("transfer_from"),
+                    balance_of: ink::selector_bytes!("balance_of"),
+                },
+                SelectorScheme::Custom(selectors) => selectors,
+            };
+            if selector == selectors.transfer {
+                let (to, value, _data) = <(AccountId, Balance, Vec<u8>)>::decode(args).ok()?;
+                Some(reply(psp22_ledger::transfer(token, caller, to, value)))
+            } else if selector == selectors.transfer_from {
+                let (from, to, value, _data) = <(AccountId, AccountId, Balance, Vec<u8>)>::decode(args).ok()?;
+                Some(reply(psp22_ledger::transfer_from(token, from, to, value)))
+            } else if selector == selectors.balance_of {
+                let owner = AccountId::decode(args).ok()?;
+                Some(reply(psp22_ledger::balance_of(token, owner)))
+            } else {
+                None
+            }
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        fn psp34_message(collection: AccountId, selector: [u8; 4], args: &mut &[u8]) -> Option<Vec<u8>> {
+            use crate::psp34_client::{Id, PSP34Error};
+            if selector == ink::selector_bytes!("PSP34::transfer") {
+                let (to, id, _data) = <(AccountId, Id, Vec<u8>)>::decode(args).ok()?;
+                // O core é operador aprovado de todos os donos (ver `psp34_ledger`)
+                let result = match id {
+                    Id::U64(id) => match psp34_ledger::owner_of(collection, id) {
+                        Some(owner) => psp34_ledger::transfer(collection, owner, to, id),
+                        None => Err(PSP34Error::TokenNotExists),
+                    },
+                    _ => Err(PSP34Error::TokenNotExists),
+                };
+                Some(reply(result))
+            } else if selector == ink::selector_bytes!("PSP34::owner_of") {
+                let owner = match Id::decode(args).ok()? {
+                    Id::U64(id) => psp34_ledger::owner_of(collection, id),
+                    _ => None,
+                };
+                Some(reply(owner))
+            } else {
+                None
+            }
+        }
+
+        fn vault_message(
+            vault: AccountId,
+            core: AccountId,
+            caller: AccountId,
+            selector: [u8; 4],
+            args: &mut &[u8],
+        ) -> Option<Vec<u8>> {
+            if selector != ink::selector_bytes!("transfer_token") {
+                return None;
+            }
+            let (token, to, amount) = <(AccountId, AccountId, Balance)>::decode(args).ok()?;
+            let result = if caller != core {
+                Err(VaultError::NotCore)
+            } else if to == AccountId::from([0u8; 32]) {
+                Err(VaultError::ZeroAddress)
+            } else {
+                // O cofre chama o token pelo trait PSP22, como `ProjectVault::transfer_token`
+                let input = (ink::selector_bytes!("PSP22::transfer"), to, amount, Vec::<u8>::new()).encode();
+                match ink::MessageResult::<Result<(), PSP22Error>>::decode(&mut &dispatch(vault, token, &input)[..]) {
+                    Ok(Ok(Ok(()))) => Ok(()),
+                    Ok(Ok(Err(error))) => Err(VaultError::Token(error)),
+                    _ => Err(VaultError::CallFailed),
+                }
+            };
+            Some(reply(result))
+        }
+
+        fn score_engine_message(score: u8, version: u32, selector: [u8; 4], args: &mut &[u8]) -> Option<Vec<u8>> {
+            if selector == ink::selector_bytes!("ScoreEngine::compute_score") {
+                <(ScoreInputs, ScoreParams)>::decode(args).ok()?;
+                Some(reply(score))
+            } else if selector == ink::selector_bytes!("ScoreEngine::formula_version") {
+                Some(reply(version))
+            } else {
+                None
+            }
+        }
+
+        /// O único código instanciado pelo core é o do `ProjectVault` (`new(core, project_id)`);
+        /// o endereço deriva do deployer, do code hash e do salt, como no pallet de contratos
+        pub(super) fn instantiate(code_hash: Hash, input: &[u8], salt: &[u8]) -> ink::env::Result<AccountId> {
+            let deployer = ink::env::account_id::<DefaultEnvironment>();
+            let Some((selector, mut args)) = input.split_first_chunk::<4>() else {
+                return Err(ink::env::Error::CalleeTrapped);
+            };
+            if *selector != ink::selector_bytes!("new") {
+                return Err(ink::env::Error::CalleeTrapped);
+            }
+            let (core, _project_id) = <(AccountId, u64)>::decode(&mut args).map_err(ink::env::Error::Decode)?;
+            let mut address = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&(deployer, code_hash, salt), &mut address);
+            let vault = AccountId::from(address);
+            register(vault, MockContract::ProjectVault { core });
+            Ok(vault)
+        }
+
+        /// Saldos dos tokens simulados. Carteiras externas são tratadas como financiadas
+        /// (`transfer_from` não falha), mas quem transfere do próprio saldo (o core, um cofre)
+        /// precisa ter saldo.
+        pub mod psp22_ledger {
+            use super::{AccountId, Balance, PSP22Error};
+            use std::cell::RefCell;
+            use std::collections::BTreeMap;
+
+            thread_local! {
+                static BALANCES: RefCell<BTreeMap<(AccountId, AccountId), Balance>> = const { RefCell::new(BTreeMap::new()) };
+            }
+
+            pub fn balance_of(token: AccountId, owner: AccountId) -> Balance {
+                BALANCES.with(|balances| balances.borrow().get(&(token, owner)).copied().unwrap_or(0))
+            }
+
+            pub fn set_balance(token: AccountId, owner: AccountId, amount: Balance) {
+                BALANCES.with(|balances| balances.borrow_mut().insert((token, owner), amount));
+            }
+
+            /// Transferência a partir do saldo de `from`, que precisa cobrir o valor
+            pub fn transfer(token: AccountId, from: AccountId, to: AccountId, value: Balance) -> Result<(), PSP22Error> {
+                let from_balance = balance_of(token, from);
+                if from_balance < value {
+                    return Err(PSP22Error::InsufficientBalance);
+                }
+                set_balance(token, from, from_balance.saturating_sub(value));
+                set_balance(token, to, balance_of(token, to).saturating_add(value));
+                Ok(())
+            }
+
+            /// Transferência a partir de uma carteira externa
+            pub fn transfer_from(token: AccountId, from: AccountId, to: AccountId, value: Balance) -> Result<(), PSP22Error> {
+                set_balance(token, from, balance_of(token, from).saturating_sub(value));
+                set_balance(token, to, balance_of(token, to).saturating_add(value));
+                Ok(())
+            }
+        }
+
+        /// Donos dos NFTs simulados. Os donos são tratados como tendo aprovado o core como
+        /// operador; o NFT precisa ter sido cunhado com `mint`, que registra a coleção.
+        #[cfg(feature = "nft-collateral")]
+        pub mod psp34_ledger {
+            use super::{register, AccountId, MockContract};
+            use crate::psp34_client::PSP34Error;
+            use std::cell::RefCell;
+            use std::collections::BTreeMap;
+
+            thread_local! {
+                static OWNERS: RefCell<BTreeMap<(AccountId, u64), AccountId>> = const { RefCell::new(BTreeMap::new()) };
+            }
+
+            pub fn owner_of(collection: AccountId, id: u64) -> Option<AccountId> {
+                OWNERS.with(|owners| owners.borrow().get(&(collection, id)).copied())
+            }
+
+            pub fn mint(collection: AccountId, id: u64, owner: AccountId) {
+                register(collection, MockContract::Psp34);
+                OWNERS.with(|owners| owners.borrow_mut().insert((collection, id), owner));
+            }
+
+            pub fn transfer(collection: AccountId, from: AccountId, to: AccountId, id: u64) -> Result<(), PSP34Error> {
+                if owner_of(collection, id) != Some(from) {
+                    return Err(PSP34Error::TokenNotExists);
+                }
+                OWNERS.with(|owners| owners.borrow_mut().insert((collection, id), to));
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Typed PSP22 client used by every cross-contract token call
+pub mod psp22_client {
+    use super::{Balance, AccountId};
+    use super::cross_call;
+    use super::security::{CallFailure, CallStage, SafeguardError};
+    use ink::codegen::TraitCallBuilder;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
+    use ink::prelude::vec::Vec;
+    use psp22::{PSP22, PSP22Error, PSP22Votes, BlockNumber};
+
+    type Psp22Ref = ink::contract_ref!(psp22::PSP22, DefaultEnvironment);
+    type Psp22VotesRef = ink::contract_ref!(psp22::PSP22Votes, DefaultEnvironment);
+
+    /// Selectors used by tokens that do not follow the trait-namespaced convention
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Psp22Selectors {
+        pub transfer: [u8; 4],
+        pub transfer_from: [u8; 4],
+        pub balance_of: [u8; 4],
+    }
+
+    /// Selector convention exposed by a PSP22 token
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum SelectorScheme {
+        /// `PSP22::transfer` etc. (tokens built from `interfaces/psp22`)
+        TraitNamespaced,
+        /// `transfer` etc. (inherent messages without the trait)
+        Bare,
+        Custom(Psp22Selectors),
+    }
+
+    impl SelectorScheme {
+        /// Raw selectors, or `None` when calls go through the trait contract ref
+        fn selectors(&self) -> Option<Psp22Selectors> {
+            match self {
+                SelectorScheme::TraitNamespaced => None,
+                SelectorScheme::Bare => Some(Psp22Selectors {
+                    transfer: ink::selector_bytes!("transfer"),
+                    transfer_from: ink::selector_bytes!("transfer_from"),
+                    balance_of: ink::selector_bytes!("balance_of"),
+                }),
+                SelectorScheme::Custom(selectors) => Some(*selectors),
+            }
+        }
+    }
+
+    pub struct Psp22Client {
+        contract: AccountId,
+        scheme: SelectorScheme,
+    }
+
+    impl Psp22Client {
+        pub fn new(contract: AccountId, scheme: SelectorScheme) -> Self {
+            Self { contract, scheme }
+        }
+
+        fn failed(&self, stage: CallStage, cause: CallFailure) -> SafeguardError {
+            SafeguardError::TokenCallFailed { token: self.contract, stage, cause }
+        }
+
+        /// Desembrulha as camadas de erro do ambiente e do dispatcher
+        fn unwrap_call<T>(&self, stage: CallStage, result: ink::env::Result<ink::MessageResult<T>>) -> Result<T, SafeguardError> {
+            match result {
+                Ok(Ok(value)) => Ok(value),
+                Ok(Err(_)) => Err(self.failed(stage, CallFailure::Dispatch)),
+                Err(error) => Err(self.failed(stage, error.into())),
+            }
+        }
+
+        /// Mesmo que `unwrap_call`, incluindo o erro PSP22 retornado pelo token
+        fn unwrap_psp22_call(
+            &self,
+            stage: CallStage,
+            result: ink::env::Result<ink::MessageResult<Result<(), PSP22Error>>>,
+        ) -> Result<(), SafeguardError> {
+            match self.unwrap_call(stage, result)? {
+                Ok(()) => Ok(()),
+                Err(PSP22Error::InsufficientAllowance) => {
+                    Err(self.failed(CallStage::Allowance, CallFailure::Psp22(PSP22Error::InsufficientAllowance)))
+                }
+                Err(error) => Err(self.failed(stage, CallFailure::Psp22(error))),
+            }
+        }
+
+        pub fn transfer(&self, to: AccountId, value: Balance) -> Result<(), SafeguardError> {
+            let result = self.invoke_transfer(to, value);
+            self.unwrap_psp22_call(CallStage::Transfer, result)
+        }
+
+        pub fn transfer_from(&self, from: AccountId, to: AccountId, value: Balance) -> Result<(), SafeguardError> {
+            let result = self.invoke_transfer_from(from, to, value);
+            self.unwrap_psp22_call(CallStage::Transfer, result)
+        }
+
+        pub fn balance_of(&self, owner: AccountId) -> Result<Balance, SafeguardError> {
+            let result = self.invoke_balance_of(owner);
+            self.unwrap_call(CallStage::Balance, result)
+        }
+
+        fn invoke_transfer(&self, to: AccountId, value: Balance) -> ink::env::Result<ink::MessageResult<Result<(), PSP22Error>>> {
+            match self.scheme.selectors() {
+                None => {
+                    let mut token: Psp22Ref = self.contract.into();
+                    cross_call::invoke(&token.call_mut().transfer(to, value, Vec::new()).gas_limit(0).params())
+                }
+                Some(selectors) => cross_call::invoke(
+                    &build_call::<DefaultEnvironment>()
+                        .call(self.contract)
+                        .gas_limit(0)
+                        .transferred_value(0)
+                        .exec_input(
+                            ExecutionInput::new(Selector::new(selectors.transfer))
+                                .push_arg(to)
+                                .push_arg(value)
+                                .push_arg(Vec::<u8>::new())
+                        )
+                        .returns::<Result<(), PSP22Error>>()
+                        .params(),
+                ),
+            }
+        }
+
+        fn invoke_transfer_from(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> ink::env::Result<ink::MessageResult<Result<(), PSP22Error>>> {
+            match self.scheme.selectors() {
+                None => {
+                    let mut token: Psp22Ref = self.contract.into();
+                    cross_call::invoke(&token.call_mut().transfer_from(from, to, value, Vec::new()).gas_limit(0).params())
+                }
+                Some(selectors) => cross_call::invoke(
+                    &build_call::<DefaultEnvironment>()
+                        .call(self.contract)
+                        .gas_limit(0)
+                        .transferred_value(0)
+                        .exec_input(
+                            ExecutionInput::new(Selector::new(selectors.transfer_from))
+                                .push_arg(from)
+                                .push_arg(to)
+                                .push_arg(value)
+                                .push_arg(Vec::<u8>::new())
+                        )
+                        .returns::<Result<(), PSP22Error>>()
+                        .params(),
+                ),
+            }
+        }
+
+        fn invoke_balance_of(&self, owner: AccountId) -> ink::env::Result<ink::MessageResult<Balance>> {
+            match self.scheme.selectors() {
+                None => {
+                    let token: Psp22Ref = self.contract.into();
+                    cross_call::invoke(&token.call().balance_of(owner).gas_limit(0).params())
+                }
+                Some(selectors) => cross_call::invoke(
+                    &build_call::<DefaultEnvironment>()
+                        .call(self.contract)
+                        .gas_limit(0)
+                        .transferred_value(0)
+                        .exec_input(ExecutionInput::new(Selector::new(selectors.balance_of)).push_arg(owner))
+                        .returns::<Balance>()
+                        .params(),
+                ),
+            }
+        }
+
+        /// Historical balance; project tokens must implement `PSP22Votes`
+        pub fn past_balance(&self, account: AccountId, block: BlockNumber) -> Result<Balance, SafeguardError> {
+            let token: Psp22VotesRef = self.contract.into();
+            self.unwrap_call(CallStage::Balance, cross_call::invoke(&token.call().get_past_balance(account, block).gas_limit(0).params()))
+        }
+
+        pub fn past_total_supply(&self, block: BlockNumber) -> Result<Balance, SafeguardError> {
+            let token: Psp22VotesRef = self.contract.into();
+            self.unwrap_call(CallStage::Balance, cross_call::invoke(&token.call().get_past_total_supply(block).gas_limit(0).params()))
+        }
+    }
+}
+
+/// Cliente PSP34 mínimo para a custódia de NFTs dados em garantia
+#[cfg(feature = "nft-collateral")]
+pub mod psp34_client {
+    use super::AccountId;
+    use super::cross_call;
+    use super::security::{CallFailure, CallStage, SafeguardError};
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
+    use ink::prelude::{string::String, vec::Vec};
+
+    /// `Id` do padrão PSP34; as garantias usam ids numéricos (`U64`)
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Id {
+        U8(u8),
+        U16(u16),
+        U32(u32),
+        U64(u64),
+        U128(u128),
+        Bytes(Vec<u8>),
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PSP34Error {
+        Custom(String),
+        SelfApprove,
+        NotApproved,
+        TokenExists,
+        TokenNotExists,
+        SafeTransferCheckFailed(String),
+    }
+
+    pub struct Psp34Client {
+        contract: AccountId,
+    }
+
+    impl Psp34Client {
+        pub fn new(contract: AccountId) -> Self {
+            Self { contract }
+        }
+
+        fn failed(&self, cause: CallFailure) -> SafeguardError {
+            SafeguardError::TokenCallFailed { token: self.contract, stage: CallStage::Transfer, cause }
+        }
+
+        fn unwrap_call(&self, result: ink::env::Result<ink::MessageResult<Result<(), PSP34Error>>>) -> Result<(), SafeguardError> {
+            match result {
+                Ok(Ok(Ok(()))) => Ok(()),
+                // Os erros PSP34 não têm variante própria em CallFailure
+                Ok(Ok(Err(_))) => Err(self.failed(CallFailure::CalleeReverted)),
+                Ok(Err(_)) => Err(self.failed(CallFailure::Dispatch)),
+                Err(error) => Err(self.failed(error.into())),
+            }
+        }
+
+        /// Move um NFT com o core como dono ou operador aprovado: no PSP34 o operador usa o
+        /// próprio `transfer`, sem informar o dono, então cabe ao chamador conferir `owner_of`
+        pub fn transfer(&self, to: AccountId, id: u64) -> Result<(), SafeguardError> {
+            let result = cross_call::invoke(
+                &build_call::<DefaultEnvironment>()
+                    .call(self.contract)
+                    .gas_limit(0)
+                    .transferred_value(0)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP34::transfer")))
+                            .push_arg(to)
+                            .push_arg(Id::U64(id))
+                            .push_arg(Vec::<u8>::new())
+                    )
+                    .returns::<Result<(), PSP34Error>>()
+                    .params(),
+            );
+            self.unwrap_call(result)
+        }
+
+        pub fn owner_of(&self, id: u64) -> Result<Option<AccountId>, SafeguardError> {
+            let result = cross_call::invoke(
+                &build_call::<DefaultEnvironment>()
+                    .call(self.contract)
+                    .gas_limit(0)
+                    .transferred_value(0)
+                    .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP34::owner_of"))).push_arg(Id::U64(id)))
+                    .returns::<Option<AccountId>>()
+                    .params(),
+            );
+            match result {
+                Ok(Ok(owner)) => Ok(owner),
+                Ok(Err(_)) => Err(SafeguardError::TokenCallFailed { token: self.contract, stage: CallStage::Balance, cause: CallFailure::Dispatch }),
+                Err(error) => Err(SafeguardError::TokenCallFailed { token: self.contract, stage: CallStage::Balance, cause: error.into() }),
+            }
+        }
+    }
+}
+
 #[ink::contract]
 pub mod safeguard {
-    use super::security::{SafeguardError, ReentrancyGuard, safe_math, validation};
-    use ink::storage::Mapping;
+    use super::security::{CallFailure, CallStage, SafeguardError, ReentrancyGuard, safe_math, validation};
+    use super::cross_call;
+    use super::psp22_client::{Psp22Client, SelectorScheme};
+    #[cfg(feature = "nft-collateral")]
+    use super::psp34_client::Psp34Client;
+    use ink::storage::{Lazy, Mapping};
+    use ink::storage::traits::StorageKey;
 
     // Events
     #[ink(event)]
@@ -153,6 +983,74 @@ pub mod safeguard {
         pub new_owner: AccountId,
     }
 
+    // Council events
+    #[ink(event)]
+    pub struct CouncilProposalCreated {
+        #[ink(topic)]
+        pub proposal_id: ProposalId,
+        #[ink(topic)]
+        pub proposer: AccountId,
+        pub action: CouncilAction,
+    }
+
+    #[ink(event)]
+    pub struct CouncilProposalApproved {
+        #[ink(topic)]
+        pub proposal_id: ProposalId,
+        #[ink(topic)]
+        pub signer: AccountId,
+        pub approvals: u32,
+    }
+
+    #[ink(event)]
+    pub struct CouncilProposalExecuted {
+        #[ink(topic)]
+        pub proposal_id: ProposalId,
+        pub executed_by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        pub role: Role,
+        #[ink(topic)]
+        pub account: AccountId,
+        pub sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        pub role: Role,
+        #[ink(topic)]
+        pub account: AccountId,
+        pub sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct CouncilUpdated {
+        pub signers: Vec<AccountId>,
+        pub threshold: u32,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferProposed {
+        #[ink(topic)]
+        pub project_id: Option<ProjectId>, // None para o contrato
+        #[ink(topic)]
+        pub new_owner: AccountId,
+        pub proposed_by: AccountId,
+        pub expires_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferCancelled {
+        #[ink(topic)]
+        pub project_id: Option<ProjectId>, // None para o contrato
+        pub new_owner: AccountId,
+        pub cancelled_by: AccountId,
+    }
+
     #[ink(event)]
     pub struct ProjectRegistered {
         #[ink(topic)]
@@ -235,6 +1133,13 @@ pub mod safeguard {
         pub decimals: u8,
     }
 
+    #[ink(event)]
+    pub struct TokenSelectorSchemeUpdated {
+        #[ink(topic)]
+        pub token_id: TokenId,
+        pub scheme: SelectorScheme,
+    }
+
     #[ink(event)]
     pub struct GuaranteeDeposited {
         #[ink(topic)]
@@ -342,60 +1247,220 @@ pub mod safeguard {
         pub creation_timestamp: u64,
     }
 
+    /// Projeto da API v1 convertido em ProjectVault; traz a última apuração legada
     #[ink(event)]
-    pub struct DepositFeeCharged {
+    pub struct LegacyProjectMigrated {
         #[ink(topic)]
         pub project_id: ProjectId,
         #[ink(topic)]
-        pub depositor: AccountId,
-        pub lunes_fee: Balance,
-        pub lusdt_fee: Balance,
+        pub owner: AccountId,
+        pub legacy_yes_votes: u64,
+        pub legacy_no_votes: u64,
     }
 
     #[ink(event)]
-    pub struct VoteOpened {
+    pub struct VaultContractDeployed {
         #[ink(topic)]
         pub project_id: ProjectId,
         #[ink(topic)]
-        pub voting_id: VotingId,
-        pub vote_type: VoteType,
-        pub start_timestamp: u64,
-        pub end_timestamp: u64,
-        pub snapshot_block: u64,
+        pub vault: AccountId,
     }
 
     #[ink(event)]
-    pub struct VoteClosed {
+    pub struct VaultContractRetired {
         #[ink(topic)]
         pub project_id: ProjectId,
         #[ink(topic)]
-        pub voting_id: VotingId,
-        pub yes_votes: u64,
-        pub no_votes: u64,
-        pub total_eligible: u64,
-        pub result: VoteResult,
+        pub vault: AccountId,
     }
 
     #[ink(event)]
-    pub struct PlanProposed {
-        #[ink(topic)]
-        pub project_id: ProjectId,
-        #[ink(topic)]
-        pub proposer: AccountId,
-        pub proposal_uri: Vec<u8>,
-        pub correction_window_end: u64,
+    pub struct VaultCodeHashUpdated {
+        pub code_hash: Option<Hash>,
     }
 
     #[ink(event)]
-    pub struct ClaimsOpened {
-        #[ink(topic)]
-        pub project_id: ProjectId,
-        pub liquidation_timestamp: u64,
-        pub claim_deadline: u64,
+    pub struct ScoreEngineUpdated {
+        pub engine: Option<AccountId>,
     }
 
+    /// O motor de score falhou; o score em cache do projeto foi mantido
     #[ink(event)]
-    pub struct ClaimPaid {
+    pub struct ScoreEngineCallFailed {
+        #[ink(topic)]
+        pub project_id: ProjectId,
+        pub engine: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct DepositFeeCharged {
+        #[ink(topic)]
+        pub project_id: ProjectId,
+        #[ink(topic)]
+        pub depositor: AccountId,
+        pub lunes_fee: Balance,
+        pub lusdt_fee: Balance,
+    }
+
+    #[ink(event)]
+    pub struct VoteOpened {
+        #[ink(topic)]
+        pub project_id: ProjectId,
+        #[ink(topic)]
+        pub voting_id: VotingId,
+        pub vote_type: VoteType,
+        pub start_timestamp: u64,
+        pub end_timestamp: u64,
+        pub snapshot_block: u64,
+    }
+
+    #[ink(event)]
+    pub struct VoteClosed {
+        #[ink(topic)]
+        pub project_id: ProjectId,
+        #[ink(topic)]
+        pub voting_id: VotingId,
+        pub yes_votes: u64,
+        pub no_votes: u64,
+        pub total_eligible_weight: Balance,
+        pub result: VoteResult,
+    }
+
+    #[ink(event)]
+    pub struct BallotCast {
+        #[ink(topic)]
+        pub project_id: ProjectId,
+        #[ink(topic)]
+        pub voting_id: VotingId,
+        #[ink(topic)]
+        pub voter: AccountId,
+        pub support: bool,
+        pub raw_weight: Balance,
+        pub effective_weight: Balance,
+    }
+
+    #[ink(event)]
+    pub struct VoteFeeCharged {
+        #[ink(topic)]
+        pub voting_id: VotingId,
+        #[ink(topic)]
+        pub voter: AccountId,
+        pub lunes_fee: Balance,
+        pub lusdt_fee: Balance,
+        pub lunes_reward: Balance,
+        pub lusdt_reward: Balance,
+    }
+
+    #[ink(event)]
+    pub struct VoterRewardClaimed {
+        #[ink(topic)]
+        pub voting_id: VotingId,
+        #[ink(topic)]
+        pub voter: AccountId,
+        pub lunes_amount: Balance,
+        pub lusdt_amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct VoteWeightingUpdated {
+        #[ink(topic)]
+        pub project_id: Option<ProjectId>,
+        pub vote_type: VoteType,
+        pub weighting: VoteWeighting,
+    }
+
+    // Upgrade events
+    #[ink(event)]
+    pub struct CodeUpgraded {
+        #[ink(topic)]
+        pub code_hash: Hash,
+        pub storage_version: u32, // Versão do storage no momento do upgrade
+    }
+
+    #[ink(event)]
+    pub struct StorageMigrated {
+        pub from_version: u32,
+        pub to_version: u32,
+    }
+
+    // State import events
+    #[ink(event)]
+    pub struct ImportStarted {
+        #[ink(topic)]
+        pub started_by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ImportPageApplied {
+        pub kind: ExportKind,
+        pub records: u32,
+    }
+
+    #[ink(event)]
+    pub struct ImportFinalized {
+        #[ink(topic)]
+        pub finalized_by: AccountId,
+        pub timestamp: u64,
+    }
+
+    // Protocol governance (DAO) events
+    #[ink(event)]
+    pub struct ProtocolGovernanceEnabled {
+        #[ink(topic)]
+        pub enabled_by: AccountId,
+        pub timestamp: u64,
+    }
+
+    #[ink(event)]
+    pub struct ProtocolProposalCreated {
+        #[ink(topic)]
+        pub proposal_id: ProposalId,
+        #[ink(topic)]
+        pub proposer: AccountId,
+        pub action: ProtocolAction,
+        pub snapshot_block: u64,
+        pub end_timestamp: u64,
+    }
+
+    #[ink(event)]
+    pub struct ProtocolVoteCast {
+        #[ink(topic)]
+        pub proposal_id: ProposalId,
+        #[ink(topic)]
+        pub voter: AccountId,
+        pub support: bool,
+        pub weight: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ProtocolProposalFinalized {
+        #[ink(topic)]
+        pub proposal_id: ProposalId,
+        pub status: ProtocolProposalStatus,
+        pub yes_weight: Balance,
+        pub no_weight: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PlanProposed {
+        #[ink(topic)]
+        pub project_id: ProjectId,
+        #[ink(topic)]
+        pub proposer: AccountId,
+        pub proposal_uri: Vec<u8>,
+        pub correction_window_end: u64,
+    }
+
+    #[ink(event)]
+    pub struct ClaimsOpened {
+        #[ink(topic)]
+        pub project_id: ProjectId,
+        pub liquidation_timestamp: u64,
+        pub claim_deadline: u64,
+    }
+
+    #[ink(event)]
+    pub struct ClaimPaid {
         #[ink(topic)]
         pub project_id: ProjectId,
         #[ink(topic)]
@@ -419,6 +1484,7 @@ pub mod safeguard {
     pub struct ContractPausedEvent {
         #[ink(topic)]
         pub paused_by: AccountId,
+        pub reason: u16,
         pub timestamp: u64,
     }
 
@@ -429,11 +1495,97 @@ pub mod safeguard {
         pub timestamp: u64,
     }
 
+    #[ink(event)]
+    pub struct ModulePausedEvent {
+        #[ink(topic)]
+        pub module: PauseModule,
+        #[ink(topic)]
+        pub paused_by: AccountId,
+        pub reason: u16,
+        pub timestamp: u64,
+    }
+
+    #[ink(event)]
+    pub struct ModuleUnpausedEvent {
+        #[ink(topic)]
+        pub module: PauseModule,
+        #[ink(topic)]
+        pub unpaused_by: AccountId,
+        pub timestamp: u64,
+    }
+
+    #[ink(event)]
+    pub struct MaxPauseDurationUpdated {
+        pub max_duration: Option<u64>,
+    }
+
+    // Rate limit events
+    #[ink(event)]
+    pub struct OutflowLimitUpdated {
+        #[ink(topic)]
+        pub token_id: TokenId,
+        pub limit: Option<OutflowLimit>,
+    }
+
+    #[ink(event)]
+    pub struct OutflowQueued {
+        #[ink(topic)]
+        pub queue_id: u64,
+        #[ink(topic)]
+        pub token_id: TokenId,
+        #[ink(topic)]
+        pub recipient: AccountId,
+        pub amount: Balance,
+        pub kind: OutflowKind,
+    }
+
+    #[ink(event)]
+    pub struct OutflowReleased {
+        #[ink(topic)]
+        pub queue_id: u64,
+        #[ink(topic)]
+        pub released_by: AccountId,
+        pub amount: Balance,
+    }
+
+    // Escape hatch events
+    #[ink(event)]
+    pub struct EscapeHatchActivated {
+        #[ink(topic)]
+        pub activated_by: AccountId,
+        pub trigger: EscapeHatchTrigger,
+        pub timestamp: u64,
+    }
+
+    #[ink(event)]
+    pub struct EscapeHatchWithdrawal {
+        #[ink(topic)]
+        pub project_id: ProjectId,
+        #[ink(topic)]
+        pub token_id: TokenId,
+        #[ink(topic)]
+        pub account: AccountId,
+        pub guarantee: Balance,
+        pub donation_share: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ComplianceHookUpdated {
+        #[ink(topic)]
+        pub project_id: Option<ProjectId>, // None = hook do protocolo
+        pub hook: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct ComplianceFailurePolicyUpdated {
+        pub policy: ComplianceFailurePolicy,
+    }
+
     // Timelock events
     #[ink(event)]
     pub struct TimelockOperationScheduled {
         #[ink(topic)]
-        pub operation_id: u64,
+        pub operation_id: OperationId,
         #[ink(topic)]
         pub target_function: u8,
         pub scheduled_by: AccountId,
@@ -444,7 +1596,7 @@ pub mod safeguard {
     #[ink(event)]
     pub struct TimelockOperationExecuted {
         #[ink(topic)]
-        pub operation_id: u64,
+        pub operation_id: OperationId,
         pub executed_by: AccountId,
         pub timestamp: u64,
     }
@@ -452,10 +1604,40 @@ pub mod safeguard {
     #[ink(event)]
     pub struct TimelockOperationCancelled {
         #[ink(topic)]
-        pub operation_id: u64,
+        pub operation_id: OperationId,
         pub cancelled_by: AccountId,
     }
 
+    #[ink(event)]
+    pub struct TimelockOperationVetoed {
+        #[ink(topic)]
+        pub operation_id: OperationId,
+        #[ink(topic)]
+        pub guardian: AccountId,
+        pub reason: Vec<u8>,
+    }
+
+    #[ink(event)]
+    pub struct GuardianUpdated {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub enabled: bool,
+    }
+
+    #[ink(event)]
+    pub struct TimelockMinDelayUpdated {
+        #[ink(topic)]
+        pub target_function: u8,
+        pub min_delay: u64,
+    }
+
+    #[ink(event)]
+    pub struct BootstrapAdminChanged {
+        #[ink(topic)]
+        pub previous_admin: AccountId,
+        pub new_admin: Option<AccountId>, // None encerra a fase de bootstrap
+    }
+
     // Cross-contract transfer events
     #[ink(event)]
     pub struct TokenTransferExecuted {
@@ -469,6 +1651,10 @@ pub mod safeguard {
     }
 
     use ink::prelude::vec::Vec;
+    use compliance::{ComplianceAction, ComplianceDecision, ComplianceHook};
+    use project_vault::project_vault::{ProjectVaultRef, VaultError};
+    use score_engine::{ScoreEngine, ScoreInputs, ScoreParams};
+    use ink::codegen::TraitCallBuilder;
 
     pub type ProjectId = u64;
     pub type TokenId = u64;
@@ -476,78 +1662,430 @@ pub mod safeguard {
     pub type NFTTokenId = u64;
     pub type VotingId = u64;
     pub type ProposalId = u64;
-    pub type OperationId = u64;
+    pub type OperationId = Hash; // blake2x256 of (function_type, data, salt)
+    pub type RewardTokenIds = (Option<TokenId>, Option<TokenId>); // (LUNES, LUSDT)
 
-    /// Timelock operation types for critical functions
-    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    /// Papéis operacionais com poderes limitados (o conselho mantém todos)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
-    pub enum TimelockFunction {
-        TransferContractOwnership = 0,
-        SetTreasuryAddress = 1,
-        SetDepositFees = 2,
-        SetScoreParameters = 3,
-        EmergencyWithdraw = 4,
-        AddSupportedToken = 5,
-        AddNftCollection = 6,
+    pub enum Role {
+        Admin,       // Configuração, timelock e concessão de papéis
+        Pauser,      // Pausa global e de projetos
+        TokenLister, // Agenda listagem de tokens e coleções NFT
+        ScoreOracle, // Atualiza o supply de LUNES
+        Keeper,      // Executa operações do timelock já liberadas
     }
 
-    /// Timelock operation data
-    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    /// Transferência de titularidade aguardando aceite do novo owner
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
-    pub struct TimelockOperation {
-        pub function_type: TimelockFunction,
-        pub scheduled_by: AccountId,
-        pub execute_after: u64,      // Timestamp when operation can be executed
-        pub expires_at: u64,         // Timestamp when operation expires
-        pub is_executed: bool,
-        pub is_cancelled: bool,
-        pub data: Vec<u8>,           // Encoded function parameters
+    pub struct PendingOwnership {
+        pub new_owner: AccountId,
+        pub proposed_by: AccountId,
+        pub expires_at: u64,
+        pub replaces: Option<AccountId>, // Signatário substituído no aceite; None adiciona um novo
     }
 
-    /// Token information for multi-asset support
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    /// Subsistemas com circuit breaker próprio
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
-    pub struct TokenInfo {
-        pub contract_address: AccountId,
-        pub symbol: [u8; 8], // Token symbol (e.g., "LUNES", "USDT")
-        pub decimals: u8,
-        pub is_active: bool,
-        pub min_guarantee_amount: Balance,
-        pub price_feed_id: Option<u32>, // For oracle integration
+    pub enum PauseModule {
+        Deposits,    // Depósitos e doações de garantia
+        Withdrawals, // Saques e liberação de garantias
+        Voting,      // Votações anuais e legado
+        Claims,      // Claims de liquidação e recompensas
+        Nft,         // Depósito e saque de NFTs
     }
 
-    /// NFT Collection information for PSP34 collateral support
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    /// Registro de uma pausa ativa de módulo
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
-    pub struct NFTCollectionInfo {
-        pub contract_address: AccountId,
-        pub name: [u8; 32], // Collection name
-        pub symbol: [u8; 8], // Collection symbol
-        pub is_active: bool,
-        pub base_value: Balance, // Base valuation for NFTs in this collection
-        pub valuation_method: NFTValuationMethod,
+    pub struct PauseInfo {
+        pub paused_by: AccountId,
+        pub reason: u16,
+        pub paused_at: u64,
     }
 
-    /// Methods for valuing NFTs as collateral
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    /// Limite de saída de um token dentro de uma janela móvel
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
-    #[allow(clippy::cast_possible_truncation)]
-    pub enum NFTValuationMethod {
-        FixedValue(Balance),
-        TraitBased,
-        OraclePrice,
+    pub struct OutflowLimit {
+        pub max_amount: Balance,
+        pub window: u64, // Em milliseconds
     }
 
-    /// Tipos de votação no SafeGard
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    /// Saída acumulada de um token; decai linearmente ao longo da janela
+    #[derive(Debug, Clone, Default, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
-    pub enum VoteType {
+    pub struct OutflowUsage {
+        pub used: Balance,
+        pub updated_at: u64,
+    }
+
+    /// Origem de uma saída de tokens sujeita ao limite
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum OutflowKind {
+        Withdrawal(ProjectId),
+        Claim(ProjectId),
+        VoterReward(VotingId),
+        Emergency,
+    }
+
+    /// Saída que excedeu o limite e aguarda capacidade ou liberação dos guardiões
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct QueuedOutflow {
+        pub kind: OutflowKind,
+        pub token_id: TokenId,
+        pub recipient: AccountId,
+        pub amount: Balance,
+        pub queued_at: u64,
+    }
+
+    /// Origem da ativação do escape hatch
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum EscapeHatchTrigger {
+        Timelock,      // Operação ActivateEscapeHatch executada
+        PauseDeadline, // Pausa global além de escape_hatch_delay
+    }
+
+    /// Base do rateio de doações de um (projeto, token), fixada no primeiro saque do escape hatch
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct EscapePool {
+        pub deposits: Balance,  // Soma das garantias dos depositantes
+        pub donations: Balance, // Doações rateadas entre eles
+    }
+
+    /// Resultado aplicado quando um hook de compliance configurado não responde
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum ComplianceFailurePolicy {
+        Allow, // fail-open
+        #[default]
+        Deny,  // fail-closed
+    }
+
+    type ComplianceHookRef = ink::contract_ref!(ComplianceHook, ink::env::DefaultEnvironment);
+    type ScoreEngineRef = ink::contract_ref!(ScoreEngine, ink::env::DefaultEnvironment);
+
+    /// Conjuntos de estado exportáveis para um novo deployment
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum ExportKind {
+        Vaults,
+        Guarantees,
+        Nfts,
+        Votings,
+        Ballots,
+        Claims,
+    }
+
+    /// Chave de um registro sem id sequencial, indexada na primeira escrita
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum ExportKey {
+        Guarantee(ProjectId, TokenId, AccountId),
+        Nft(ProjectId, NFTCollectionId, AccountId, NFTTokenId),
+        Ballot(VotingId, AccountId),
+        Claim(ProjectId, AccountId),
+    }
+
+    /// Total de cofre importado que os registros por depositante precisam fechar
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum ImportGap {
+        Guarantee(ProjectId, TokenId),
+        Nft(ProjectId, NFTCollectionId),
+    }
+
+    /// Registro de `ExportKind::Vaults`
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct VaultExport {
+        pub vault: ProjectVault,
+        pub score: u8,
+        pub liquidation_voting: Option<VotingId>,
+        pub pre_voting_status: Option<ProjectStatus>, // Só com votação aberta
+        pub token_checkpoints: bool,
+        pub token_totals: Vec<(TokenId, Balance, Balance)>, // (token, total, parcela doada)
+        pub nft_totals: Vec<(NFTCollectionId, Balance)>,
+    }
+
+    /// Registro de `ExportKind::Guarantees`
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct GuaranteeExport {
+        pub project_id: ProjectId,
+        pub token_id: TokenId,
+        pub account: AccountId,
+        pub amount: Balance,
+    }
+
+    /// Registro de `ExportKind::Nfts`
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct NftExport {
+        pub project_id: ProjectId,
+        pub collection_id: NFTCollectionId,
+        pub account: AccountId,
+        pub nft_token_id: NFTTokenId,
+        pub value: Balance,
+    }
+
+    /// Registro de `ExportKind::Votings`
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct VotingExport {
+        pub voting_id: VotingId,
+        pub info: VotingInfo,
+        pub reward_pools: Vec<(TokenId, Balance)>,
+        pub reward_tokens: RewardTokenIds,
+    }
+
+    /// Registro de `ExportKind::Ballots`
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct BallotExport {
+        pub voting_id: VotingId,
+        pub voter: AccountId,
+        pub ballot: Ballot,
+        pub reward_claimed: bool,
+    }
+
+    /// Registro de `ExportKind::Claims`
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct ClaimExport {
+        pub project_id: ProjectId,
+        pub account: AccountId,
+        pub claim: ClaimInfo,
+    }
+
+    /// Modo de importação de um deployment novo; `Locked` é definitivo
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum ImportState {
+        Active,
+        Locked,
+    }
+
+    /// Tamanho máximo de uma página de exportação
+    pub const MAX_EXPORT_PAGE: u32 = 100;
+
+    /// Versão do layout de storage esperada por este código (0 = layout original, sem
+    /// `storage_version`). Incrementar junto com um passo em `_migrate_from`. O struct raiz
+    /// empacotado mantém todos os campos do layout original, na mesma ordem e com os mesmos
+    /// tipos (os que saíram de uso ficam como placeholders), e não ganha campos novos: eles
+    /// vão para `Mapping`/`Lazy`, que têm chave própria, e assim o código atualizado continua
+    /// decodificando o struct raiz. Mudar o tipo de um valor já gravado em `Mapping`/`Lazy`
+    /// exige reescrevê-lo num passo de `_migrate_from`.
+    pub const STORAGE_VERSION: u32 = 1;
+
+    /// Maximum number of council signers
+    pub const MAX_COUNCIL_SIGNERS: usize = 20;
+
+    /// Casas decimais da unidade comum de peso de voto; saldos de tokens com outras
+    /// casas são reescalados antes de somar (NFTs já são avaliados nesta unidade)
+    pub const VOTE_WEIGHT_DECIMALS: u8 = 18;
+
+    /// Conselho M-de-N que detém os privilégios administrativos
+    #[derive(Debug, Default, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct CouncilConfig {
+        pub signers: Vec<AccountId>,
+        pub threshold: u32,
+    }
+
+    /// Ações privilegiadas executadas pelo conselho após atingir o limiar
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum CouncilAction {
+        // Composição do conselho
+        AddSigner(AccountId),
+        RemoveSigner(AccountId),
+        SetThreshold(u32),
+        // Emergência
+        Pause(u16),
+        Unpause,
+        PauseModule { module: PauseModule, reason: u16 },
+        UnpauseModule(PauseModule),
+        SetMaxPauseDuration(Option<u64>),
+        EmergencyPauseProject { project_id: ProjectId, pause: bool },
+        CloseProject(ProjectId),
+        // Timelock
+        ScheduleOperation { function_type: u8, data: Vec<u8>, salt: Hash },
+        CancelOperation(OperationId),
+        ExecuteOperation(OperationId),
+        SetTimelockDelay(u64),
+        SetMinDelay { function_type: u8, min_delay: u64 },
+        // Configuração
+        SetLunesTokenId(TokenId),
+        SetLusdtTokenId(TokenId),
+        UpdateLunesSupply(Balance),
+        SetVoteFees { lunes_fee: Balance, lusdt_fee: Balance },
+        SetVoterRewardShare(u16),
+        SetDefaultVoteWeighting { vote_type: VoteType, weighting: VoteWeighting },
+        SetProjectVoteWeighting { project_id: ProjectId, vote_type: VoteType, weighting: VoteWeighting },
+        SetProjectTokenCheckpoints { project_id: ProjectId, enabled: bool },
+        EnableProtocolGovernance,
+        // Papéis
+        GrantRole { role: Role, account: AccountId },
+        RevokeRole { role: Role, account: AccountId },
+    }
+
+    /// Proposta do conselho aguardando aprovações
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct CouncilProposal {
+        pub proposer: AccountId,
+        pub action: CouncilAction,
+        pub created_at: u64,
+        pub is_executed: bool,
+    }
+
+    /// Timelock operation types for critical functions.
+    /// The operation `data` is the SCALE encoding of the matching `*Args` type below.
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum TimelockFunction {
+        TransferContractOwnership = 0,
+        SetTreasuryAddress = 1,
+        SetDepositFees = 2,
+        SetScoreParameters = 3,
+        EmergencyWithdraw = 4,
+        AddSupportedToken = 5,
+        AddNftCollection = 6,
+        SetOutflowLimit = 7,
+        ActivateEscapeHatch = 8,
+        SetTokenSelectorScheme = 9,
+        UpgradeCode = 10,
+    }
+
+    /// Lifecycle state of a timelock operation (derived from flags and block time)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum OperationStatus {
+        Pending,   // Waiting for execute_after
+        Ready,     // Executable until expires_at
+        Expired,
+        Executed,
+        Cancelled,
+    }
+
+    /// Maximum page size for operation listings
+    pub const MAX_OPERATIONS_PAGE: u32 = 100;
+
+    /// New contract owner
+    pub type TransferOwnershipArgs = AccountId;
+    /// New treasury address
+    pub type SetTreasuryAddressArgs = AccountId;
+    /// (LUNES fee, LUSDT fee)
+    pub type SetDepositFeesArgs = (Balance, Balance);
+    /// Score v1.1 parameters (`None` keeps the current value)
+    pub type SetScoreParametersArgs = ScoreParameters;
+    /// (token, amount, recipient)
+    pub type EmergencyWithdrawArgs = (TokenId, Balance, AccountId);
+    /// (contract address, symbol, decimals, minimum guarantee)
+    pub type AddSupportedTokenArgs = (AccountId, [u8; 8], u8, Balance);
+    /// (contract address, name, symbol, base value, valuation method)
+    pub type AddNftCollectionArgs = (AccountId, [u8; 32], [u8; 8], Balance, NFTValuationMethod);
+    /// (token, limit; `None` removes it)
+    pub type SetOutflowLimitArgs = (TokenId, Option<OutflowLimit>);
+    /// No arguments (empty payload)
+    pub type ActivateEscapeHatchArgs = ();
+    /// New code hash (must already be uploaded)
+    pub type UpgradeCodeArgs = Hash;
+    /// (token, selector convention)
+    pub type SetTokenSelectorSchemeArgs = (TokenId, SelectorScheme);
+
+    /// Timelock operation with its arguments decoded
+    #[cfg(feature = "timelock")]
+    enum TimelockCall {
+        TransferContractOwnership(AccountId),
+        EmergencyWithdraw { token_id: TokenId, amount: Balance, recipient: AccountId },
+        ActivateEscapeHatch,
+        Protocol(ProtocolAction),
+    }
+
+    /// Timelock operation data
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct TimelockOperation {
+        pub function_type: TimelockFunction,
+        pub scheduled_by: AccountId,
+        pub execute_after: u64,      // Timestamp when operation can be executed
+        pub expires_at: u64,         // Timestamp when operation expires
+        pub is_executed: bool,
+        pub is_cancelled: bool,
+        pub data: Vec<u8>,           // Encoded function parameters
+    }
+
+    /// Token information for multi-asset support
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct TokenInfo {
+        pub contract_address: AccountId,
+        pub symbol: [u8; 8], // Token symbol (e.g., "LUNES", "USDT")
+        pub decimals: u8,
+        pub is_active: bool,
+        pub min_guarantee_amount: Balance,
+        pub price_feed_id: Option<u32>, // For oracle integration
+        pub selector_scheme: SelectorScheme, // Convenção de seletores do contrato PSP22
+    }
+
+    /// NFT Collection information for PSP34 collateral support
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct NFTCollectionInfo {
+        pub contract_address: AccountId,
+        pub name: [u8; 32], // Collection name
+        pub symbol: [u8; 8], // Collection symbol
+        pub is_active: bool,
+        pub base_value: Balance, // Base valuation for NFTs in this collection, in vote weight units (18 decimals)
+        pub valuation_method: NFTValuationMethod,
+    }
+
+    /// Methods for valuing NFTs as collateral
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    #[allow(clippy::cast_possible_truncation)]
+    pub enum NFTValuationMethod {
+        FixedValue(Balance),
+        TraitBased,
+        OraclePrice,
+    }
+
+    /// Tipos de votação no SafeGard
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum VoteType {
         Annual,           // Votação anual de governança
         Extension,        // Extensão de lockup
         Emergency,        // Votação de emergência
         Liquidation,      // Votação para liquidação
     }
 
+    /// Função de ponderação aplicada ao peso bruto de cada voto
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum VoteWeighting {
+        Linear,                              // Peso efetivo = peso bruto
+        Quadratic,                           // Peso efetivo = raiz quadrada do peso bruto
+        CappedLinear { max_share_bps: u16 }, // Linear, limitado a uma fração do total elegível (em bps)
+    }
+
+    /// Registro individual de voto (peso bruto e efetivo para transparência)
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Ballot {
+        pub support: bool,
+        pub raw_weight: Balance,
+        pub effective_weight: Balance,
+        pub timestamp: u64,
+    }
+
     /// Resultado de uma votação
     #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
@@ -582,9 +2120,128 @@ pub mod safeguard {
         pub snapshot_block: u64,
         pub yes_votes: u64,
         pub no_votes: u64,
-        pub total_eligible: u64,
+        pub total_eligible: u64,           // Obsoleto (sempre 0): pesos normalizados não cabem em u64; use total_eligible_weight
         pub result: VoteResult,
         pub proposal_uri: Vec<u8>,
+        pub weighting: VoteWeighting,      // Fixada na abertura da votação
+        pub yes_weight: Balance,           // Soma dos pesos efetivos "Sim"
+        pub no_weight: Balance,            // Soma dos pesos efetivos "Não"
+        pub total_eligible_weight: Balance, // Peso bruto total elegível na abertura
+    }
+
+    /// `TokenInfo` do layout v0, lido apenas pela migração
+    #[derive(scale::Encode, scale::Decode)]
+    struct TokenInfoV0 {
+        contract_address: AccountId,
+        symbol: [u8; 8],
+        decimals: u8,
+        is_active: bool,
+        min_guarantee_amount: Balance,
+        price_feed_id: Option<u32>,
+    }
+
+    /// `VotingInfo` do layout v0, lido apenas pela migração
+    #[derive(scale::Encode, scale::Decode)]
+    struct VotingInfoV0 {
+        vote_type: VoteType,
+        start_timestamp: u64,
+        end_timestamp: u64,
+        snapshot_block: u64,
+        yes_votes: u64,
+        no_votes: u64,
+        total_eligible: u64,
+        result: VoteResult,
+        proposal_uri: Vec<u8>,
+    }
+
+    /// Saldo registrado ao final de um bloco
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Checkpoint {
+        pub block: u64,
+        pub amount: Balance,
+    }
+
+    /// Série histórica de saldos mantida por checkpoints
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum CheckpointKey {
+        UserGuarantee(ProjectId, TokenId, AccountId),
+        ProjectTotal(ProjectId, TokenId),
+        UserTokenBalance(AccountId, TokenId), // Saldo travado do usuário somando todos os projetos
+        TokenTotal(TokenId),                  // Total travado do token por depositantes
+        UserNftValue(ProjectId, AccountId),   // Valor estimado dos NFTs do usuário no projeto
+        ProjectNftValue(ProjectId),           // Valor estimado de todos os NFTs do projeto
+    }
+
+    /// Parâmetros do Score v1.1 (campos `None` permanecem inalterados)
+    #[derive(Debug, Clone, Default, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct ScoreParameters {
+        pub alpha: Option<Balance>,
+        pub gamma: Option<u32>,
+        pub delta: Option<u32>,
+        pub t_min: Option<Balance>,
+        pub theta: Option<u32>,
+        pub s_ref: Option<Balance>,
+        pub floor_f: Option<Balance>,
+        pub kappa: Option<u32>,
+    }
+
+    /// Alterações de parâmetros globais executáveis pela DAO do protocolo
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum ProtocolAction {
+        SetScoreParameters(ScoreParameters),
+        SetDepositFees { lunes_fee: Balance, lusdt_fee: Balance },
+        AddSupportedToken {
+            contract_address: AccountId,
+            symbol: [u8; 8],
+            decimals: u8,
+            min_guarantee_amount: Balance,
+        },
+        AddNftCollection {
+            contract_address: AccountId,
+            name: [u8; 32],
+            symbol: [u8; 8],
+            base_value: Balance,
+            valuation_method: NFTValuationMethod,
+        },
+        SetTreasuryAddress(AccountId),
+        SetGuardian { account: AccountId, enabled: bool },
+        SetOutflowLimit { token_id: TokenId, limit: Option<OutflowLimit> },
+        SetEscapeHatchDelay(u64),
+        SetTokenSelectorScheme { token_id: TokenId, scheme: SelectorScheme },
+        SetProtocolComplianceHook(Option<AccountId>),
+        SetComplianceFailurePolicy(ComplianceFailurePolicy),
+        SetVaultCodeHash(Option<Hash>),
+        SetScoreEngine(Option<AccountId>),
+        UpgradeCode(Hash),
+    }
+
+    /// Estado de uma proposta do protocolo
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum ProtocolProposalStatus {
+        Active,
+        Executed,
+        Defeated,
+    }
+
+    /// Proposta de alteração de parâmetros globais, votada por LUNES travados
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct ProtocolProposal {
+        pub proposer: AccountId,
+        pub action: ProtocolAction,
+        pub description_uri: Vec<u8>,
+        pub snapshot_block: u64,
+        pub start_timestamp: u64,
+        pub end_timestamp: u64,
+        pub yes_weight: Balance,
+        pub no_weight: Balance,
+        pub total_eligible_weight: Balance, // LUNES travados no snapshot
+        pub status: ProtocolProposalStatus,
     }
 
     /// Informações do cofre de um projeto
@@ -658,7 +2315,18 @@ pub mod safeguard {
 
     #[ink(storage)]
     pub struct Safeguard {
-        contract_owner: AccountId,
+        contract_owner: AccountId,           // Layout v0: owner único; `_migrate_from(0)` o leva ao conselho e zera
+        council: Lazy<CouncilConfig>,        // Conselho M-de-N no lugar do owner único
+        council_proposals: Mapping<ProposalId, CouncilProposal>,
+        council_approvals: Mapping<(ProposalId, AccountId), bool>,
+        next_council_proposal_id: Lazy<ProposalId>,
+        council_executing: Lazy<bool>,       // Verdadeiro apenas durante a execução de uma proposta aprovada
+        roles: Mapping<(Role, AccountId), bool>,
+        
+        // Transferências de titularidade em duas etapas
+        pending_contract_owner: Lazy<Option<PendingOwnership>>,
+        pending_project_owners: Mapping<ProjectId, PendingOwnership>,
+        ownership_transfer_window: Lazy<u64>, // Prazo para aceite (7 dias em ms)
         // Store individual project fields instead of nested mappings
         project_owners: Mapping<ProjectId, AccountId>,
         project_vote_end_times: Mapping<ProjectId, u64>,
@@ -681,6 +2349,8 @@ pub mod safeguard {
         supported_tokens: Mapping<TokenId, TokenInfo>,
         token_guarantees: Mapping<(ProjectId, TokenId, AccountId), Balance>,
         project_total_guarantees: Mapping<(ProjectId, TokenId), Balance>,
+        guarantee_checkpoints: Mapping<(CheckpointKey, u32), Checkpoint>,
+        guarantee_checkpoint_counts: Mapping<CheckpointKey, u32>,
         user_token_balances: Mapping<(AccountId, TokenId), Balance>,
         next_token_id: TokenId,
         
@@ -689,6 +2359,7 @@ pub mod safeguard {
         nft_guarantees: Mapping<(ProjectId, NFTCollectionId, AccountId, NFTTokenId), Balance>,
         project_nft_guarantees: Mapping<(ProjectId, NFTCollectionId), Balance>,
         user_nft_deposits: Mapping<(AccountId, NFTCollectionId), u32>, // Count of NFTs deposited
+        nft_custody: Mapping<(NFTCollectionId, NFTTokenId), (ProjectId, AccountId)>, // Onde cada NFT custodiado está registrado
         next_nft_collection_id: NFTCollectionId,
         
         // Score v1.1 parameters
@@ -712,12 +2383,36 @@ pub mod safeguard {
         user_claims: Mapping<(ProjectId, AccountId), ClaimInfo>,
         next_voting_id: VotingId,
         
+        // Ponderação de votos
+        ballots: Mapping<(VotingId, AccountId), Ballot>,
+        default_vote_weightings: Mapping<VoteType, VoteWeighting>,
+        project_vote_weightings: Mapping<(ProjectId, VoteType), VoteWeighting>,
+        project_token_checkpoints: Mapping<ProjectId, bool>, // Token do projeto implementa PSP22Votes
+        project_liquidation_votings: Mapping<ProjectId, VotingId>, // Votação que abriu a liquidação
+        pre_voting_statuses: Mapping<ProjectId, ProjectStatus>, // Status restaurado quando a votação não muda o projeto
+        
         // Treasury e taxas
         treasury_address: AccountId,
         lunes_deposit_fee: Balance,    // 100 LUNES
         lusdt_deposit_fee: Balance,    // 10 LUSDT
         lusdt_token_id: Option<TokenId>,
         
+        // Taxas de votação e recompensas para votantes
+        lunes_vote_fee: Lazy<Balance>,
+        lusdt_vote_fee: Lazy<Balance>,
+        voter_reward_share_bps: Lazy<u16>,   // Parcela das taxas de votação destinada aos votantes
+        voter_reward_pools: Mapping<(VotingId, TokenId), Balance>,
+        voter_rewards_claimed: Mapping<(VotingId, AccountId), bool>,
+        voter_reward_tokens: Mapping<VotingId, RewardTokenIds>, // Fixados na primeira taxa da votação
+        
+        // Governança do protocolo (DAO ponderada por LUNES travados)
+        protocol_governance_enabled: Lazy<bool>, // Quando ativa, setters globais só via proposta
+        protocol_proposals: Mapping<ProposalId, ProtocolProposal>,
+        protocol_ballots: Mapping<(ProposalId, AccountId), Ballot>,
+        next_protocol_proposal_id: Lazy<ProposalId>,
+        protocol_voting_duration: Lazy<u64>, // 7 dias em milliseconds
+        protocol_quorum_bps: Lazy<u16>,      // Participação mínima sobre o total travado
+        
         // Configurações de governança
         annual_voting_duration: u64,   // 7 dias em milliseconds
         correction_window_duration: u64, // 30 dias para correções
@@ -725,13 +2420,57 @@ pub mod safeguard {
         
         // Global pausability
         is_paused: bool,
+        storage_version: Lazy<u32>, // Versão já migrada; menor que STORAGE_VERSION após um upgrade
         paused_at: u64,
+        pause_reason: Lazy<u16>,
+        module_pauses: Mapping<PauseModule, PauseInfo>, // Circuit breakers por subsistema
+        max_pause_duration: Lazy<Option<u64>>, // Após esse prazo qualquer conta pode despausar
+        
+        // Limite de saída por token
+        outflow_limits: Mapping<TokenId, OutflowLimit>,
+        outflow_usage: Mapping<TokenId, OutflowUsage>,
+        queued_outflows: Mapping<u64, QueuedOutflow>,
+        next_queued_outflow_id: Lazy<u64>,
+        
+        // Escape hatch: saque direto pelos depositantes
+        escape_hatch_activated_at: Lazy<Option<u64>>,
+        escape_hatch_delay: Lazy<u64>, // Pausa global mais longa que isso libera a ativação pública
+        project_donations: Mapping<(ProjectId, TokenId), Balance>,
+        escape_pools: Mapping<(ProjectId, TokenId), EscapePool>,
+        
+        // Hooks de compliance opcionais (protocolo e por projeto)
+        protocol_compliance_hook: Lazy<Option<AccountId>>,
+        project_compliance_hooks: Mapping<ProjectId, AccountId>,
+        compliance_failure_policy: Lazy<ComplianceFailurePolicy>,
+        
+        // Exportação e importação de estado entre deployments
+        export_keys: Mapping<(ExportKind, u32), ExportKey>,
+        export_key_positions: Mapping<ExportKey, u32>,
+        export_counts: Mapping<ExportKind, u32>,
+        import_state: Lazy<ImportState>, // Vazio = nunca importou
+        import_expected_balances: Mapping<TokenId, Balance>, // Saldos que o contrato deve deter ao final
+        import_guarantee_gaps: Mapping<(ProjectId, TokenId), Balance>, // Depósitos do cofre ainda sem depositante importado
+        import_nft_gaps: Mapping<(ProjectId, NFTCollectionId), Balance>, // Valor em NFTs ainda sem registro importado
+        import_open_gaps: Lazy<u32>, // Pares (projeto, ativo) com diferença pendente
+        
+        // Factory: cofre segregado (contrato próprio) por projeto
+        vault_code_hash: Lazy<Option<Hash>>, // None = projetos novos ficam custodiados no core
+        project_vault_contracts: Mapping<ProjectId, AccountId>,
+        
+        // Motor de score externo (contrato atualizável)
+        score_engine: Lazy<Option<AccountId>>, // None = fórmula v1 embutida
         
         // Timelock for critical operations
         timelock_operations: Mapping<OperationId, TimelockOperation>,
-        next_operation_id: OperationId,
+        operation_index: Mapping<u32, OperationId>, // Ordem de agendamento, para listagem
+        operation_count: Lazy<u32>,
+        timelock_min_delays: Mapping<TimelockFunction, u64>, // Sobrescreve o mínimo padrão por função
+        next_operation_id: u64,        // Layout v0: ids sequenciais, hoje hashes; só mantém o struct raiz decodificável
         timelock_delay: u64,           // Default: 48 hours in milliseconds
         timelock_expiry: u64,          // Default: 7 days in milliseconds
+        bootstrap_admin: Lazy<Option<AccountId>>, // Bypass do timelock na fase inicial; None após encerrada
+        guardians: Mapping<AccountId, bool>,  // Podem vetar operações enfileiradas
+        guardian_count: Lazy<u32>,
         
         // Score calculation cache for optimization
         project_token_count: Mapping<ProjectId, u32>,  // Number of tokens per project
@@ -750,8 +2489,17 @@ pub mod safeguard {
                 new_owner: caller,
             });
 
-            Self {
-                contract_owner: caller,
+            let mut contract = Self {
+                contract_owner: AccountId::from([0u8; 32]),
+                council: Lazy::default(),
+                council_proposals: Mapping::default(),
+                council_approvals: Mapping::default(),
+                next_council_proposal_id: Lazy::default(),
+                council_executing: Lazy::default(),
+                roles: Mapping::default(),
+                pending_contract_owner: Lazy::default(),
+                pending_project_owners: Mapping::default(),
+                ownership_transfer_window: Lazy::default(),
                 project_owners: Mapping::default(),
                 project_vote_end_times: Mapping::default(),
                 project_vote_yes_counts: Mapping::default(),
@@ -773,6 +2521,8 @@ pub mod safeguard {
                 supported_tokens: Mapping::default(),
                 token_guarantees: Mapping::default(),
                 project_total_guarantees: Mapping::default(),
+                guarantee_checkpoints: Mapping::default(),
+                guarantee_checkpoint_counts: Mapping::default(),
                 user_token_balances: Mapping::default(),
                 next_token_id: 0,
                 
@@ -781,6 +2531,7 @@ pub mod safeguard {
                 nft_guarantees: Mapping::default(),
                 project_nft_guarantees: Mapping::default(),
                 user_nft_deposits: Mapping::default(),
+                nft_custody: Mapping::default(),
                 next_nft_collection_id: 0,
                 
                 // Initialize Score v1.1 parameters with defaults
@@ -804,12 +2555,36 @@ pub mod safeguard {
                 user_claims: Mapping::default(),
                 next_voting_id: 0,
                 
+                // Initialize vote weighting (Linear when unset)
+                ballots: Mapping::default(),
+                default_vote_weightings: Mapping::default(),
+                project_vote_weightings: Mapping::default(),
+                project_token_checkpoints: Mapping::default(),
+                project_liquidation_votings: Mapping::default(),
+                pre_voting_statuses: Mapping::default(),
+                
                 // Treasury configuration
                 treasury_address: caller, // Initially set to contract owner
                 lunes_deposit_fee: 100 * 1_000_000_000_000_000_000,  // 100 LUNES
                 lusdt_deposit_fee: 10 * 1_000_000_000_000_000_000,   // 10 LUSDT
                 lusdt_token_id: None,
                 
+                // Voting fees disabled until configured by governance
+                lunes_vote_fee: Lazy::default(),
+                lusdt_vote_fee: Lazy::default(),
+                voter_reward_share_bps: Lazy::default(),
+                voter_reward_pools: Mapping::default(),
+                voter_rewards_claimed: Mapping::default(),
+                voter_reward_tokens: Mapping::default(),
+                
+                // Protocol governance starts in bootstrap mode (owner setters allowed)
+                protocol_governance_enabled: Lazy::default(),
+                protocol_proposals: Mapping::default(),
+                protocol_ballots: Mapping::default(),
+                next_protocol_proposal_id: Lazy::default(),
+                protocol_voting_duration: Lazy::default(),
+                protocol_quorum_bps: Lazy::default(),
+                
                 // Governance timing configuration
                 annual_voting_duration: 7 * 24 * 60 * 60 * 1000,      // 7 dias em ms
                 correction_window_duration: 30 * 24 * 60 * 60 * 1000, // 30 dias em ms
@@ -817,20 +2592,77 @@ pub mod safeguard {
                 
                 // Initialize pausability
                 is_paused: false,
+                storage_version: Lazy::default(),
                 paused_at: 0,
+                pause_reason: Lazy::default(),
+                module_pauses: Mapping::default(),
+                max_pause_duration: Lazy::default(),
+                
+                // Sem limite de saída até a governança configurar
+                outflow_limits: Mapping::default(),
+                outflow_usage: Mapping::default(),
+                queued_outflows: Mapping::default(),
+                next_queued_outflow_id: Lazy::default(),
+                
+                escape_hatch_activated_at: Lazy::default(),
+                escape_hatch_delay: Lazy::default(),
+                project_donations: Mapping::default(),
+                escape_pools: Mapping::default(),
+                
+                protocol_compliance_hook: Lazy::default(),
+                project_compliance_hooks: Mapping::default(),
+                compliance_failure_policy: Lazy::default(),
+                
+                export_keys: Mapping::default(),
+                export_key_positions: Mapping::default(),
+                export_counts: Mapping::default(),
+                import_state: Lazy::default(),
+                import_expected_balances: Mapping::default(),
+                import_guarantee_gaps: Mapping::default(),
+                import_nft_gaps: Mapping::default(),
+                import_open_gaps: Lazy::default(),
+                
+                vault_code_hash: Lazy::default(),
+                project_vault_contracts: Mapping::default(),
+                
+                score_engine: Lazy::default(),
                 
                 // Initialize timelock
                 timelock_operations: Mapping::default(),
+                operation_index: Mapping::default(),
+                operation_count: Lazy::default(),
+                timelock_min_delays: Mapping::default(),
                 next_operation_id: 0,
                 timelock_delay: 48 * 60 * 60 * 1000,    // 48 hours in ms
                 timelock_expiry: 7 * 24 * 60 * 60 * 1000, // 7 days in ms
+                bootstrap_admin: Lazy::default(),
+                guardians: Mapping::default(),
+                guardian_count: Lazy::default(),
                 
                 // Initialize score cache
                 project_token_count: Mapping::default(),
                 project_nft_count: Mapping::default(),
                 
                 reentrancy_guard: ReentrancyGuard::default(),
-            }
+            };
+            // Campos acrescentados ao layout original ficam fora do struct raiz empacotado
+            contract._seed_governance(caller);
+            contract.storage_version.set(&STORAGE_VERSION);
+            contract
+        }
+
+        /// Conselho, admin de bootstrap e prazos padrão dos campos `Lazy`; usado pelo
+        /// construtor e pela migração do layout v0, que só tinha `contract_owner`
+        fn _seed_governance(&mut self, owner: AccountId) {
+            self.council.set(&CouncilConfig {
+                signers: ink::prelude::vec![owner],
+                threshold: 1,
+            });
+            self.ownership_transfer_window.set(&(7 * 24 * 60 * 60 * 1000)); // 7 dias em ms
+            self.protocol_voting_duration.set(&(7 * 24 * 60 * 60 * 1000));  // 7 dias em ms
+            self.protocol_quorum_bps.set(&1_000);                            // 10% dos LUNES travados
+            self.escape_hatch_delay.set(&(30 * 24 * 60 * 60 * 1000));       // 30 dias em ms
+            self.bootstrap_admin.set(&Some(owner));
         }
 
         /// Registra um novo projeto no SafeGard com cofre segregado
@@ -842,6 +2674,7 @@ pub mod safeguard {
             token_contract: AccountId,
             treasury_address: AccountId,
         ) -> Result<ProjectId, SafeguardError> {
+            self._ensure_not_paused()?;
             let caller = self.env().caller();
             validation::validate_account(caller)?;
 
@@ -850,6 +2683,21 @@ pub mod safeguard {
                 return Err(SafeguardError::InvalidInput);
             }
 
+            // Com a factory ligada o registro instancia e financia o cofre dedicado
+            self.reentrancy_guard.start()?;
+            let result = self._register_project(caller, name, metadata_uri, token_contract, treasury_address);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _register_project(
+            &mut self,
+            caller: AccountId,
+            name: Vec<u8>,
+            metadata_uri: Vec<u8>,
+            token_contract: AccountId,
+            treasury_address: AccountId,
+        ) -> Result<ProjectId, SafeguardError> {
             let project_id = self.next_project_id;
             let current_timestamp = self.env().block_timestamp();
 
@@ -872,9 +2720,6 @@ pub mod safeguard {
                 claims_deadline: None,
             };
 
-            // Armazenar o cofre do projeto
-            self.project_vaults.insert(project_id, &project_vault);
-            
             // Manter compatibilidade com sistema existente
             self.project_owners.insert(project_id, &caller);
             self.project_creation_timestamps.insert(project_id, &current_timestamp);
@@ -892,23 +2737,266 @@ pub mod safeguard {
                 treasury_address,
             });
 
+            self._init_project_vault(project_vault)?;
+
+            Ok(project_id)
+        }
+
+        /// Armazena o cofre de um projeto e, com a factory ligada, instancia o contrato dedicado
+        fn _init_project_vault(&mut self, project_vault: ProjectVault) -> Result<(), SafeguardError> {
+            let project_id = project_vault.project_id;
+            let creation_timestamp = project_vault.creation_timestamp;
+            self.project_vaults.insert(project_id, &project_vault);
+
             self.env().emit_event(VaultCreated {
                 project_id,
-                creation_timestamp: current_timestamp,
+                creation_timestamp,
             });
 
-            Ok(project_id)
+            // Factory: instancia o cofre dedicado quando o code hash está configurado
+            if self.vault_code_hash.get_or_default().is_some() {
+                self._deploy_vault_contract(project_id)?;
+            }
+            Ok(())
         }
 
-        /// Depósito de garantia com taxas obrigatórias (100 LUNES + 10 LUSDT)
-        #[ink(message, payable)]
-        pub fn deposit_guarantee_with_fees(
-            &mut self, 
-            project_id: ProjectId,
-            token_id: TokenId,
-            amount: Balance,
-        ) -> Result<(), SafeguardError> {
-            let caller = self.env().caller();
+        // ==================== FACTORY ====================
+
+        /// Code hash do contrato ProjectVault; None desliga a factory (admin de bootstrap; depois via DAO)
+        #[ink(message)]
+        pub fn set_vault_code_hash(&mut self, code_hash: Option<Hash>) -> Result<(), SafeguardError> {
+            self._ensure_protocol_admin()?;
+            Self::_validate_vault_code_hash(&code_hash)?;
+            self._set_vault_code_hash(code_hash);
+            Ok(())
+        }
+
+        fn _validate_vault_code_hash(code_hash: &Option<Hash>) -> Result<(), SafeguardError> {
+            if *code_hash == Some(Hash::from([0u8; 32])) {
+                return Err(SafeguardError::InvalidInput);
+            }
+            Ok(())
+        }
+
+        fn _set_vault_code_hash(&mut self, code_hash: Option<Hash>) {
+            self.vault_code_hash.set(&code_hash);
+            self.env().emit_event(VaultCodeHashUpdated { code_hash });
+        }
+
+        /// Cria o cofre dedicado de um projeto registrado antes da factory (dono do projeto).
+        /// Falha com `VaultTokenSchemeUnsupported` se o projeto tiver garantias em tokens que o
+        /// cofre não movimenta (ver `_ensure_vault_custody`).
+        #[ink(message)]
+        pub fn create_vault(&mut self, project_id: ProjectId) -> Result<AccountId, SafeguardError> {
+            self._ensure_not_paused()?;
+            let vault = self.project_vaults.get(project_id).ok_or(SafeguardError::ProjectNotFound)?;
+            if vault.owner != self.env().caller() {
+                return Err(SafeguardError::NotOwner);
+            }
+            // Em liquidação os claims já pagos não saem dos totais; a custódia não pode migrar
+            if matches!(
+                vault.status,
+                ProjectStatus::Rejected | ProjectStatus::Liquidation | ProjectStatus::ClaimsOpen | ProjectStatus::Closed
+            ) {
+                return Err(SafeguardError::ProjectNotActive);
+            }
+            self.reentrancy_guard.start()?;
+            let result = self._deploy_vault_contract(project_id);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _deploy_vault_contract(&mut self, project_id: ProjectId) -> Result<AccountId, SafeguardError> {
+            if self.project_vault_contracts.contains(project_id) {
+                return Err(SafeguardError::VaultAlreadyExists);
+            }
+            let code_hash = self.vault_code_hash.get_or_default().ok_or(SafeguardError::InvalidInput)?;
+            self._ensure_project_fits_vault(project_id)?;
+            let deployed = match cross_call::instantiate(
+                &ProjectVaultRef::new(self.env().account_id(), project_id)
+                    .code_hash(code_hash)
+                    .gas_limit(0)
+                    .endowment(0)
+                    .salt_bytes(project_id.to_le_bytes())
+                    .params(),
+            ) {
+                Ok(Ok(vault_ref)) => Some(ink::ToAccountId::to_account_id(&vault_ref)),
+                _ => None,
+            };
+            let vault = deployed.ok_or(SafeguardError::VaultInstantiationFailed)?;
+            self.project_vault_contracts.insert(project_id, &vault);
+            self.env().emit_event(VaultContractDeployed { project_id, vault });
+            self._fund_vault(project_id, vault)?;
+            Ok(vault)
+        }
+
+        /// Move para o cofre recém-criado as garantias que o projeto já tinha no core
+        fn _fund_vault(&mut self, project_id: ProjectId, vault: AccountId) -> Result<(), SafeguardError> {
+            for token_id in 0..self.next_token_id {
+                let held = self.project_total_guarantees.get((project_id, token_id)).unwrap_or(0);
+                if held > 0 {
+                    self._psp22_transfer(token_id, vault, held)?;
+                }
+            }
+            Ok(())
+        }
+
+        /// O cofre só é criado se todas as garantias do projeto no core seguirem a regra de custódia
+        fn _ensure_project_fits_vault(&self, project_id: ProjectId) -> Result<(), SafeguardError> {
+            for token_id in 0..self.next_token_id {
+                let Some(token_info) = self.supported_tokens.get(token_id) else { continue };
+                if self.project_total_guarantees.get((project_id, token_id)).unwrap_or(0) > 0 {
+                    Self::_ensure_vault_custody(&token_info)?;
+                }
+            }
+            Ok(())
+        }
+
+        /// Regra de custódia do cofre dedicado: ele chama os tokens pelo trait PSP22, então só
+        /// custodia tokens `TraitNamespaced`. Um projeto com cofre não recebe nem paga tokens de
+        /// outros esquemas, e o cofre não é criado enquanto o projeto tiver saldo deles no core.
+        fn _ensure_vault_custody(token_info: &TokenInfo) -> Result<(), SafeguardError> {
+            if token_info.selector_scheme != SelectorScheme::TraitNamespaced {
+                return Err(SafeguardError::VaultTokenSchemeUnsupported);
+            }
+            Ok(())
+        }
+
+        /// Devolve ao core os saldos do cofre dedicado e o desvincula do projeto (dono do projeto ou Admin).
+        /// Necessário antes de exportar o projeto para um novo deployment.
+        #[ink(message)]
+        pub fn retire_vault(&mut self, project_id: ProjectId) -> Result<(), SafeguardError> {
+            let vault = self.project_vaults.get(project_id).ok_or(SafeguardError::ProjectNotFound)?;
+            if vault.owner != self.env().caller() {
+                self._ensure_role(Role::Admin)?;
+            }
+            let vault_contract = self.project_vault_contracts.get(project_id).ok_or(SafeguardError::InvalidInput)?;
+            self.reentrancy_guard.start()?;
+            let result = self._retire_vault(project_id, vault_contract);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _retire_vault(&mut self, project_id: ProjectId, vault: AccountId) -> Result<(), SafeguardError> {
+            // Desvincula primeiro: a partir daqui os pagamentos do projeto saem do core
+            self.project_vault_contracts.remove(project_id);
+            let core = self.env().account_id();
+            for token_id in 0..self.next_token_id {
+                let Some(token_info) = self.supported_tokens.get(token_id) else { continue };
+                // Pela regra de custódia o cofre só tem tokens `TraitNamespaced`
+                if Self::_ensure_vault_custody(&token_info).is_err() {
+                    continue;
+                }
+                let balance = self._psp22_balance_of(token_id, vault)?;
+                if balance > 0 {
+                    self._vault_transfer(vault, token_id, &token_info, core, balance)?;
+                }
+            }
+            self.env().emit_event(VaultContractRetired { project_id, vault });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_vault_code_hash(&self) -> Option<Hash> {
+            self.vault_code_hash.get_or_default()
+        }
+
+        /// Registro projeto → contrato do cofre
+        #[ink(message)]
+        pub fn get_project_vault_contract(&self, project_id: ProjectId) -> Option<AccountId> {
+            self.project_vault_contracts.get(project_id)
+        }
+
+        /// Paga a partir do cofre dedicado do projeto, quando existir
+        fn _project_transfer(
+            &mut self,
+            project_id: ProjectId,
+            token_id: TokenId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            let token_info = self.supported_tokens.get(token_id).ok_or(SafeguardError::TokenNotSupported)?;
+            let Some(vault) = self.project_vault_contracts.get(project_id) else {
+                return self._psp22_transfer(token_id, to, amount);
+            };
+            Self::_ensure_vault_custody(&token_info)?;
+            self._vault_transfer(vault, token_id, &token_info, to, amount)
+        }
+
+        /// Recebe depósitos na custódia do projeto: o cofre dedicado, quando existir
+        fn _project_deposit(
+            &mut self,
+            project_id: ProjectId,
+            token_id: TokenId,
+            from: AccountId,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            let token_info = self.supported_tokens.get(token_id).ok_or(SafeguardError::TokenNotSupported)?;
+            let to = match self.project_vault_contracts.get(project_id) {
+                Some(vault) => {
+                    Self::_ensure_vault_custody(&token_info)?;
+                    vault
+                }
+                None => self.env().account_id(),
+            };
+            self._psp22_transfer_from_to(token_id, from, to, amount)
+        }
+
+        /// Ordena ao cofre dedicado que transfira `amount` de um token
+        fn _vault_transfer(
+            &mut self,
+            vault: AccountId,
+            token_id: TokenId,
+            token_info: &TokenInfo,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            let mut vault_ref: ProjectVaultRef = ink::env::call::FromAccountId::from_account_id(vault);
+            let cause = match cross_call::invoke(&vault_ref.call_mut().transfer_token(token_info.contract_address, to, amount).params()) {
+                Ok(Ok(Ok(()))) => None,
+                Ok(Ok(Err(VaultError::Token(error)))) => Some(CallFailure::Psp22(error)),
+                Ok(Ok(Err(_))) => Some(CallFailure::CalleeReverted),
+                Ok(Err(_)) => Some(CallFailure::Dispatch),
+                Err(error) => Some(error.into()),
+            };
+            if let Some(cause) = cause {
+                return Err(SafeguardError::TokenCallFailed {
+                    token: token_info.contract_address,
+                    stage: CallStage::Transfer,
+                    cause,
+                });
+            }
+            self.env().emit_event(TokenTransferExecuted {
+                token_id,
+                from: vault,
+                to,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Depósito de garantia com taxas obrigatórias (100 LUNES + 10 LUSDT)
+        #[ink(message, payable)]
+        pub fn deposit_guarantee_with_fees(
+            &mut self, 
+            project_id: ProjectId,
+            token_id: TokenId,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            self._ensure_module_active(PauseModule::Deposits)?;
+            self.reentrancy_guard.start()?;
+            let result = self._deposit_guarantee_with_fees(project_id, token_id, amount);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _deposit_guarantee_with_fees(
+            &mut self, 
+            project_id: ProjectId,
+            token_id: TokenId,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
             
             // Verificar se o projeto existe e está ativo
             let mut vault = self.project_vaults.get(project_id).ok_or(SafeguardError::ProjectNotFound)?;
@@ -922,9 +3010,6 @@ pub mod safeguard {
                 return Err(SafeguardError::TokenNotSupported);
             }
 
-            // Cobrar taxas obrigatórias
-            self._charge_deposit_fees(project_id, caller)?;
-
             // Processar o depósito da garantia
             self._process_guarantee_deposit(project_id, token_id, caller, amount)?;
 
@@ -939,8 +3024,12 @@ pub mod safeguard {
 
             self.project_vaults.insert(project_id, &vault);
 
-            // Atualizar score do projeto
-            self.update_project_score(project_id)?;
+            // Atualizar score do projeto; um motor fora do ar não bloqueia depósitos
+            self._refresh_project_score(project_id);
+
+            // Custódia e taxas obrigatórias depois de registrar o depósito
+            self._project_deposit(project_id, token_id, caller, amount)?;
+            self._charge_deposit_fees(project_id, caller)?;
 
             Ok(())
         }
@@ -980,11 +3069,15 @@ pub mod safeguard {
         ) -> Result<(), SafeguardError> {
             // Atualizar saldo do usuário
             let current_balance = self.token_guarantees.get(&(project_id, token_id, depositor)).unwrap_or(0);
-            self.token_guarantees.insert((project_id, token_id, depositor), &(current_balance.saturating_add(amount)));
+            self._set_user_guarantee(project_id, token_id, depositor, current_balance.saturating_add(amount));
 
             // Atualizar total do projeto
             let current_total = self.project_total_guarantees.get(&(project_id, token_id)).unwrap_or(0);
-            self.project_total_guarantees.insert((project_id, token_id), &(current_total.saturating_add(amount)));
+            self._set_project_total_guarantee(project_id, token_id, current_total.saturating_add(amount));
+
+            // Atualizar saldo travado do usuário no token
+            let current_locked = self.user_token_balances.get((depositor, token_id)).unwrap_or(0);
+            self._set_user_token_balance(depositor, token_id, current_locked.saturating_add(amount));
 
             // Emitir evento de depósito
             self.env().emit_event(TokenGuaranteeDeposited {
@@ -1000,6 +3093,30 @@ pub mod safeguard {
         /// Inicia votação anual de governança para um projeto
         #[ink(message)]
         pub fn start_annual_voting(&mut self, project_id: ProjectId) -> Result<VotingId, SafeguardError> {
+            self._ensure_module_active(PauseModule::Voting)?;
+            self.reentrancy_guard.start()?;
+            let result = self._start_annual_voting(project_id);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        /// Inicia uma votação de qualquer tipo (extensão, emergência, liquidação)
+        /// A ponderação configurada para o tipo é fixada na abertura. Sem esta mensagem só a
+        /// votação anual podia ser aberta, e a ponderação por `VoteType` não teria efeito.
+        #[ink(message)]
+        pub fn start_voting(&mut self, project_id: ProjectId, vote_type: VoteType) -> Result<VotingId, SafeguardError> {
+            self._ensure_module_active(PauseModule::Voting)?;
+            self.reentrancy_guard.start()?;
+            let result = self._open_voting(project_id, vote_type);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _start_annual_voting(&mut self, project_id: ProjectId) -> Result<VotingId, SafeguardError> {
+            self._open_voting(project_id, VoteType::Annual)
+        }
+
+        fn _open_voting(&mut self, project_id: ProjectId, vote_type: VoteType) -> Result<VotingId, SafeguardError> {
             let caller = self.env().caller();
             
             // Verificar se o projeto existe
@@ -1015,11 +3132,17 @@ pub mod safeguard {
                 return Err(SafeguardError::VotingInProgress);
             }
 
-            // Verificar se já passou um ano desde a última votação
+            // Projetos liquidados ou encerrados não voltam a votar
+            if !matches!(vault.status, ProjectStatus::Active | ProjectStatus::Approved) {
+                return Err(SafeguardError::ProjectNotActive);
+            }
+
+            // Votação anual: verificar se já passou um ano desde a última
             let current_timestamp = self.env().block_timestamp();
             let one_year_ms = 365 * 24 * 60 * 60 * 1000u64;
             
-            if vault.last_annual_vote > 0 && 
+            if vote_type == VoteType::Annual &&
+               vault.last_annual_vote > 0 && 
                current_timestamp < vault.last_annual_vote.saturating_add(one_year_ms) {
                 return Err(SafeguardError::TooEarlyForVoting);
             }
@@ -1027,17 +3150,25 @@ pub mod safeguard {
             // Criar nova votação
             let voting_id = self.next_voting_id;
             let end_timestamp = current_timestamp.saturating_add(self.annual_voting_duration);
+
+            // Snapshot no último bloco selado antes da abertura
+            let snapshot_block = self.env().block_number().saturating_sub(1);
+            let total_eligible_weight = self._total_voting_power_at(&vault, snapshot_block)?;
             
             let voting_info = VotingInfo {
-                vote_type: VoteType::Annual,
+                vote_type,
                 start_timestamp: current_timestamp,
                 end_timestamp,
-                snapshot_block: self.env().block_number() as u64,
+                snapshot_block: snapshot_block as u64,
                 yes_votes: 0,
                 no_votes: 0,
-                total_eligible: self._calculate_eligible_voters(project_id)?,
+                total_eligible: 0,
                 result: VoteResult::Pending,
                 proposal_uri: Vec::new(),
+                weighting: self.get_vote_weighting(project_id, vote_type),
+                yes_weight: 0,
+                no_weight: 0,
+                total_eligible_weight,
             };
 
             // Armazenar votação
@@ -1045,6 +3176,7 @@ pub mod safeguard {
             
             // Atualizar vault
             vault.current_voting_id = Some(voting_id);
+            self.pre_voting_statuses.insert(project_id, &vault.status);
             vault.status = ProjectStatus::VotingPeriod;
             self.project_vaults.insert(project_id, &vault);
             
@@ -1054,7 +3186,7 @@ pub mod safeguard {
             self.env().emit_event(VoteOpened {
                 project_id,
                 voting_id,
-                vote_type: VoteType::Annual,
+                vote_type,
                 start_timestamp: current_timestamp,
                 end_timestamp,
                 snapshot_block: voting_info.snapshot_block,
@@ -1070,6 +3202,7 @@ pub mod safeguard {
             project_id: ProjectId, 
             proposal_uri: Vec<u8>
         ) -> Result<(), SafeguardError> {
+            self._ensure_module_active(PauseModule::Voting)?;
             let caller = self.env().caller();
             
             // Verificar se o projeto existe
@@ -1109,6 +3242,18 @@ pub mod safeguard {
             &mut self, 
             project_id: ProjectId, 
             vote: bool
+        ) -> Result<(), SafeguardError> {
+            self._ensure_module_active(PauseModule::Voting)?;
+            self.reentrancy_guard.start()?;
+            let result = self._vote_on_proposal(project_id, vote);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _vote_on_proposal(
+            &mut self, 
+            project_id: ProjectId, 
+            vote: bool
         ) -> Result<(), SafeguardError> {
             let caller = self.env().caller();
             
@@ -1125,187 +3270,434 @@ pub mod safeguard {
                 return Err(SafeguardError::VotingExpired);
             }
 
-            // Verificar se o usuário tem direito a voto (possui garantias no projeto)
-            let user_has_guarantees = self._user_has_guarantees(project_id, caller)?;
-            if !user_has_guarantees {
-                return Err(SafeguardError::NotEligibleToVote);
+            // Verificar se já votou nesta votação
+            if self.ballots.contains((voting_id, caller)) {
+                return Err(SafeguardError::AlreadyVoted);
             }
 
-            // Verificar se já votou
-            if self.project_votes.contains((project_id, caller)) {
-                return Err(SafeguardError::AlreadyVoted);
+            // Peso bruto no snapshot da votação
+            let raw_weight = self._voting_power_at(&vault, caller, voting_info.snapshot_block)?;
+            if raw_weight == 0 {
+                return Err(SafeguardError::NotEligibleToVote);
             }
+            self._check_compliance(project_id, caller, ComplianceAction::Vote, raw_weight)?;
+            let effective_weight = Self::_apply_vote_weighting(
+                voting_info.weighting,
+                raw_weight,
+                voting_info.total_eligible_weight,
+            );
 
             // Registrar voto
             if vote {
                 voting_info.yes_votes = voting_info.yes_votes.saturating_add(1);
+                voting_info.yes_weight = voting_info.yes_weight.saturating_add(effective_weight);
             } else {
                 voting_info.no_votes = voting_info.no_votes.saturating_add(1);
+                voting_info.no_weight = voting_info.no_weight.saturating_add(effective_weight);
             }
 
             self.voting_infos.insert(voting_id, &voting_info);
-            self.project_votes.insert((project_id, caller), &(if vote { 1 } else { 0 }));
+            self.ballots.insert((voting_id, caller), &Ballot {
+                support: vote,
+                raw_weight,
+                effective_weight,
+                timestamp: current_timestamp,
+            });
+            self._index_export_key(ExportKind::Ballots, ExportKey::Ballot(voting_id, caller));
+
+            // Cobrar taxas de votação (parte vai para o pool de recompensas)
+            self._charge_vote_fees(voting_id, caller)?;
+
+            self.env().emit_event(BallotCast {
+                project_id,
+                voting_id,
+                voter: caller,
+                support: vote,
+                raw_weight,
+                effective_weight,
+            });
 
             Ok(())
         }
 
-        /// Finaliza uma votação e determina o resultado
-        #[ink(message)]
-        pub fn finalize_voting(&mut self, project_id: ProjectId) -> Result<VoteResult, SafeguardError> {
-            let caller = self.env().caller();
-            
-            // Verificar se o projeto existe
-            let mut vault = self.project_vaults.get(project_id).ok_or(SafeguardError::ProjectNotFound)?;
-            
-            // Verificar se há votação ativa
-            let voting_id = vault.current_voting_id.ok_or(SafeguardError::NoActiveVoting)?;
-            let mut voting_info = self.voting_infos.get(voting_id).ok_or(SafeguardError::VotingNotFound)?;
-
-            // Verificar se a votação expirou
-            let current_timestamp = self.env().block_timestamp();
-            if current_timestamp <= voting_info.end_timestamp {
-                return Err(SafeguardError::VotingStillActive);
+        /// Cobra as taxas de votação, separando a parcela de recompensa dos votantes
+        fn _charge_vote_fees(&mut self, voting_id: VotingId, voter: AccountId) -> Result<(), SafeguardError> {
+            if self.lunes_vote_fee.get_or_default() == 0 && self.lusdt_vote_fee.get_or_default() == 0 {
+                return Ok(());
             }
 
-            // Calcular resultado (maioria simples)
-            let total_votes = voting_info.yes_votes.saturating_add(voting_info.no_votes);
-            let result = if voting_info.yes_votes > voting_info.no_votes {
-                VoteResult::Approved
-            } else if voting_info.no_votes > voting_info.yes_votes {
-                VoteResult::Rejected
-            } else {
-                VoteResult::Rejected // Empate = rejeição
+            // Tokens fixados na primeira cobrança: trocar lunes_token_id/lusdt_token_id
+            // depois não deixa os pools desta votação sem resgate
+            let (lunes_id, lusdt_id) = match self.voter_reward_tokens.get(voting_id) {
+                Some(tokens) => tokens,
+                None => {
+                    let tokens = (self.lunes_token_id, self.lusdt_token_id);
+                    self.voter_reward_tokens.insert(voting_id, &tokens);
+                    tokens
+                }
             };
 
-            // Atualizar resultado
-            voting_info.result = result.clone();
-            self.voting_infos.insert(voting_id, &voting_info);
-
-            // Atualizar status do projeto baseado no resultado
-            match result {
-                VoteResult::Approved => {
-                    vault.status = ProjectStatus::Approved;
-                    vault.last_annual_vote = current_timestamp;
-                },
-                VoteResult::Rejected => {
-                    vault.status = ProjectStatus::Rejected;
-                    // Iniciar processo de liquidação
-                    vault.liquidation_timestamp = Some(current_timestamp);
-                    vault.claims_deadline = Some(current_timestamp.saturating_add(self.claims_period_duration));
-                },
-                _ => {}
-            }
-
-            vault.current_voting_id = None;
-            self.project_vaults.insert(project_id, &vault);
+            let lunes_reward = self._collect_vote_fee(voting_id, lunes_id, voter, self.lunes_vote_fee.get_or_default())?;
+            let lusdt_reward = self._collect_vote_fee(voting_id, lusdt_id, voter, self.lusdt_vote_fee.get_or_default())?;
 
-            // Emitir evento
-            self.env().emit_event(VoteClosed {
-                project_id,
+            self.env().emit_event(VoteFeeCharged {
                 voting_id,
-                yes_votes: voting_info.yes_votes,
-                no_votes: voting_info.no_votes,
-                total_eligible: voting_info.total_eligible,
-                result: result.clone(),
+                voter,
+                lunes_fee: self.lunes_vote_fee.get_or_default(),
+                lusdt_fee: self.lusdt_vote_fee.get_or_default(),
+                lunes_reward,
+                lusdt_reward,
             });
 
-            // Se rejeitado, abrir claims
-            if result == VoteResult::Rejected {
-                self.env().emit_event(ClaimsOpened {
-                    project_id,
-                    liquidation_timestamp: current_timestamp,
-                    claim_deadline: vault.claims_deadline.unwrap(),
-                });
-            }
-
-            Ok(result)
+            Ok(())
         }
 
-        /// Calcula o número de votantes elegíveis
-        fn _calculate_eligible_voters(&self, _project_id: ProjectId) -> Result<u64, SafeguardError> {
-            // Por simplicidade, conta todos os usuários que têm garantias no projeto
-            let _count = 0u64;
-            
-            // Iterar sobre todos os tokens suportados e contar usuários únicos
-            // Em uma implementação real, seria mais eficiente manter um registro separado
-            for _token_id in 0..self.next_token_id {
-                // Esta é uma implementação simplificada
-                // Na prática, precisaríamos de uma estrutura de dados mais eficiente
+        /// Recebe uma taxa de votação, envia o restante à treasury e retorna a parcela do pool
+        fn _collect_vote_fee(
+            &mut self,
+            voting_id: VotingId,
+            token_id: Option<TokenId>,
+            voter: AccountId,
+            fee: Balance,
+        ) -> Result<Balance, SafeguardError> {
+            if fee == 0 {
+                return Ok(0);
             }
-            
-            // Retorna um valor padrão por enquanto
-            Ok(10) // Placeholder
-        }
+            let token_id = token_id.ok_or(SafeguardError::TokenNotSupported)?;
 
-        /// Verifica se um usuário tem garantias no projeto
-        fn _user_has_guarantees(&self, project_id: ProjectId, user: AccountId) -> Result<bool, SafeguardError> {
-            // Verificar se o usuário tem garantias em qualquer token
-            for token_id in 0..self.next_token_id {
-                let balance = self.token_guarantees.get(&(project_id, token_id, user)).unwrap_or(0);
-                if balance > 0 {
-                    return Ok(true);
-                }
-            }
-            
-            // Verificar NFTs também
-            for collection_id in 0..self.next_nft_collection_id {
-                let nft_count = self.user_nft_deposits.get(&(user, collection_id)).unwrap_or(0);
-                if nft_count > 0 {
-                    return Ok(true);
-                }
+            let reward = fee
+                .saturating_mul(self.voter_reward_share_bps.get_or_default() as Balance)
+                .saturating_div(10_000);
+            let treasury_part = safe_math::safe_sub_balance(fee, reward)?;
+
+            let pool = self.voter_reward_pools.get((voting_id, token_id)).unwrap_or(0);
+            self.voter_reward_pools.insert((voting_id, token_id), &safe_math::safe_add_balance(pool, reward)?);
+
+            self._psp22_transfer_from(token_id, voter, fee)?;
+            if treasury_part > 0 {
+                self._psp22_transfer(token_id, self.treasury_address, treasury_part)?;
             }
-            
-            Ok(false)
+
+            Ok(reward)
         }
 
-        /// Processa claim de liquidação com distribuição proporcional
+        /// Resgata a recompensa de votação, proporcional ao peso efetivo do voto
         #[ink(message)]
-        pub fn process_claim(&mut self, project_id: ProjectId) -> Result<(), SafeguardError> {
+        pub fn claim_voter_reward(&mut self, voting_id: VotingId) -> Result<(Balance, Balance), SafeguardError> {
+            self._ensure_module_active(PauseModule::Claims)?;
+            self.reentrancy_guard.start()?;
+            let result = self._claim_voter_reward(voting_id);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _claim_voter_reward(&mut self, voting_id: VotingId) -> Result<(Balance, Balance), SafeguardError> {
             let caller = self.env().caller();
-            
-            // Verificar se o projeto existe
-            let vault = self.project_vaults.get(project_id).ok_or(SafeguardError::ProjectNotFound)?;
-            
-            // Verificar se o projeto está em liquidação
-            if vault.status != ProjectStatus::Rejected && vault.status != ProjectStatus::ClaimsOpen {
-                return Err(SafeguardError::ClaimsNotOpen);
-            }
 
-            // Verificar se ainda está dentro do prazo de claims
-            let current_timestamp = self.env().block_timestamp();
-            if let Some(deadline) = vault.claims_deadline {
-                if current_timestamp > deadline {
-                    return Err(SafeguardError::ClaimsExpired);
-                }
+            let voting_info = self.voting_infos.get(voting_id).ok_or(SafeguardError::VotingNotFound)?;
+            if voting_info.result == VoteResult::Pending {
+                return Err(SafeguardError::VotingStillActive);
             }
-
-            // Verificar se o usuário já fez claim
-            if let Some(existing_claim) = self.user_claims.get(&(project_id, caller)) {
-                if existing_claim.is_claimed {
-                    return Err(SafeguardError::AlreadyClaimed);
-                }
+            if !self.ballots.contains((voting_id, caller)) {
+                return Err(SafeguardError::NotEligibleToVote);
+            }
+            if self.voter_rewards_claimed.get((voting_id, caller)).unwrap_or(false) {
+                return Err(SafeguardError::AlreadyClaimed);
             }
 
-            // Calcular a participação proporcional do usuário
-            let claim_info = self._calculate_proportional_claim(project_id, caller)?;
-            
-            // Verificar se o usuário tem direito a claim
-            if claim_info.project_tokens_held == 0 {
+            let (lunes_amount, lusdt_amount) = self.get_unclaimed_voter_reward(voting_id, caller);
+            if lunes_amount == 0 && lusdt_amount == 0 {
                 return Err(SafeguardError::NoClaimableAmount);
             }
 
-            // Processar transferências (simulado - em implementação real seria cross-contract calls)
-            self._execute_claim_transfers(project_id, caller, &claim_info)?;
+            self.voter_rewards_claimed.insert((voting_id, caller), &true);
 
-            // Marcar claim como processado
-            let mut updated_claim = claim_info;
-            updated_claim.is_claimed = true;
-            updated_claim.claim_timestamp = Some(current_timestamp);
-            self.user_claims.insert((project_id, caller), &updated_claim);
+            let (lunes_id, lusdt_id) = self.get_voter_reward_tokens(voting_id);
+            if lunes_amount > 0 {
+                let lunes_id = lunes_id.ok_or(SafeguardError::TokenNotSupported)?;
+                self._send_outflow(OutflowKind::VoterReward(voting_id), lunes_id, caller, lunes_amount)?;
+            }
+            if lusdt_amount > 0 {
+                let lusdt_id = lusdt_id.ok_or(SafeguardError::TokenNotSupported)?;
+                self._send_outflow(OutflowKind::VoterReward(voting_id), lusdt_id, caller, lusdt_amount)?;
+            }
 
-            // Emitir evento
-            self.env().emit_event(ClaimPaid {
-                project_id,
+            self.env().emit_event(VoterRewardClaimed {
+                voting_id,
+                voter: caller,
+                lunes_amount,
+                lusdt_amount,
+            });
+
+            Ok((lunes_amount, lusdt_amount))
+        }
+
+        /// Recompensa ainda não resgatada de um votante (LUNES, LUSDT)
+        /// Retorna zero enquanto a votação não for finalizada
+        #[ink(message)]
+        pub fn get_unclaimed_voter_reward(&self, voting_id: VotingId, voter: AccountId) -> (Balance, Balance) {
+            let voting_info = match self.voting_infos.get(voting_id) {
+                Some(info) if info.result != VoteResult::Pending => info,
+                _ => return (0, 0),
+            };
+            let ballot = match self.ballots.get((voting_id, voter)) {
+                Some(ballot) => ballot,
+                None => return (0, 0),
+            };
+            if self.voter_rewards_claimed.get((voting_id, voter)).unwrap_or(false) {
+                return (0, 0);
+            }
+
+            let total_weight = voting_info.yes_weight.saturating_add(voting_info.no_weight);
+            if total_weight == 0 {
+                return (0, 0);
+            }
+
+            // Pools de 18 casas vezes pesos normalizados passam de u128; o produto vai em 256 bits
+            let share_of = |token_id: Option<TokenId>| -> Balance {
+                let pool = token_id
+                    .and_then(|id| self.voter_reward_pools.get((voting_id, id)))
+                    .unwrap_or(0);
+                safe_math::mul_div(pool, ballot.effective_weight, total_weight)
+            };
+
+            let (lunes_id, lusdt_id) = self.get_voter_reward_tokens(voting_id);
+            (share_of(lunes_id), share_of(lusdt_id))
+        }
+
+        /// Tokens (LUNES, LUSDT) em que os pools de uma votação foram formados
+        #[ink(message)]
+        pub fn get_voter_reward_tokens(&self, voting_id: VotingId) -> RewardTokenIds {
+            self.voter_reward_tokens.get(voting_id).unwrap_or((None, None))
+        }
+
+        /// Obter pool de recompensas de uma votação para um token
+        #[ink(message)]
+        pub fn get_voter_reward_pool(&self, voting_id: VotingId, token_id: TokenId) -> Balance {
+            self.voter_reward_pools.get((voting_id, token_id)).unwrap_or(0)
+        }
+
+        /// Aplica a função de ponderação ao peso bruto de um voto
+        fn _apply_vote_weighting(
+            weighting: VoteWeighting,
+            raw_weight: Balance,
+            total_eligible_weight: Balance,
+        ) -> Balance {
+            match weighting {
+                VoteWeighting::Linear => raw_weight,
+                VoteWeighting::Quadratic => safe_math::isqrt(raw_weight),
+                VoteWeighting::CappedLinear { max_share_bps } => {
+                    let cap = total_eligible_weight
+                        .saturating_mul(max_share_bps as Balance)
+                        .saturating_div(10_000);
+                    raw_weight.min(cap)
+                }
+            }
+        }
+
+        /// Peso de voto bruto no snapshot: saldo histórico do token do projeto quando
+        /// ele implementa PSP22Votes, senão as garantias PSP22 do usuário no projeto
+        fn _voting_power_at(
+            &self,
+            vault: &ProjectVault,
+            account: AccountId,
+            snapshot_block: u64,
+        ) -> Result<Balance, SafeguardError> {
+            if self.project_token_checkpoints.get(vault.project_id).unwrap_or(false) {
+                let block = BlockNumber::try_from(snapshot_block).map_err(|_| SafeguardError::InvalidInput)?;
+                return self._psp22_past_balance(vault.token_contract, account, block);
+            }
+            Ok(self._guarantee_power_at(vault.project_id, account, snapshot_block))
+        }
+
+        /// Peso total elegível no snapshot (supply histórico ou garantias do cofre)
+        fn _total_voting_power_at(&self, vault: &ProjectVault, snapshot_block: BlockNumber) -> Result<Balance, SafeguardError> {
+            if self.project_token_checkpoints.get(vault.project_id).unwrap_or(false) {
+                return self._psp22_past_total_supply(vault.token_contract, snapshot_block);
+            }
+            Ok(self._total_guarantee_power_at(vault.project_id, snapshot_block as u64))
+        }
+
+        /// Peso de voto bruto de um depositante no bloco (garantias PSP22 mais o valor
+        /// estimado dos NFTs depositados no projeto, tudo na unidade comum de peso)
+        fn _guarantee_power_at(&self, project_id: ProjectId, account: AccountId, block: u64) -> Balance {
+            let mut total = self._checkpoint_at(&CheckpointKey::UserNftValue(project_id, account), block);
+            for token_id in 0..self.next_token_id {
+                let balance = self._checkpoint_at(&CheckpointKey::UserGuarantee(project_id, token_id, account), block);
+                total = total.saturating_add(self._vote_weight_of(token_id, balance));
+            }
+            total
+        }
+
+        /// Peso total elegível do projeto no bloco (garantias PSP22 e NFTs do cofre)
+        fn _total_guarantee_power_at(&self, project_id: ProjectId, block: u64) -> Balance {
+            let mut total = self._checkpoint_at(&CheckpointKey::ProjectNftValue(project_id), block);
+            for token_id in 0..self.next_token_id {
+                let balance = self._checkpoint_at(&CheckpointKey::ProjectTotal(project_id, token_id), block);
+                total = total.saturating_add(self._vote_weight_of(token_id, balance));
+            }
+            total
+        }
+
+        /// Reescala um saldo do token para `VOTE_WEIGHT_DECIMALS` casas
+        fn _vote_weight_of(&self, token_id: TokenId, balance: Balance) -> Balance {
+            if balance == 0 {
+                return 0;
+            }
+            let decimals = self.supported_tokens.get(token_id).map_or(VOTE_WEIGHT_DECIMALS, |info| info.decimals);
+            if decimals <= VOTE_WEIGHT_DECIMALS {
+                let scale = 10u128.saturating_pow(u32::from(VOTE_WEIGHT_DECIMALS.saturating_sub(decimals)));
+                balance.saturating_mul(scale)
+            } else {
+                let scale = 10u128.saturating_pow(u32::from(decimals.saturating_sub(VOTE_WEIGHT_DECIMALS)));
+                balance.saturating_div(scale)
+            }
+        }
+
+        /// Finaliza uma votação e determina o resultado
+        #[ink(message)]
+        pub fn finalize_voting(&mut self, project_id: ProjectId) -> Result<VoteResult, SafeguardError> {
+            self._ensure_module_active(PauseModule::Voting)?;
+            self._finalize_voting(project_id)
+        }
+
+        fn _finalize_voting(&mut self, project_id: ProjectId) -> Result<VoteResult, SafeguardError> {
+            // Verificar se o projeto existe
+            let mut vault = self.project_vaults.get(project_id).ok_or(SafeguardError::ProjectNotFound)?;
+            
+            // Verificar se há votação ativa
+            let voting_id = vault.current_voting_id.ok_or(SafeguardError::NoActiveVoting)?;
+            let mut voting_info = self.voting_infos.get(voting_id).ok_or(SafeguardError::VotingNotFound)?;
+
+            // Verificar se a votação expirou
+            let current_timestamp = self.env().block_timestamp();
+            if current_timestamp <= voting_info.end_timestamp {
+                return Err(SafeguardError::VotingStillActive);
+            }
+
+            // Calcular resultado (maioria simples dos pesos efetivos; empate = rejeição).
+            // Sem nenhum peso votado a votação expira sem efeito
+            let result = if voting_info.yes_weight == 0 && voting_info.no_weight == 0 {
+                VoteResult::Expired
+            } else if voting_info.yes_weight > voting_info.no_weight {
+                VoteResult::Approved
+            } else {
+                VoteResult::Rejected
+            };
+
+            // Atualizar resultado
+            voting_info.result = result.clone();
+            self.voting_infos.insert(voting_id, &voting_info);
+
+            // Atualizar status do projeto baseado no resultado. Só liquidam uma liquidação aprovada
+            // ("liquidar?") e uma anual rejeitada ("continuar?"). Extensão e emergência rejeitadas,
+            // assim como votações expiradas, devolvem o projeto ao status anterior à abertura.
+            // Só a anual aprovada reinicia o ciclo anual.
+            let liquidate = match voting_info.vote_type {
+                VoteType::Liquidation => result == VoteResult::Approved,
+                VoteType::Annual => result == VoteResult::Rejected,
+                VoteType::Extension | VoteType::Emergency => false,
+            };
+            let previous_status = self.pre_voting_statuses.take(project_id).unwrap_or(ProjectStatus::Active);
+            if liquidate {
+                vault.status = ProjectStatus::Rejected;
+                // Iniciar processo de liquidação (claims usam o snapshot desta votação)
+                self.project_liquidation_votings.insert(project_id, &voting_id);
+                vault.liquidation_timestamp = Some(current_timestamp);
+                vault.claims_deadline = Some(current_timestamp.saturating_add(self.claims_period_duration));
+            } else {
+                // Uma liquidação rejeitada também confirma o projeto
+                let confirmed = result == VoteResult::Approved
+                    || (voting_info.vote_type == VoteType::Liquidation && result == VoteResult::Rejected);
+                if confirmed {
+                    vault.status = ProjectStatus::Approved;
+                    if voting_info.vote_type == VoteType::Annual {
+                        vault.last_annual_vote = current_timestamp;
+                    }
+                } else {
+                    vault.status = previous_status;
+                }
+            }
+
+            vault.current_voting_id = None;
+            self.project_vaults.insert(project_id, &vault);
+
+            // Emitir evento
+            self.env().emit_event(VoteClosed {
+                project_id,
+                voting_id,
+                yes_votes: voting_info.yes_votes,
+                no_votes: voting_info.no_votes,
+                total_eligible_weight: voting_info.total_eligible_weight,
+                result: result.clone(),
+            });
+
+            // Se liquidado, abrir claims
+            if liquidate {
+                self.env().emit_event(ClaimsOpened {
+                    project_id,
+                    liquidation_timestamp: current_timestamp,
+                    claim_deadline: vault.claims_deadline.unwrap(),
+                });
+            }
+
+            Ok(result)
+        }
+
+        /// Processa claim de liquidação com distribuição proporcional
+        #[ink(message)]
+        pub fn process_claim(&mut self, project_id: ProjectId) -> Result<(), SafeguardError> {
+            self._ensure_module_active(PauseModule::Claims)?;
+            let caller = self.env().caller();
+            self.reentrancy_guard.start()?;
+            let result = self._process_claim(project_id, caller, true);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _process_claim(&mut self, project_id: ProjectId, caller: AccountId, enforce_deadline: bool) -> Result<(), SafeguardError> {
+            // Verificar se o projeto existe
+            let vault = self.project_vaults.get(project_id).ok_or(SafeguardError::ProjectNotFound)?;
+            
+            // Verificar se o projeto está em liquidação
+            if vault.status != ProjectStatus::Rejected && vault.status != ProjectStatus::ClaimsOpen {
+                return Err(SafeguardError::ClaimsNotOpen);
+            }
+
+            // Verificar se ainda está dentro do prazo de claims
+            let current_timestamp = self.env().block_timestamp();
+            if let Some(deadline) = vault.claims_deadline {
+                if enforce_deadline && current_timestamp > deadline {
+                    return Err(SafeguardError::ClaimsExpired);
+                }
+            }
+
+            // Verificar se o usuário já fez claim
+            if let Some(existing_claim) = self.user_claims.get(&(project_id, caller)) {
+                if existing_claim.is_claimed {
+                    return Err(SafeguardError::AlreadyClaimed);
+                }
+            }
+
+            // Calcular a participação proporcional do usuário
+            let claim_info = self._calculate_proportional_claim(project_id, caller)?;
+            
+            // Verificar se o usuário tem direito a claim
+            if claim_info.project_tokens_held == 0 {
+                return Err(SafeguardError::NoClaimableAmount);
+            }
+            self._check_compliance(project_id, caller, ComplianceAction::Claim, claim_info.project_tokens_held)?;
+
+            // Marcar claim como processado antes das transferências
+            let mut updated_claim = claim_info;
+            updated_claim.is_claimed = true;
+            updated_claim.claim_timestamp = Some(current_timestamp);
+            self.user_claims.insert((project_id, caller), &updated_claim);
+            self._index_export_key(ExportKind::Claims, ExportKey::Claim(project_id, caller));
+
+            // Emitir evento
+            self.env().emit_event(ClaimPaid {
+                project_id,
                 claimer: caller,
                 project_tokens_returned: updated_claim.project_tokens_held,
                 lunes_received: updated_claim.lunes_share,
@@ -1313,6 +3705,9 @@ pub mod safeguard {
                 other_tokens_received: updated_claim.other_tokens_share,
             });
 
+            // Transferências por último (checks-effects-interactions)
+            self._execute_claim_transfers(project_id, caller, &updated_claim)?;
+
             Ok(())
         }
 
@@ -1320,12 +3715,17 @@ pub mod safeguard {
         fn _calculate_proportional_claim(&self, project_id: ProjectId, claimer: AccountId) -> Result<ClaimInfo, SafeguardError> {
             let vault = self.project_vaults.get(project_id).ok_or(SafeguardError::ProjectNotFound)?;
             
-            // Calcular total de tokens do projeto que o usuário possui
-            // Em uma implementação real, isso seria obtido do contrato do token do projeto
-            let user_project_tokens: Balance = 1000 * 1_000_000_000_000_000_000; // Placeholder: 1000 tokens
-            
-            // Calcular supply total do projeto
-            let total_project_supply: Balance = 1_000_000 * 1_000_000_000_000_000_000; // Placeholder: 1M tokens
+            // Elegibilidade e peso lidos no snapshot da votação que abriu a liquidação,
+            // impedindo depósitos feitos depois do snapshot
+            let (user_project_tokens, total_project_supply) = match self.project_liquidation_votings.get(project_id)
+                .and_then(|voting_id| self.voting_infos.get(voting_id))
+            {
+                Some(voting_info) => (
+                    self._voting_power_at(&vault, claimer, voting_info.snapshot_block)?,
+                    voting_info.total_eligible_weight,
+                ),
+                None => (0, 0),
+            };
             
             if total_project_supply == 0 {
                 return Ok(ClaimInfo {
@@ -1338,19 +3738,11 @@ pub mod safeguard {
                 });
             }
 
-            // Calcular proporção do usuário
-            let user_proportion = user_project_tokens.saturating_mul(1_000_000_000_000_000_000)
-                .saturating_div(total_project_supply);
-
-            // Calcular shares proporcionais
-            let lunes_share = vault.total_lunes_collateral.saturating_mul(user_proportion)
-                .saturating_div(1_000_000_000_000_000_000);
-            
-            let lusdt_share = vault.total_lusdt_collateral.saturating_mul(user_proportion)
-                .saturating_div(1_000_000_000_000_000_000);
-            
-            let other_share = vault.total_other_collateral.saturating_mul(user_proportion)
-                .saturating_div(1_000_000_000_000_000_000);
+            // Shares proporcionais ao peso; pesos normalizados passam de 1e30, então o
+            // produto é feito em 256 bits para não saturar
+            let lunes_share = safe_math::mul_div(vault.total_lunes_collateral, user_project_tokens, total_project_supply);
+            let lusdt_share = safe_math::mul_div(vault.total_lusdt_collateral, user_project_tokens, total_project_supply);
+            let other_share = safe_math::mul_div(vault.total_other_collateral, user_project_tokens, total_project_supply);
 
             Ok(ClaimInfo {
                 project_tokens_held: user_project_tokens,
@@ -1367,14 +3759,15 @@ pub mod safeguard {
         /// Encerra um projeto após o período de claims
         #[ink(message)]
         pub fn close_project(&mut self, project_id: ProjectId) -> Result<(), SafeguardError> {
+            self._ensure_not_paused()?;
             let caller = self.env().caller();
             
             // Verificar se o projeto existe
             let mut vault = self.project_vaults.get(project_id).ok_or(SafeguardError::ProjectNotFound)?;
             
-            // Verificar permissões (owner ou contract owner)
-            if vault.owner != caller && self.contract_owner != caller {
-                return Err(SafeguardError::NotOwner);
+            // Verificar permissões (owner do projeto ou conselho)
+            if vault.owner != caller {
+                self._ensure_role(Role::Admin)?;
             }
 
             // Verificar se o período de claims expirou
@@ -1399,25 +3792,22 @@ pub mod safeguard {
             Ok(())
         }
 
-        /// Configurar endereço da treasury (owner only)
+        /// Configurar endereço da treasury (admin de bootstrap; depois via timelock ou DAO)
         #[ink(message)]
         pub fn set_treasury_address(&mut self, treasury: AccountId) -> Result<(), SafeguardError> {
-            let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
+            self._ensure_protocol_admin()?;
+            self._set_treasury_address(treasury);
+            Ok(())
+        }
 
+        fn _set_treasury_address(&mut self, treasury: AccountId) {
             self.treasury_address = treasury;
-            Ok(())
         }
 
-        /// Configurar token LUSDT (owner only)
+        /// Configurar token LUSDT (Admin)
         #[ink(message)]
         pub fn set_lusdt_token_id(&mut self, token_id: TokenId) -> Result<(), SafeguardError> {
-            let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
+            self._ensure_role(Role::Admin)?;
 
             // Verificar se o token existe
             let token_info = self.supported_tokens.get(token_id).ok_or(SafeguardError::TokenNotSupported)?;
@@ -1429,19 +3819,54 @@ pub mod safeguard {
             Ok(())
         }
 
-        /// Configurar taxas de depósito (owner only)
+        /// Configurar taxas de depósito (admin de bootstrap; depois via timelock ou DAO)
         #[ink(message)]
         pub fn set_deposit_fees(&mut self, lunes_fee: Balance, lusdt_fee: Balance) -> Result<(), SafeguardError> {
-            let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
+            self._ensure_protocol_admin()?;
+            self._set_deposit_fees(lunes_fee, lusdt_fee);
+            Ok(())
+        }
 
+        fn _set_deposit_fees(&mut self, lunes_fee: Balance, lusdt_fee: Balance) {
             self.lunes_deposit_fee = lunes_fee;
             self.lusdt_deposit_fee = lusdt_fee;
+        }
+
+        /// Obter taxas de depósito (LUNES, LUSDT)
+        #[ink(message)]
+        pub fn get_deposit_fees(&self) -> (Balance, Balance) {
+            (self.lunes_deposit_fee, self.lusdt_deposit_fee)
+        }
+
+        /// Configurar taxas de votação (Admin)
+        #[ink(message)]
+        pub fn set_vote_fees(&mut self, lunes_fee: Balance, lusdt_fee: Balance) -> Result<(), SafeguardError> {
+            self._ensure_role(Role::Admin)?;
+
+            self.lunes_vote_fee.set(&lunes_fee);
+            self.lusdt_vote_fee.set(&lusdt_fee);
+            Ok(())
+        }
+
+        /// Configurar a parcela das taxas de votação destinada aos votantes, em bps (Admin)
+        #[ink(message)]
+        pub fn set_voter_reward_share(&mut self, share_bps: u16) -> Result<(), SafeguardError> {
+            self._ensure_role(Role::Admin)?;
+
+            if share_bps > 10_000 {
+                return Err(SafeguardError::InvalidInput);
+            }
+
+            self.voter_reward_share_bps.set(&share_bps);
             Ok(())
         }
 
+        /// Obter configuração de taxas de votação (LUNES, LUSDT, parcela dos votantes em bps)
+        #[ink(message)]
+        pub fn get_vote_fee_config(&self) -> (Balance, Balance, u16) {
+            (self.lunes_vote_fee.get_or_default(), self.lusdt_vote_fee.get_or_default(), self.voter_reward_share_bps.get_or_default())
+        }
+
         /// Obter informações do cofre de um projeto
         #[ink(message)]
         pub fn get_project_vault(&self, project_id: ProjectId) -> Option<ProjectVault> {
@@ -1460,29 +3885,123 @@ pub mod safeguard {
             self.user_claims.get(&(project_id, user))
         }
 
-        /// Função legada para compatibilidade
+        /// Obter o voto registrado de um usuário em uma votação
+        #[ink(message)]
+        pub fn get_ballot(&self, voting_id: VotingId, voter: AccountId) -> Option<Ballot> {
+            self.ballots.get((voting_id, voter))
+        }
+
+        /// Configurar a ponderação padrão de um tipo de votação (Admin)
+        #[ink(message)]
+        pub fn set_default_vote_weighting(
+            &mut self,
+            vote_type: VoteType,
+            weighting: VoteWeighting,
+        ) -> Result<(), SafeguardError> {
+            self._ensure_role(Role::Admin)?;
+
+            Self::_validate_vote_weighting(weighting)?;
+            self.default_vote_weightings.insert(vote_type, &weighting);
+
+            self.env().emit_event(VoteWeightingUpdated {
+                project_id: None,
+                vote_type,
+                weighting,
+            });
+            Ok(())
+        }
+
+        /// Configurar a ponderação de um tipo de votação para um projeto (Admin)
+        #[ink(message)]
+        pub fn set_project_vote_weighting(
+            &mut self,
+            project_id: ProjectId,
+            vote_type: VoteType,
+            weighting: VoteWeighting,
+        ) -> Result<(), SafeguardError> {
+            self._ensure_role(Role::Admin)?;
+
+            if !self.project_vaults.contains(project_id) {
+                return Err(SafeguardError::ProjectNotFound);
+            }
+
+            Self::_validate_vote_weighting(weighting)?;
+            self.project_vote_weightings.insert((project_id, vote_type), &weighting);
+
+            self.env().emit_event(VoteWeightingUpdated {
+                project_id: Some(project_id),
+                vote_type,
+                weighting,
+            });
+            Ok(())
+        }
+
+        /// Indicar se o token do projeto implementa PSP22Votes (owner do projeto ou do contrato)
+        /// Quando ativo, pesos e total elegível são lidos no `snapshot_block` da votação
+        #[ink(message)]
+        pub fn set_project_token_checkpoints(&mut self, project_id: ProjectId, enabled: bool) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
+            let vault = self.project_vaults.get(project_id).ok_or(SafeguardError::ProjectNotFound)?;
+            if caller != vault.owner {
+                self._ensure_role(Role::Admin)?;
+            }
+
+            // Não alterar a fonte de peso durante uma votação
+            if vault.current_voting_id.is_some() {
+                return Err(SafeguardError::VotingInProgress);
+            }
+
+            self.project_token_checkpoints.insert(project_id, &enabled);
+            Ok(())
+        }
+
+        /// Verificar se o token do projeto é lido via PSP22Votes
+        #[ink(message)]
+        pub fn uses_token_checkpoints(&self, project_id: ProjectId) -> bool {
+            self.project_token_checkpoints.get(project_id).unwrap_or(false)
+        }
+
+        /// Ponderação efetiva para um projeto e tipo de votação
+        /// (projeto > padrão do tipo > linear)
+        #[ink(message)]
+        pub fn get_vote_weighting(&self, project_id: ProjectId, vote_type: VoteType) -> VoteWeighting {
+            self.project_vote_weightings.get((project_id, vote_type))
+                .or_else(|| self.default_vote_weightings.get(vote_type))
+                .unwrap_or(VoteWeighting::Linear)
+        }
+
+        fn _validate_vote_weighting(weighting: VoteWeighting) -> Result<(), SafeguardError> {
+            if let VoteWeighting::CappedLinear { max_share_bps } = weighting {
+                if max_share_bps == 0 || max_share_bps > 10_000 {
+                    return Err(SafeguardError::InvalidInput);
+                }
+            }
+            Ok(())
+        }
+
+        /// Função legada para compatibilidade: registra um projeto v2 sem nome nem metadados
+        #[cfg(feature = "legacy-api")]
         #[ink(message)]
         pub fn register_project_legacy(&mut self, pair_psp22: Option<AccountId>) -> Result<ProjectId, SafeguardError> {
+            self._ensure_not_paused()?;
             let caller = self.env().caller();
             validation::validate_account(caller)?;
+            self.reentrancy_guard.start()?;
+            let result = self._register_project_legacy(caller, pair_psp22);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        #[cfg(feature = "legacy-api")]
+        fn _register_project_legacy(&mut self, caller: AccountId, pair_psp22: Option<AccountId>) -> Result<ProjectId, SafeguardError> {
 
             let project_id = self.next_project_id;
+            let creation_timestamp = self.env().block_timestamp();
             
-            // Initialize project fields
             self.project_owners.insert(project_id, &caller);
-            self.project_vote_end_times.insert(project_id, &0);
-            self.project_vote_yes_counts.insert(project_id, &0);
-            self.project_vote_no_counts.insert(project_id, &0);
-            self.project_withdraw_statuses.insert(project_id, &false);
+            self.project_creation_timestamps.insert(project_id, &creation_timestamp);
             self.project_statuses.insert(project_id, &true);
-            self.project_permission_balances.insert(project_id, &0);
             self.project_psp22_pairs.insert(project_id, &pair_psp22);
-            self.project_withdrawals.insert(&(project_id, caller), &0);
-            self.project_lunes_withdraw_balances.insert(project_id, &0);
-            self.project_guarantees.insert(&(project_id, caller), &0);
-            // Set project creation timestamp for vesting logic
-            let creation_timestamp = self.env().block_timestamp();
-            self.project_creation_timestamps.insert(project_id, &creation_timestamp);
             
             // Increment next project ID
             self.next_project_id = safe_math::safe_add(self.next_project_id, 1)?;
@@ -1497,16 +4016,113 @@ pub mod safeguard {
                 treasury_address: AccountId::from([0u8; 32]),
             });
             
+            self._init_project_vault(Self::_legacy_project_vault(project_id, caller, pair_psp22, creation_timestamp))?;
+            
             Ok(project_id)
         }
 
+        /// Converte um projeto registrado pela API v1 em ProjectVault (qualquer conta)
         #[ink(message)]
-        pub fn emergency_pause_project(&mut self, project_id: ProjectId, pause: bool) -> Result<(), SafeguardError> {
-            let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
-
+        pub fn migrate_legacy_project(&mut self, project_id: ProjectId) -> Result<(), SafeguardError> {
+            self._ensure_not_paused()?;
+            self.reentrancy_guard.start()?;
+            let result = self._migrate_legacy_project(project_id);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        /// Projeto sem ProjectVault, ainda no modelo v1
+        #[ink(message)]
+        pub fn is_legacy_project(&self, project_id: ProjectId) -> bool {
+            self.project_owners.contains(project_id) && !self.project_vaults.contains(project_id)
+        }
+
+        fn _migrate_legacy_project(&mut self, project_id: ProjectId) -> Result<(), SafeguardError> {
+            let owner = self.project_owners.get(project_id).ok_or(SafeguardError::ProjectNotFound)?;
+            if self.project_vaults.contains(project_id) {
+                return Err(SafeguardError::ProjectAlreadyMigrated);
+            }
+            // Uma votação v1 aberta ainda recebe votos; migrar agora descartaria a apuração
+            if self.project_vote_end_times.get(project_id).unwrap_or(0) > self.env().block_timestamp() {
+                return Err(SafeguardError::VotingInProgress);
+            }
+            let creation_timestamp = self.project_creation_timestamps.get(project_id).unwrap_or(0);
+            let pair_psp22 = self.project_psp22_pairs.get(project_id).unwrap_or(None);
+
+            // A votação v2 passa a ser a fonte da verdade; a apuração v1 sai no evento
+            let legacy_yes_votes = self.project_vote_yes_counts.get(project_id).unwrap_or(0);
+            let legacy_no_votes = self.project_vote_no_counts.get(project_id).unwrap_or(0);
+            self.project_vote_yes_counts.remove(project_id);
+            self.project_vote_no_counts.remove(project_id);
+            self.project_vote_end_times.remove(project_id);
+            self.project_withdraw_statuses.remove(project_id);
+
+            // `project_guarantees` não vira colateral: a API v1 nunca custodiou tokens,
+            // então não há saldo no core que lastreie esses registros
+            self._init_project_vault(Self::_legacy_project_vault(project_id, owner, pair_psp22, creation_timestamp))?;
+            self.env().emit_event(LegacyProjectMigrated {
+                project_id,
+                owner,
+                legacy_yes_votes,
+                legacy_no_votes,
+            });
+            Ok(())
+        }
+
+        fn _legacy_project_vault(
+            project_id: ProjectId,
+            owner: AccountId,
+            pair_psp22: Option<AccountId>,
+            creation_timestamp: u64,
+        ) -> ProjectVault {
+            ProjectVault {
+                project_id,
+                owner,
+                name: Vec::new(),
+                metadata_uri: Vec::new(),
+                token_contract: pair_psp22.unwrap_or(AccountId::from([0u8; 32])),
+                treasury_address: owner,
+                creation_timestamp,
+                status: ProjectStatus::Active,
+                total_lunes_collateral: 0,
+                total_lusdt_collateral: 0,
+                total_other_collateral: 0,
+                current_voting_id: None,
+                last_annual_vote: 0,
+                liquidation_timestamp: None,
+                claims_deadline: None,
+            }
+        }
+
+        /// Cofre do projeto, migrando na hora projetos ainda no modelo v1
+        #[cfg(feature = "legacy-api")]
+        fn _legacy_adapter_vault(&mut self, project_id: ProjectId) -> Result<ProjectVault, SafeguardError> {
+            if !self.project_vaults.contains(project_id) {
+                self.reentrancy_guard.start()?;
+                let result = self._migrate_legacy_project(project_id);
+                self.reentrancy_guard.end();
+                result?;
+            }
+            self.project_vaults.get(project_id).ok_or(SafeguardError::ProjectNotFound)
+        }
+
+        /// Checagens comuns da API v1: projeto existe, não está pausado e o caller é o dono
+        #[cfg(feature = "legacy-api")]
+        fn _ensure_legacy_owner(&self, project_id: ProjectId) -> Result<(), SafeguardError> {
+            let owner = self.project_owners.get(project_id).ok_or(SafeguardError::InvalidInput)?;
+            if !self.project_statuses.get(project_id).unwrap_or(true) {
+                return Err(SafeguardError::ProjectPaused);
+            }
+            if owner != self.env().caller() {
+                return Err(SafeguardError::NotOwner);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn emergency_pause_project(&mut self, project_id: ProjectId, pause: bool) -> Result<(), SafeguardError> {
+            self._ensure_role(Role::Pauser)?;
+
             self.project_statuses.insert(project_id, &!pause);
 
             // TODO: Emit a ProjectPaused/Unpaused event
@@ -1514,10 +4130,13 @@ pub mod safeguard {
             Ok(())
         }
 
+        /// API v1: voto na votação v2 aberta do projeto, com o peso do snapshot
+        #[cfg(feature = "legacy-api")]
         #[ink(message, selector = 0x34567890)]
         pub fn vote(&mut self, project_id: ProjectId, vote_value: bool) -> Result<(), SafeguardError> {
-            // Validate inputs
-            validation::validate_account(self.env().caller())?;
+            self._ensure_module_active(PauseModule::Voting)?;
+            let caller = self.env().caller();
+            validation::validate_account(caller)?;
             
             // Check if project exists
             if !self.project_owners.contains(project_id) {
@@ -1529,53 +4148,73 @@ pub mod safeguard {
                 return Err(SafeguardError::ProjectPaused);
             }
             
-            // Check if user has already voted
-            if self.project_votes.contains(&(project_id, self.env().caller())) {
-                return Err(SafeguardError::AlreadyVoted);
-            }
+            let vault = self._legacy_adapter_vault(project_id)?;
+            self.reentrancy_guard.start()?;
+            let result = self._vote_on_proposal(project_id, vote_value);
+            self.reentrancy_guard.end();
+            result?;
             
-            // Execute vote logic
-            self._vote_internal(project_id, vote_value)
+            // Evento legado para indexadores da API v1
+            self.env().emit_event(VoteCast {
+                project_id,
+                voter: caller,
+                vote_id: vault.current_voting_id.unwrap_or_default(),
+                vote_value,
+            });
+            Ok(())
         }
 
+        /// API v1: finaliza a votação v2 encerrada do projeto (dono do projeto)
+        #[cfg(feature = "legacy-api")]
         #[ink(message)]
         pub fn vote_finish(&mut self, project_id: ProjectId, balance_per_lunes: Balance) -> Result<(), SafeguardError> {
-            // Validate inputs
+            self._ensure_module_active(PauseModule::Voting)?;
             validation::validate_account(self.env().caller())?;
             validation::validate_amount(balance_per_lunes)?;
+            self._ensure_legacy_owner(project_id)?;
             
-            // Check if project exists
-            if !self.project_owners.contains(project_id) {
-                return Err(SafeguardError::InvalidInput);
-            }
-            
-            // Check if project is paused
-            if !self.project_statuses.get(project_id).unwrap_or(true) {
-                return Err(SafeguardError::ProjectPaused);
-            }
+            let vault = self._legacy_adapter_vault(project_id)?;
+            let voting_id = vault.current_voting_id.ok_or(SafeguardError::NoActiveVoting)?;
+            self._finalize_voting(project_id)?;
             
-            // Execute finish vote logic
-            self._finish_vote_internal(project_id, balance_per_lunes)
+            let voting_info = self.voting_infos.get(voting_id).ok_or(SafeguardError::VotingNotFound)?;
+            self.env().emit_event(VotingFinished {
+                project_id,
+                vote_id: voting_id,
+                yes_votes: voting_info.yes_votes,
+                no_votes: voting_info.no_votes,
+            });
+            Ok(())
         }
 
+        /// API v1: abre os claims de um projeto rejeitado na votação v2 (dono do projeto).
+        /// Os claims v2 liberam a posição inteira, então `amount` precisa ser o colateral LUNES
+        /// total do cofre; saques parciais não existem no modelo v2.
+        #[cfg(feature = "legacy-api")]
         #[ink(message)]
         pub fn withdraw(&mut self, project_id: ProjectId, amount: Balance) -> Result<(), SafeguardError> {
-            // Validate inputs
+            self._ensure_module_active(PauseModule::Withdrawals)?;
             validation::validate_account(self.env().caller())?;
-            validation::validate_amount(amount)?;
+            self._ensure_legacy_owner(project_id)?;
             
-            // Check if project exists
-            if !self.project_owners.contains(project_id) {
-                return Err(SafeguardError::InvalidInput);
+            let mut vault = self._legacy_adapter_vault(project_id)?;
+            match vault.status {
+                ProjectStatus::Rejected => {}
+                ProjectStatus::ClaimsOpen => return Err(SafeguardError::WithdrawalInProgress),
+                _ => return Err(SafeguardError::ClaimsNotOpen),
             }
-            
-            // Check if project is paused
-            if !self.project_statuses.get(project_id).unwrap_or(true) {
-                return Err(SafeguardError::ProjectPaused);
+            if amount != vault.total_lunes_collateral {
+                return Err(SafeguardError::InvalidAmount);
             }
+            vault.status = ProjectStatus::ClaimsOpen;
+            self.project_vaults.insert(project_id, &vault);
             
-            // Execute withdraw logic
-            self._withdraw_internal(project_id, amount)
+            self.env().emit_event(WithdrawalActivated {
+                project_id,
+                vote_id: self.project_liquidation_votings.get(project_id).unwrap_or_default(),
+                balance_per_lunes: amount,
+            });
+            Ok(())
         }
 
         #[ink(message)]
@@ -1591,19 +4230,75 @@ pub mod safeguard {
             // Validate new owner
             validation::validate_account(new_owner)?;
             
-            // Transfer ownership
-            self.project_owners.insert(project_id, &new_owner);
+            // Takes effect only when the new owner accepts
+            let pending = self._new_pending_ownership(new_owner, None);
+            self.pending_project_owners.insert(project_id, &pending);
+            
+            self.env().emit_event(OwnershipTransferProposed {
+                project_id: Some(project_id),
+                new_owner,
+                proposed_by: caller,
+                expires_at: pending.expires_at,
+            });
+            
+            Ok(())
+        }
+
+        /// Accept a pending project ownership transfer (new owner, before expiry)
+        #[ink(message)]
+        pub fn accept_project_ownership(&mut self, project_id: ProjectId) -> Result<(), SafeguardError> {
+            let pending = self.pending_project_owners.get(project_id)
+                .ok_or(SafeguardError::NoPendingOwnership)?;
+            self._check_pending_acceptance(&pending)?;
+            
+            let previous_owner = self.project_owners.get(project_id).ok_or(SafeguardError::InvalidInput)?;
+            self.pending_project_owners.remove(project_id);
+            
+            // Keep both ownership records in sync
+            self.project_owners.insert(project_id, &pending.new_owner);
+            if let Some(mut vault) = self.project_vaults.get(project_id) {
+                vault.owner = pending.new_owner;
+                self.project_vaults.insert(project_id, &vault);
+            }
             
-            // Emit ownership transfer event
             self.env().emit_event(ProjectOwnershipTransferred {
                 project_id,
-                previous_owner: caller,
-                new_owner,
+                previous_owner,
+                new_owner: pending.new_owner,
+            });
+            
+            Ok(())
+        }
+
+        /// Cancel a pending project ownership transfer (current project owner)
+        #[ink(message)]
+        pub fn cancel_project_ownership_transfer(&mut self, project_id: ProjectId) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
+            let owner = self.project_owners.get(project_id).ok_or(SafeguardError::InvalidInput)?;
+            if owner != caller {
+                return Err(SafeguardError::NotOwner);
+            }
+            
+            let pending = self.pending_project_owners.get(project_id)
+                .ok_or(SafeguardError::NoPendingOwnership)?;
+            self.pending_project_owners.remove(project_id);
+            
+            self.env().emit_event(OwnershipTransferCancelled {
+                project_id: Some(project_id),
+                new_owner: pending.new_owner,
+                cancelled_by: caller,
             });
             
             Ok(())
         }
 
+        /// Get the pending ownership transfer of a project
+        #[ink(message)]
+        pub fn get_pending_project_owner(&self, project_id: ProjectId) -> Option<PendingOwnership> {
+            self.pending_project_owners.get(project_id)
+        }
+
+        #[cfg(feature = "legacy-api")]
         #[ink(message)]
         pub fn get_withdrawal(&self, project_id: ProjectId, account: AccountId) -> Result<Balance, SafeguardError> {
             // Validate inputs
@@ -1619,6 +4314,7 @@ pub mod safeguard {
             Ok(withdrawal)
         }
 
+        #[cfg(feature = "legacy-api")]
         #[ink(message)]
         pub fn get_user_vote(&self, project_id: ProjectId, account: AccountId) -> Result<Option<u64>, SafeguardError> {
             // Validate inputs
@@ -1630,10 +4326,10 @@ pub mod safeguard {
             }
             
             // Get user vote
-            let vote = self.project_votes.get(&(project_id, account));
-            Ok(vote)
+            Ok(self._legacy_vote_of(project_id, account))
         }
 
+        #[cfg(feature = "legacy-api")]
         #[ink(message)]
         pub fn has_voted(&self, project_id: ProjectId, account: AccountId) -> Result<bool, SafeguardError> {
             // Validate inputs
@@ -1645,11 +4341,23 @@ pub mod safeguard {
             }
             
             // Check if user has voted
-            Ok(self.project_votes.contains(&(project_id, account)))
+            Ok(self._legacy_vote_of(project_id, account).is_some())
         }
 
+        /// Votação v2 aberta em que a conta votou; projetos não migrados usam o registro v1
+        #[cfg(feature = "legacy-api")]
+        fn _legacy_vote_of(&self, project_id: ProjectId, account: AccountId) -> Option<u64> {
+            match self.project_vaults.get(project_id) {
+                Some(vault) => vault.current_voting_id.filter(|voting_id| self.ballots.contains((*voting_id, account))),
+                None => self.project_votes.get((project_id, account)),
+            }
+        }
+
+        /// API v1: `active = true` abre a votação anual v2 do projeto (dono do projeto)
+        #[cfg(feature = "legacy-api")]
         #[ink(message)]
         pub fn vote_active(&mut self, project_id: ProjectId, active: bool, balance_min: Balance) -> Result<(), SafeguardError> {
+            self._ensure_module_active(PauseModule::Voting)?;
             let caller = self.env().caller();
             let owner = self.project_owners.get(project_id).ok_or(SafeguardError::InvalidInput)?;
             
@@ -1657,9 +4365,31 @@ pub mod safeguard {
             if owner != caller {
                 return Err(SafeguardError::NotOwner);
             }
+            validation::validate_amount(balance_min)?;
+            
+            // Votações v2 não são canceladas; desativar só vale sem votação aberta
+            let vault = self._legacy_adapter_vault(project_id)?;
+            self.project_permission_balances.insert(project_id, &balance_min);
+            if !active {
+                if vault.current_voting_id.is_some() {
+                    return Err(SafeguardError::VotingInProgress);
+                }
+                return Ok(());
+            }
             
-            // Execute internal activation logic
-            self._activate_vote_internal(project_id, active, balance_min)
+            self.reentrancy_guard.start()?;
+            let result = self._start_annual_voting(project_id);
+            self.reentrancy_guard.end();
+            let voting_id = result?;
+            
+            let voting_info = self.voting_infos.get(voting_id).ok_or(SafeguardError::VotingNotFound)?;
+            self.env().emit_event(VotingActivated {
+                project_id,
+                vote_id: voting_id,
+                minimum_balance: balance_min,
+                end_time: voting_info.end_timestamp,
+            });
+            Ok(())
         }
 
         #[ink(message, selector = 0x7890abcd)]
@@ -1667,13 +4397,26 @@ pub mod safeguard {
             self.next_project_id
         }
 
+        #[cfg(feature = "legacy-api")]
         #[ink(message, selector = 0x45678901)]
         pub fn get_project_info(&self, project_id: ProjectId) -> Option<InfoContract> {
             let owner = self.project_owners.get(project_id)?;
-            let data_vote_end = self.project_vote_end_times.get(project_id).unwrap_or(0);
-            let qtd_vote_yes = self.project_vote_yes_counts.get(project_id).unwrap_or(0);
-            let qtd_vote_no = self.project_vote_no_counts.get(project_id).unwrap_or(0);
-            let status_withdraw = self.project_withdraw_statuses.get(project_id).unwrap_or(false);
+            let (data_vote_end, qtd_vote_yes, qtd_vote_no, status_withdraw) = match self.project_vaults.get(project_id) {
+                // Projetos v2 (inclusive migrados): a votação do cofre é a fonte da verdade
+                Some(vault) => {
+                    let voting = vault.current_voting_id
+                        .or_else(|| self.project_liquidation_votings.get(project_id))
+                        .and_then(|voting_id| self.voting_infos.get(voting_id));
+                    let (end, yes, no) = voting.map(|v| (v.end_timestamp, v.yes_votes, v.no_votes)).unwrap_or_default();
+                    (end, yes, no, vault.status == ProjectStatus::ClaimsOpen)
+                }
+                None => (
+                    self.project_vote_end_times.get(project_id).unwrap_or(0),
+                    self.project_vote_yes_counts.get(project_id).unwrap_or(0),
+                    self.project_vote_no_counts.get(project_id).unwrap_or(0),
+                    self.project_withdraw_statuses.get(project_id).unwrap_or(false),
+                ),
+            };
             let status = self.project_statuses.get(project_id).unwrap_or(false);
             let balance_permission = self.project_permission_balances.get(project_id).unwrap_or(0);
             let pair_psp22 = self.project_psp22_pairs.get(project_id).unwrap_or(None);
@@ -1709,131 +4452,113 @@ pub mod safeguard {
             // Validate new owner
             validation::validate_account(new_owner)?;
             
-            // Check ownership
-            if self.env().caller() != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
-            
-            // Store previous owner for event emission
-            let previous_owner = self.contract_owner;
-            
-            // Transfer ownership
-            self.contract_owner = new_owner;
+            // Direct transfer only during bootstrap; afterwards via timelock
+            self._ensure_bootstrap_admin()?;
             
-            // Emit ownership transfer event
-            self.env().emit_event(OwnershipTransferred {
-                previous_owner: Some(previous_owner),
-                new_owner,
-            });
+            // A signer hands over its own seat; anyone else can only add a signer
+            let caller = self.env().caller();
+            let replaces = self._is_council_signer(caller).then_some(caller);
+            self._propose_contract_ownership(new_owner, replaces);
+            Ok(())
+        }
+
+        /// Accept a pending contract ownership transfer (new owner, before expiry)
+        #[ink(message)]
+        pub fn accept_contract_ownership(&mut self) -> Result<(), SafeguardError> {
+            let pending = self.pending_contract_owner.get_or_default().ok_or(SafeguardError::NoPendingOwnership)?;
+            self._check_pending_acceptance(&pending)?;
             
+            self._transfer_contract_ownership(pending.new_owner, pending.replaces)?;
+            self.pending_contract_owner.set(&None);
             Ok(())
         }
 
-        fn _vote_internal(&mut self, project_id: ProjectId, vote_value: bool) -> Result<(), SafeguardError> {
+        /// Cancel a pending contract ownership transfer (bootstrap admin or Admin)
+        #[ink(message)]
+        pub fn cancel_contract_ownership_transfer(&mut self) -> Result<(), SafeguardError> {
             let caller = self.env().caller();
-            let project_id_value = self.project_ids.get(project_id).unwrap_or(0);
-
-            // Record vote
-            self.project_votes.insert((project_id, caller), &project_id_value);
-
-            // Update vote counts with overflow protection
-            if vote_value {
-                let current_yes_votes = self.project_vote_yes_counts.get(project_id).unwrap_or(0);
-                self.project_vote_yes_counts.insert(project_id, &safe_math::safe_add(current_yes_votes, 1)?);
-            } else {
-                let current_no_votes = self.project_vote_no_counts.get(project_id).unwrap_or(0);
-                self.project_vote_no_counts.insert(project_id, &safe_math::safe_add(current_no_votes, 1)?);
+            if self.bootstrap_admin.get_or_default() != Some(caller) {
+                self._ensure_role(Role::Admin)?;
             }
-
-            // Emit vote event
-            self.env().emit_event(VoteCast {
-                project_id,
-                voter: caller,
-                vote_id: project_id_value,
-                vote_value,
+            
+            let pending = self.pending_contract_owner.get_or_default().ok_or(SafeguardError::NoPendingOwnership)?;
+            self.pending_contract_owner.set(&None);
+            
+            self.env().emit_event(OwnershipTransferCancelled {
+                project_id: None,
+                new_owner: pending.new_owner,
+                cancelled_by: caller,
             });
-
+            
             Ok(())
         }
 
-        fn _finish_vote_internal(&mut self, project_id: ProjectId, _balance_per_lunes: Balance) -> Result<(), SafeguardError> {
-            let caller = self.env().caller();
-            let owner = self.project_owners.get(project_id).ok_or(SafeguardError::InvalidInput)?;
-
-            // Ensure the caller is the owner of this specific project
-            if owner != caller {
-                return Err(SafeguardError::NotOwner);
-            }
-
-            // Set vote end time to current timestamp
-            let current_time = self.env().block_timestamp();
-            self.project_vote_end_times.insert(project_id, &current_time);
+        /// Get the pending contract ownership transfer
+        #[ink(message)]
+        pub fn get_pending_contract_owner(&self) -> Option<PendingOwnership> {
+            self.pending_contract_owner.get_or_default()
+        }
 
-            // Get vote counts for the event
-            let yes_votes = self.project_vote_yes_counts.get(project_id).unwrap_or(0);
-            let no_votes = self.project_vote_no_counts.get(project_id).unwrap_or(0);
-            
-            // Emit vote finished event
-            self.env().emit_event(VotingFinished {
-                project_id,
-                vote_id: project_id,
-                yes_votes,
-                no_votes,
+        fn _propose_contract_ownership(&mut self, new_owner: AccountId, replaces: Option<AccountId>) {
+            let pending = self._new_pending_ownership(new_owner, replaces);
+            self.env().emit_event(OwnershipTransferProposed {
+                project_id: None,
+                new_owner,
+                proposed_by: pending.proposed_by,
+                expires_at: pending.expires_at,
             });
+            self.pending_contract_owner.set(&Some(pending));
+        }
 
-            Ok(())
+        fn _new_pending_ownership(&self, new_owner: AccountId, replaces: Option<AccountId>) -> PendingOwnership {
+            PendingOwnership {
+                new_owner,
+                proposed_by: self.env().caller(),
+                expires_at: self.env().block_timestamp().saturating_add(self.ownership_transfer_window.get_or_default()),
+                replaces,
+            }
         }
 
-        fn _withdraw_internal(&mut self, project_id: ProjectId, amount: Balance) -> Result<(), SafeguardError> {
-            let caller = self.env().caller();
-            let owner = self.project_owners.get(project_id).ok_or(SafeguardError::InvalidInput)?;
-            
-            // Ensure the caller is the owner of this specific project
-            if owner != caller {
+        fn _check_pending_acceptance(&self, pending: &PendingOwnership) -> Result<(), SafeguardError> {
+            if self.env().caller() != pending.new_owner {
                 return Err(SafeguardError::NotOwner);
             }
-            
-            // Check if withdrawal is already in progress
-            if self.project_withdraw_statuses.get(project_id).unwrap_or(false) {
-                return Err(SafeguardError::WithdrawalInProgress);
+            if self.env().block_timestamp() > pending.expires_at {
+                return Err(SafeguardError::OwnershipTransferExpired);
             }
-            
-            // Mark withdrawal as in progress
-            self.project_withdraw_statuses.insert(project_id, &true);
-            
-            // Update withdrawal amount
-            let current_withdrawal = self.project_withdrawals.get((project_id, caller)).unwrap_or(0);
-            let new_withdrawal = safe_math::safe_add_balance(current_withdrawal, amount)?;
-            self.project_withdrawals.insert((project_id, caller), &new_withdrawal);
-            
-            // Emit withdrawal event
-            self.env().emit_event(WithdrawalActivated {
-                project_id,
-                vote_id: project_id,
-                balance_per_lunes: amount,
-            });
-            
             Ok(())
         }
 
-        fn _activate_vote_internal(&mut self, project_id: ProjectId, active: bool, balance_min: Balance) -> Result<(), SafeguardError> {
-            // Validate minimum balance
-            validation::validate_amount(balance_min)?;
-            
-            // Set project status
-            self.project_statuses.insert(project_id, &active);
+        /// Rotates `replaces` out for the new owner, or adds it as a signer; the threshold is kept
+        fn _transfer_contract_ownership(&mut self, new_owner: AccountId, replaces: Option<AccountId>) -> Result<(), SafeguardError> {
+            if self._is_council_signer(new_owner) || self.is_guardian(new_owner) {
+                return Err(SafeguardError::InvalidInput);
+            }
             
-            // Set permission balance threshold
-            self.project_permission_balances.insert(project_id, &balance_min);
+            // The replaced signer may have been removed meanwhile: fall back to adding
+            let mut council = self.council.get_or_default();
+            let seat = replaces.and_then(|old| council.signers.iter().position(|signer| *signer == old));
+            let previous_owner = match seat {
+                Some(index) => {
+                    let previous = council.signers[index];
+                    council.signers[index] = new_owner;
+                    Some(previous)
+                }
+                None => {
+                    if council.signers.len() >= MAX_COUNCIL_SIGNERS {
+                        return Err(SafeguardError::InvalidInput);
+                    }
+                    council.signers.push(new_owner);
+                    None
+                }
+            };
+            self.council.set(&council);
             
-            // Emit vote activation event
-            self.env().emit_event(VotingActivated {
-                project_id,
-                vote_id: project_id,
-                minimum_balance: balance_min,
-                end_time: self.env().block_timestamp(),
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner,
             });
-            
+            self._emit_council_updated();
             Ok(())
         }
 
@@ -1846,12 +4571,18 @@ pub mod safeguard {
             decimals: u8,
             min_guarantee_amount: Balance,
         ) -> Result<TokenId, SafeguardError> {
-            // Only contract owner can add tokens
-            let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
+            // Direct listing only during bootstrap; afterwards via timelock or DAO
+            self._ensure_protocol_admin()?;
+            self._add_supported_token(contract_address, symbol, decimals, min_guarantee_amount)
+        }
 
+        fn _add_supported_token(
+            &mut self,
+            contract_address: AccountId,
+            symbol: [u8; 8],
+            decimals: u8,
+            min_guarantee_amount: Balance,
+        ) -> Result<TokenId, SafeguardError> {
             validation::validate_account(contract_address)?;
             validation::validate_amount(min_guarantee_amount)?;
 
@@ -1863,6 +4594,7 @@ pub mod safeguard {
                 is_active: true,
                 min_guarantee_amount,
                 price_feed_id: None,
+                selector_scheme: SelectorScheme::TraitNamespaced,
             };
 
             self.supported_tokens.insert(token_id, &token_info);
@@ -1878,13 +4610,47 @@ pub mod safeguard {
             Ok(token_id)
         }
 
+        /// Define a convenção de seletores PSP22 de um token (admin de bootstrap; depois via timelock ou DAO)
+        #[ink(message)]
+        pub fn set_token_selector_scheme(&mut self, token_id: TokenId, scheme: SelectorScheme) -> Result<(), SafeguardError> {
+            self._ensure_protocol_admin()?;
+            self._validate_selector_scheme(token_id, &scheme)?;
+            self._set_token_selector_scheme(token_id, scheme)
+        }
+
+        fn _validate_selector_scheme(&self, token_id: TokenId, scheme: &SelectorScheme) -> Result<(), SafeguardError> {
+            if !self.supported_tokens.contains(token_id) {
+                return Err(SafeguardError::TokenNotSupported);
+            }
+            // Seletores customizados precisam ser distintos entre si
+            if let SelectorScheme::Custom(selectors) = scheme {
+                if selectors.transfer == selectors.transfer_from
+                    || selectors.transfer == selectors.balance_of
+                    || selectors.transfer_from == selectors.balance_of
+                {
+                    return Err(SafeguardError::InvalidInput);
+                }
+            }
+            Ok(())
+        }
+
+        fn _set_token_selector_scheme(&mut self, token_id: TokenId, scheme: SelectorScheme) -> Result<(), SafeguardError> {
+            let mut token_info = self.supported_tokens.get(token_id).ok_or(SafeguardError::TokenNotSupported)?;
+            token_info.selector_scheme = scheme;
+            self.supported_tokens.insert(token_id, &token_info);
+            self.env().emit_event(TokenSelectorSchemeUpdated { token_id, scheme });
+            Ok(())
+        }
+
         /// Add guarantee to a project using a specific token
+        #[cfg(feature = "legacy-api")]
         #[ink(message)]
         pub fn set_balance_per_lunes(
             &mut self,
             project_id: ProjectId,
             _balance_per_lunes: Balance,
         ) -> Result<(), SafeguardError> {
+            self._ensure_not_paused()?;
             let _caller = self.env().caller();
             
             // Validate inputs
@@ -1908,6 +4674,19 @@ pub mod safeguard {
             project_id: ProjectId,
             token_id: TokenId,
             amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            self._ensure_module_active(PauseModule::Deposits)?;
+            self.reentrancy_guard.start()?;
+            let result = self._add_guarantee(project_id, token_id, amount);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _add_guarantee(
+            &mut self,
+            project_id: ProjectId,
+            token_id: TokenId,
+            amount: Balance,
         ) -> Result<(), SafeguardError> {
             let caller = self.env().caller();
             
@@ -1932,20 +4711,22 @@ pub mod safeguard {
                 return Err(SafeguardError::InvalidAmount);
             }
             
+            self._check_compliance(project_id, caller, ComplianceAction::AddGuarantee, amount)?;
+            
             // Update guarantee mappings
             let current_guarantee = self.token_guarantees.get((project_id, token_id, caller)).unwrap_or(0);
             let new_guarantee = safe_math::safe_add_balance(current_guarantee, amount)?;
-            self.token_guarantees.insert((project_id, token_id, caller), &new_guarantee);
+            self._set_user_guarantee(project_id, token_id, caller, new_guarantee);
             
             // Update project total guarantees
             let current_total = self.project_total_guarantees.get((project_id, token_id)).unwrap_or(0);
             let new_total = safe_math::safe_add_balance(current_total, amount)?;
-            self.project_total_guarantees.insert((project_id, token_id), &new_total);
+            self._set_project_total_guarantee(project_id, token_id, new_total);
             
             // Update user token balance (for tracking)
             let current_balance = self.user_token_balances.get((caller, token_id)).unwrap_or(0);
             let new_balance = safe_math::safe_add_balance(current_balance, amount)?;
-            self.user_token_balances.insert((caller, token_id), &new_balance);
+            self._set_user_token_balance(caller, token_id, new_balance);
             
             self.env().emit_event(GuaranteeDeposited {
                 project_id,
@@ -1955,9 +4736,11 @@ pub mod safeguard {
             });
             
             // Update project score after adding guarantee
-            let _ = self.update_project_score(project_id);
+            self._refresh_project_score(project_id);
             
-            Ok(())
+            // Take custody once the deposit is recorded
+            self._project_deposit(project_id, token_id, caller, amount)
+
         }
 
         /// Donate tokens to a project's guarantee pool
@@ -1968,8 +4751,21 @@ pub mod safeguard {
             token_id: TokenId,
             amount: Balance,
         ) -> Result<(), SafeguardError> {
-            let caller = self.env().caller();
-            
+            self._ensure_module_active(PauseModule::Deposits)?;
+            self.reentrancy_guard.start()?;
+            let result = self._donate_to_guarantee(project_id, token_id, amount);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _donate_to_guarantee(
+            &mut self,
+            project_id: ProjectId,
+            token_id: TokenId,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
+            
             // Validate inputs
             validation::validate_amount(amount)?;
             
@@ -1989,7 +4785,11 @@ pub mod safeguard {
             // Update project total guarantees (donations go directly to project pool)
             let current_total = self.project_total_guarantees.get(&(project_id, token_id)).unwrap_or(0);
             let new_total = safe_math::safe_add_balance(current_total, amount)?;
-            self.project_total_guarantees.insert(&(project_id, token_id), &new_total);
+            self._set_project_total_guarantee(project_id, token_id, new_total);
+            
+            // Parcela doada, rateada entre os depositantes no escape hatch
+            let donated = self.project_donations.get((project_id, token_id)).unwrap_or(0);
+            self.project_donations.insert((project_id, token_id), &donated.saturating_add(amount));
             
             self.env().emit_event(DonationReceived {
                 project_id,
@@ -1999,9 +4799,9 @@ pub mod safeguard {
             });
             
             // Update project score after donation
-            let _ = self.update_project_score(project_id);
+            self._refresh_project_score(project_id);
             
-            Ok(())
+            self._project_deposit(project_id, token_id, caller, amount)
         }
 
         /// Withdraw guarantee from a project
@@ -2011,6 +4811,19 @@ pub mod safeguard {
             project_id: ProjectId,
             token_id: TokenId,
             amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            self._ensure_module_active(PauseModule::Withdrawals)?;
+            self.reentrancy_guard.start()?;
+            let result = self._withdraw_guarantee(project_id, token_id, amount);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _withdraw_guarantee(
+            &mut self,
+            project_id: ProjectId,
+            token_id: TokenId,
+            amount: Balance,
         ) -> Result<(), SafeguardError> {
             let caller = self.env().caller();
             
@@ -2022,6 +4835,13 @@ pub mod safeguard {
                 return Err(SafeguardError::InvalidInput);
             }
             
+            // Após votação, liquidação ou abertura de claims o colateral pertence aos holders
+            if let Some(vault) = self.project_vaults.get(project_id) {
+                if !matches!(vault.status, ProjectStatus::Active | ProjectStatus::Approved) {
+                    return Err(SafeguardError::ProjectNotActive);
+                }
+            }
+            
             // Check user's guarantee balance
             let current_guarantee = self.token_guarantees.get((project_id, token_id, caller)).unwrap_or(0);
             if current_guarantee < amount {
@@ -2030,39 +4850,24 @@ pub mod safeguard {
             
             // Update guarantee mappings
             let new_guarantee = safe_math::safe_sub_balance(current_guarantee, amount)?;
-            if new_guarantee == 0 {
-                self.token_guarantees.remove((project_id, token_id, caller));
-            } else {
-                self.token_guarantees.insert((project_id, token_id, caller), &new_guarantee);
-            }
+            self._set_user_guarantee(project_id, token_id, caller, new_guarantee);
             
             // Update project total guarantees
             let current_total = self.project_total_guarantees.get((project_id, token_id)).unwrap_or(0);
             let new_total = safe_math::safe_sub_balance(current_total, amount)?;
-            if new_total == 0 {
-                self.project_total_guarantees.remove((project_id, token_id));
-            } else {
-                self.project_total_guarantees.insert((project_id, token_id), &new_total);
-            }
+            self._set_project_total_guarantee(project_id, token_id, new_total);
             
             // Update user token balance
             let current_balance = self.user_token_balances.get((caller, token_id)).unwrap_or(0);
             let new_balance = safe_math::safe_sub_balance(current_balance, amount)?;
-            if new_balance == 0 {
-                self.user_token_balances.remove((caller, token_id));
-            } else {
-                self.user_token_balances.insert((caller, token_id), &new_balance);
-            }
+            self._set_user_token_balance(caller, token_id, new_balance);
+            self._release_vault_collateral(project_id, token_id, amount);
             
-            self.env().emit_event(GuaranteeWithdrawn {
-                project_id,
-                token_id,
-                account: caller,
-                amount,
-            });
+            // GuaranteeWithdrawn é emitido quando a saída couber no limite do token
+            self._send_outflow(OutflowKind::Withdrawal(project_id), token_id, caller, amount)?;
             
             // Update project score after withdrawal
-            let _ = self.update_project_score(project_id);
+            self._refresh_project_score(project_id);
             
             Ok(())
         }
@@ -2094,6 +4899,141 @@ pub mod safeguard {
             self.project_total_guarantees.get((project_id, token_id)).unwrap_or(0)
         }
 
+        /// Get user's guarantee balance at the end of a past block
+        #[ink(message)]
+        pub fn get_user_guarantee_at(
+            &self,
+            project_id: ProjectId,
+            token_id: TokenId,
+            account: AccountId,
+            block: u64,
+        ) -> Balance {
+            self._checkpoint_at(&CheckpointKey::UserGuarantee(project_id, token_id, account), block)
+        }
+
+        /// Get total guarantees for a project and token at the end of a past block
+        #[ink(message)]
+        pub fn get_project_total_guarantee_at(
+            &self,
+            project_id: ProjectId,
+            token_id: TokenId,
+            block: u64,
+        ) -> Balance {
+            self._checkpoint_at(&CheckpointKey::ProjectTotal(project_id, token_id), block)
+        }
+
+        /// Store a user's guarantee balance and checkpoint it at the current block
+        fn _set_user_guarantee(&mut self, project_id: ProjectId, token_id: TokenId, account: AccountId, amount: Balance) {
+            if amount == 0 {
+                self.token_guarantees.remove((project_id, token_id, account));
+            } else {
+                self.token_guarantees.insert((project_id, token_id, account), &amount);
+                self._index_export_key(ExportKind::Guarantees, ExportKey::Guarantee(project_id, token_id, account));
+            }
+            self._write_checkpoint(CheckpointKey::UserGuarantee(project_id, token_id, account), amount);
+        }
+
+        /// Store a project's total guarantee and checkpoint it at the current block
+        fn _set_project_total_guarantee(&mut self, project_id: ProjectId, token_id: TokenId, amount: Balance) {
+            if amount == 0 {
+                self.project_total_guarantees.remove((project_id, token_id));
+            } else {
+                self.project_total_guarantees.insert((project_id, token_id), &amount);
+            }
+            self._write_checkpoint(CheckpointKey::ProjectTotal(project_id, token_id), amount);
+        }
+
+        /// Tira do cofre o colateral que saiu; os claims de liquidação são rateados sobre esses totais
+        fn _release_vault_collateral(&mut self, project_id: ProjectId, token_id: TokenId, amount: Balance) {
+            let Some(mut vault) = self.project_vaults.get(project_id) else {
+                return;
+            };
+            let total = if Some(token_id) == self.lunes_token_id {
+                &mut vault.total_lunes_collateral
+            } else if Some(token_id) == self.lusdt_token_id {
+                &mut vault.total_lusdt_collateral
+            } else {
+                &mut vault.total_other_collateral
+            };
+            *total = total.saturating_sub(amount);
+            self.project_vaults.insert(project_id, &vault);
+        }
+
+        /// Store a user's balance locked across projects and checkpoint it with the token total
+        fn _set_user_token_balance(&mut self, account: AccountId, token_id: TokenId, amount: Balance) {
+            let previous = self.user_token_balances.get((account, token_id)).unwrap_or(0);
+            if amount == 0 {
+                self.user_token_balances.remove((account, token_id));
+            } else {
+                self.user_token_balances.insert((account, token_id), &amount);
+            }
+            self._write_checkpoint(CheckpointKey::UserTokenBalance(account, token_id), amount);
+
+            let total = self._checkpoint_at(&CheckpointKey::TokenTotal(token_id), u64::MAX);
+            self._write_checkpoint(
+                CheckpointKey::TokenTotal(token_id),
+                total.saturating_sub(previous).saturating_add(amount),
+            );
+        }
+
+        /// Checkpoint the NFT value held by a user in a project, and the project's NFT total
+        #[cfg(feature = "nft-collateral")]
+        fn _checkpoint_nft_value(&mut self, project_id: ProjectId, account: AccountId, value: Balance, deposit: bool) {
+            let user_key = CheckpointKey::UserNftValue(project_id, account);
+            let project_key = CheckpointKey::ProjectNftValue(project_id);
+            let user_value = self._checkpoint_at(&user_key, u64::MAX);
+            let project_value = self._checkpoint_at(&project_key, u64::MAX);
+            if deposit {
+                self._write_checkpoint(user_key, user_value.saturating_add(value));
+                self._write_checkpoint(project_key, project_value.saturating_add(value));
+            } else {
+                self._write_checkpoint(user_key, user_value.saturating_sub(value));
+                self._write_checkpoint(project_key, project_value.saturating_sub(value));
+            }
+        }
+
+        /// Append a checkpoint, overwriting the last one if it belongs to the current block
+        fn _write_checkpoint(&mut self, key: CheckpointKey, amount: Balance) {
+            let block = self.env().block_number() as u64;
+            let count = self.guarantee_checkpoint_counts.get(&key).unwrap_or(0);
+
+            if let Some(last_index) = count.checked_sub(1) {
+                if let Some(last) = self.guarantee_checkpoints.get((key.clone(), last_index)) {
+                    if last.block == block {
+                        self.guarantee_checkpoints.insert((key, last_index), &Checkpoint { block, amount });
+                        return;
+                    }
+                }
+            }
+
+            self.guarantee_checkpoints.insert((key.clone(), count), &Checkpoint { block, amount });
+            self.guarantee_checkpoint_counts.insert(key, &count.saturating_add(1));
+        }
+
+        /// Balance recorded at the end of `block` (binary search over checkpoints)
+        fn _checkpoint_at(&self, key: &CheckpointKey, block: u64) -> Balance {
+            let mut low = 0u32;
+            let mut high = self.guarantee_checkpoint_counts.get(key).unwrap_or(0);
+
+            // Find the first checkpoint strictly after `block`
+            while low < high {
+                let mid = low.saturating_add(high.saturating_sub(low).saturating_div(2));
+                let checkpoint_block = self.guarantee_checkpoints.get((key.clone(), mid))
+                    .map(|c| c.block)
+                    .unwrap_or(u64::MAX);
+                if checkpoint_block > block {
+                    high = mid;
+                } else {
+                    low = mid.saturating_add(1);
+                }
+            }
+
+            match high.checked_sub(1) {
+                Some(index) => self.guarantee_checkpoints.get((key.clone(), index)).map(|c| c.amount).unwrap_or(0),
+                None => 0,
+            }
+        }
+
         /// Get user's total balance for a specific token across all projects
         #[ink(message)]
         pub fn get_user_token_balance(
@@ -2105,6 +5045,7 @@ pub mod safeguard {
         }
 
         /// Add a new supported NFT collection to the vault
+        #[cfg(feature = "nft-collateral")]
         #[ink(message)]
         pub fn add_nft_collection(
             &mut self,
@@ -2114,12 +5055,20 @@ pub mod safeguard {
             base_value: Balance,
             valuation_method: NFTValuationMethod,
         ) -> Result<NFTCollectionId, SafeguardError> {
-            // Only contract owner can add NFT collections
-            let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
+            // Direct listing only during bootstrap; afterwards via timelock or DAO
+            self._ensure_protocol_admin()?;
+            self._add_nft_collection(contract_address, name, symbol, base_value, valuation_method)
+        }
 
+        #[cfg(feature = "nft-collateral")]
+        fn _add_nft_collection(
+            &mut self,
+            contract_address: AccountId,
+            name: [u8; 32],
+            symbol: [u8; 8],
+            base_value: Balance,
+            valuation_method: NFTValuationMethod,
+        ) -> Result<NFTCollectionId, SafeguardError> {
             validation::validate_account(contract_address)?;
             validation::validate_amount(base_value)?;
 
@@ -2148,12 +5097,27 @@ pub mod safeguard {
         }
 
         /// Deposit NFT as guarantee for a project
+        #[cfg(feature = "nft-collateral")]
         #[ink(message)]
         pub fn deposit_nft_guarantee(
             &mut self,
             project_id: ProjectId,
             collection_id: NFTCollectionId,
             nft_token_id: NFTTokenId,
+        ) -> Result<(), SafeguardError> {
+            self._ensure_module_active(PauseModule::Nft)?;
+            self.reentrancy_guard.start()?;
+            let result = self._deposit_nft_guarantee(project_id, collection_id, nft_token_id);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        fn _deposit_nft_guarantee(
+            &mut self,
+            project_id: ProjectId,
+            collection_id: NFTCollectionId,
+            nft_token_id: NFTTokenId,
         ) -> Result<(), SafeguardError> {
             let caller = self.env().caller();
             
@@ -2170,6 +5134,15 @@ pub mod safeguard {
                 return Err(SafeguardError::InvalidInput);
             }
             
+            // Um NFT já custodiado não pode ser registrado de novo, nem em outro projeto
+            if self.nft_custody.contains((collection_id, nft_token_id)) {
+                return Err(SafeguardError::NFTAlreadyDeposited);
+            }
+            let collection = Psp34Client::new(collection_info.contract_address);
+            if collection.owner_of(nft_token_id)? != Some(caller) {
+                return Err(SafeguardError::NotOwner);
+            }
+            
             // Calculate NFT value based on collection's valuation method
             let estimated_value = match collection_info.valuation_method {
                 NFTValuationMethod::FixedValue(_) => collection_info.base_value,
@@ -2185,11 +5158,14 @@ pub mod safeguard {
             
             // Store NFT guarantee
             self.nft_guarantees.insert((project_id, collection_id, caller, nft_token_id), &estimated_value);
+            self.nft_custody.insert((collection_id, nft_token_id), &(project_id, caller));
+            self._index_export_key(ExportKind::Nfts, ExportKey::Nft(project_id, collection_id, caller, nft_token_id));
             
             // Update project NFT guarantee totals
             let current_total = self.project_nft_guarantees.get((project_id, collection_id)).unwrap_or(0);
             let new_total = safe_math::safe_add_balance(current_total, estimated_value)?;
             self.project_nft_guarantees.insert((project_id, collection_id), &new_total);
+            self._checkpoint_nft_value(project_id, caller, estimated_value, true);
             
             // Update user NFT deposit count
             let current_count = self.user_nft_deposits.get((caller, collection_id)).unwrap_or(0);
@@ -2205,12 +5181,19 @@ pub mod safeguard {
             });
             
             // Update project score after NFT deposit
-            let _ = self.update_project_score(project_id);
+            self._refresh_project_score(project_id);
             
+            // Take custody of the NFT once the deposit is recorded; the core moves it as approved operator
+            let core = self.env().account_id();
+            collection.transfer(core, nft_token_id)?;
+            if collection.owner_of(nft_token_id)? != Some(core) {
+                return Err(SafeguardError::TransferFailed);
+            }
             Ok(())
         }
 
         /// Withdraw NFT guarantee from a project
+        #[cfg(feature = "nft-collateral")]
         #[ink(message)]
         pub fn withdraw_nft_guarantee(
             &mut self,
@@ -2218,6 +5201,7 @@ pub mod safeguard {
             collection_id: NFTCollectionId,
             nft_token_id: NFTTokenId,
         ) -> Result<(), SafeguardError> {
+            self._ensure_module_active(PauseModule::Nft)?;
             let caller = self.env().caller();
             
             // Check if project exists
@@ -2225,12 +5209,32 @@ pub mod safeguard {
                 return Err(SafeguardError::InvalidInput);
             }
             
+            self.reentrancy_guard.start()?;
+            let result = self._remove_nft_guarantee(project_id, collection_id, caller, nft_token_id);
+            self.reentrancy_guard.end();
+            result?;
+            
+            // Update project score after NFT withdrawal
+            self._refresh_project_score(project_id);
+            
+            Ok(())
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        fn _remove_nft_guarantee(
+            &mut self,
+            project_id: ProjectId,
+            collection_id: NFTCollectionId,
+            caller: AccountId,
+            nft_token_id: NFTTokenId,
+        ) -> Result<(), SafeguardError> {
             // Check if NFT guarantee exists
             let estimated_value = self.nft_guarantees.get((project_id, collection_id, caller, nft_token_id))
                 .ok_or(SafeguardError::InvalidInput)?;
             
             // Remove NFT guarantee
             self.nft_guarantees.remove((project_id, collection_id, caller, nft_token_id));
+            self.nft_custody.remove((collection_id, nft_token_id));
             
             // Update project NFT guarantee totals
             let current_total = self.project_nft_guarantees.get((project_id, collection_id)).unwrap_or(0);
@@ -2240,6 +5244,7 @@ pub mod safeguard {
             } else {
                 self.project_nft_guarantees.insert((project_id, collection_id), &new_total);
             }
+            self._checkpoint_nft_value(project_id, caller, estimated_value, false);
             
             // Update user NFT deposit count
             let current_count = self.user_nft_deposits.get((caller, collection_id)).unwrap_or(0);
@@ -2260,19 +5265,20 @@ pub mod safeguard {
                 estimated_value,
             });
             
-            // Update project score after NFT withdrawal
-            let _ = self.update_project_score(project_id);
-            
-            Ok(())
+            // Return the NFT to its depositor
+            let collection = self.supported_nft_collections.get(collection_id).ok_or(SafeguardError::InvalidInput)?;
+            Psp34Client::new(collection.contract_address).transfer(caller, nft_token_id)
         }
 
         /// Get NFT collection information
+        #[cfg(feature = "nft-collateral")]
         #[ink(message)]
         pub fn get_nft_collection_info(&self, collection_id: NFTCollectionId) -> Option<NFTCollectionInfo> {
             self.supported_nft_collections.get(collection_id)
         }
 
         /// Get NFT guarantee value for a specific NFT
+        #[cfg(feature = "nft-collateral")]
         #[ink(message)]
         pub fn get_nft_guarantee_value(
             &self,
@@ -2285,6 +5291,7 @@ pub mod safeguard {
         }
 
         /// Get total NFT guarantee value for a project and collection
+        #[cfg(feature = "nft-collateral")]
         #[ink(message)]
         pub fn get_project_nft_guarantee_total(
             &self,
@@ -2295,6 +5302,7 @@ pub mod safeguard {
         }
 
         /// Get user's NFT deposit count for a collection
+        #[cfg(feature = "nft-collateral")]
         #[ink(message)]
         pub fn get_user_nft_deposit_count(
             &self,
@@ -2310,6 +5318,7 @@ pub mod safeguard {
             &mut self,
             project_id: ProjectId,
         ) -> Result<(), SafeguardError> {
+            self._ensure_module_active(PauseModule::Withdrawals)?;
             let caller = self.env().caller();
             
             // Check if project exists
@@ -2404,54 +5413,45 @@ pub mod safeguard {
         }
 
         /// Calculate project guarantee score using Score v1.1 model (0-100)
+        ///
+        /// O core só fornece os saldos custodiados; a fórmula vive no motor de score.
+        /// Se o motor configurado falhar, devolve o último score em cache.
         #[ink(message)]
         pub fn calculate_project_score(&self, project_id: ProjectId) -> u8 {
+            self._compute_project_score(project_id)
+                .unwrap_or_else(|_| self.project_scores.get(project_id).unwrap_or(0))
+        }
+
+        /// Score pelo motor configurado; sem motor, fórmula v1 embutida (feature `builtin-score`)
+        fn _compute_project_score(&self, project_id: ProjectId) -> Result<u8, SafeguardError> {
             // Check if project exists
             if !self.project_owners.contains(project_id) {
-                return 0;
+                return Ok(0);
             }
             
-            // Get project supply (for now, use a default value - this should be configurable per project)
-            let project_supply: Balance = 1_000_000 * 1_000_000_000_000_000_000; // 1M tokens with 18 decimals (placeholder)
-            
             // Calculate Lunes collateral amount
             let lunes_collateral = self._get_lunes_collateral_amount(project_id);
             
             // If no Lunes collateral, score is 0 (Lunes is mandatory)
             if lunes_collateral == 0 {
-                return 0;
+                return Ok(0);
             }
             
-            // Calculate burn progress (π)
-            let pi = self._calculate_burn_progress();
-            
-            // Calculate effective Lunes supply
-            let s_l_eff = self._calculate_effective_lunes_supply(pi);
-            
-            // Calculate project relative size for base target calculation
-            let r_proj = project_supply.saturating_div(self.s_ref.max(self.epsilon));
-            
-            // Calculate base target T_base
-            let t_base = self._calculate_base_target(r_proj);
-            
-            // Apply temporal uplift
-            let uplift = self._calculate_uplift(pi);
-            let t_prime = t_base.saturating_div(uplift.max(self.epsilon));
-            
-            // Calculate Lunes component (up to 95 points)
-            let s_lunes = if lunes_collateral > 0 {
-                let ratio = lunes_collateral.saturating_mul(95).saturating_div(t_prime.max(self.epsilon));
-                ratio.min(95)
-            } else {
-                0
-            };
-            
-            // Calculate other tokens component (up to 5 points)
-            let s_other = self._calculate_other_tokens_score(project_id, lunes_collateral, project_supply, s_l_eff);
-            
-            // Final score (capped at 100)
-            let total_score = s_lunes.saturating_add(s_other);
-            (total_score.min(100)) as u8
+            let inputs = self._score_inputs(project_id, lunes_collateral);
+            let params = self._score_params();
+            if let Some(engine) = self.score_engine.get_or_default() {
+                // Com motor configurado não há fallback: uma fórmula diferente mudaria o score em silêncio
+                let engine_ref: ScoreEngineRef = engine.into();
+                let computed = match cross_call::invoke(&engine_ref.call().compute_score(inputs, params).gas_limit(0).params()) {
+                    Ok(Ok(score)) => Some(score),
+                    _ => None,
+                };
+                return computed.map(|score| score.min(100)).ok_or(SafeguardError::ScoreEngineCallFailed);
+            }
+            #[cfg(feature = "builtin-score")]
+            return Ok(score_engine::v1::compute_score(&inputs, &params));
+            #[cfg(not(feature = "builtin-score"))]
+            Err(SafeguardError::FeatureDisabled)
         }
         
         /// Get cached project score or calculate if not cached
@@ -2469,12 +5469,28 @@ pub mod safeguard {
         /// Update and cache project score
         #[ink(message)]
         pub fn update_project_score(&mut self, project_id: ProjectId) -> Result<u8, SafeguardError> {
+            self._ensure_not_paused()?;
+            self._update_project_score(project_id)
+        }
+
+        /// Recalcula o score depois de uma mudança de colateral sem travar a operação.
+        /// Com motor externo, a falha sai em `ScoreEngineCallFailed` e o score em cache é
+        /// mantido; sem motor nem fórmula embutida (`FeatureDisabled`) não há score a manter.
+        fn _refresh_project_score(&mut self, project_id: ProjectId) {
+            if self._update_project_score(project_id).is_err() {
+                if let Some(engine) = self.score_engine.get_or_default() {
+                    self.env().emit_event(ScoreEngineCallFailed { project_id, engine });
+                }
+            }
+        }
+
+        fn _update_project_score(&mut self, project_id: ProjectId) -> Result<u8, SafeguardError> {
             // Check if project exists
             if !self.project_owners.contains(project_id) {
                 return Err(SafeguardError::InvalidInput);
             }
             
-            let new_score = self.calculate_project_score(project_id);
+            let new_score = self._compute_project_score(project_id)?;
             self.project_scores.insert(project_id, &new_score);
             
             self.env().emit_event(ProjectScoreUpdated {
@@ -2494,67 +5510,9 @@ pub mod safeguard {
             }
         }
         
-        /// Calculate burn progress (π) - normalized between 0 and 1
-        fn _calculate_burn_progress(&self) -> u32 {
-            let initial_supply = 200_000_000 * 1_000_000_000_000_000_000; // 200M Lunes
-            let floor_supply = self.floor_f;
-            
-            if self.current_lunes_supply <= floor_supply {
-                100 // π = 1.0 (represented as 100)
-            } else if self.current_lunes_supply >= initial_supply {
-                0 // π = 0.0
-            } else {
-                // π = (200M - S_L_atual) / (200M - floor_f)
-                let numerator = initial_supply.saturating_sub(self.current_lunes_supply);
-                let denominator = initial_supply.saturating_sub(floor_supply);
-                if denominator > 0 {
-                    numerator.saturating_mul(100).saturating_div(denominator).min(100) as u32
-                } else {
-                    0
-                }
-            }
-        }
-        
-        /// Calculate effective Lunes supply (S_L_eff)
-        fn _calculate_effective_lunes_supply(&self, pi: u32) -> Balance {
-            // S_L_eff = (1 - π) * S_L_atual + π * floor_f
-            let pi_balance = pi as Balance;
-            let one_minus_pi = (100 - pi) as Balance;
-            
-            let term1 = one_minus_pi.saturating_mul(self.current_lunes_supply).saturating_div(100);
-            let term2 = pi_balance.saturating_mul(self.floor_f).saturating_div(100);
-            
-            term1.saturating_add(term2)
-        }
-        
-        /// Calculate base target T_base = max(T_min, α * r_proj^γ)
-        fn _calculate_base_target(&self, r_proj: Balance) -> Balance {
-            // Calculate r_proj^gamma using integer approximation
-            let gamma_factor = self._power_approximation(r_proj, self.gamma);
-            let alpha_times_gamma = self.alpha.saturating_mul(gamma_factor).saturating_div(1_000_000_000_000_000_000); // Normalize
-            
-            alpha_times_gamma.max(self.t_min)
-        }
-        
-        /// Calculate temporal uplift u(π) = 1 + θ * π
-        fn _calculate_uplift(&self, pi: u32) -> Balance {
-            // u(π) = 1 + θ * π, where θ and π are scaled by 100
-            let base: Balance = 1_000_000_000_000_000_000; // 1.0 with 18 decimals
-            let theta_times_pi = (self.theta as Balance).saturating_mul(pi as Balance).saturating_div(10000); // θ*π/100/100
-            let uplift_scaled = theta_times_pi.saturating_mul(1_000_000_000_000_000_000).saturating_div(100);
-            
-            base.saturating_add(uplift_scaled)
-        }
-        
-        /// Calculate other tokens score component (up to 5 points)
-        fn _calculate_other_tokens_score(&self, project_id: ProjectId, lunes_collateral: Balance, project_supply: Balance, s_l_eff: Balance) -> Balance {
-            if lunes_collateral == 0 {
-                return 0; // No score for other tokens if no Lunes
-            }
-            
-            // Calculate total value of other tokens (excluding Lunes)
-            let mut other_tokens_value: Balance = 0;
-            
+        /// Saldos do projeto entregues ao motor de score (haircuts ficam na fórmula)
+        fn _score_inputs(&self, project_id: ProjectId, lunes_collateral: Balance) -> ScoreInputs {
+            let mut other_token_collateral = Vec::new();
             for token_id in 0..self.next_token_id {
                 // Skip Lunes token
                 if Some(token_id) == self.lunes_token_id {
@@ -2563,77 +5521,83 @@ pub mod safeguard {
                 
                 if let Some(token_info) = self.supported_tokens.get(token_id) {
                     if token_info.is_active {
-                        let project_total = self.project_total_guarantees.get(&(project_id, token_id)).unwrap_or(0);
+                        let project_total = self.project_total_guarantees.get((project_id, token_id)).unwrap_or(0);
                         if project_total > 0 {
-                            // Apply haircut (for simplicity, using a fixed 90% haircut for now)
-                            let haircut_value = project_total.saturating_mul(90).saturating_div(100);
-                            other_tokens_value = other_tokens_value.saturating_add(haircut_value);
+                            other_token_collateral.push(project_total);
                         }
                     }
                 }
             }
             
-            // Add NFT values (with haircuts)
+            let mut nft_collateral = Vec::new();
             for collection_id in 0..self.next_nft_collection_id {
-                if let Some(_collection_info) = self.supported_nft_collections.get(collection_id) {
-                    let project_nft_total = self.project_nft_guarantees.get(&(project_id, collection_id)).unwrap_or(0);
+                if self.supported_nft_collections.contains(collection_id) {
+                    let project_nft_total = self.project_nft_guarantees.get((project_id, collection_id)).unwrap_or(0);
                     if project_nft_total > 0 {
-                        // Apply haircut for NFTs (using 50% haircut)
-                        let haircut_value = project_nft_total.saturating_mul(50).saturating_div(100);
-                        other_tokens_value = other_tokens_value.saturating_add(haircut_value);
+                        nft_collateral.push(project_nft_total);
                     }
                 }
             }
             
-            if other_tokens_value == 0 {
-                return 0;
+            ScoreInputs {
+                lunes_collateral,
+                other_token_collateral,
+                nft_collateral,
+                // Project supply (for now, a default value - this should be configurable per project)
+                project_supply: 1_000_000 * 1_000_000_000_000_000_000, // 1M tokens with 18 decimals (placeholder)
+                current_lunes_supply: self.current_lunes_supply,
             }
-            
-            // Calculate q = E_other / max(C_L, ε)
-            let q = other_tokens_value.saturating_div(lunes_collateral.max(self.epsilon));
-            
-            // Calculate r_extra for penalty calculation
-            let r_extra = project_supply.saturating_div(s_l_eff.max(self.epsilon));
-            
-            // Calculate g(r_extra) = 1 / (1 + r_extra^δ)
-            let r_extra_delta = self._power_approximation(r_extra, self.delta);
-            let denominator = 1_000_000_000_000_000_000_u128.saturating_add(r_extra_delta); // 1 + r_extra^δ
-            let g = 1_000_000_000_000_000_000_u128.saturating_div(denominator.max(self.epsilon));
-            
-            // S_other = 5 * min(1, q * g)
-            let q_times_g = q.saturating_mul(g).saturating_div(1_000_000_000_000_000_000);
-            let score_factor = q_times_g.min(1_000_000_000_000_000_000); // min(1, q*g)
-            
-            score_factor.saturating_mul(5).saturating_div(1_000_000_000_000_000_000)
         }
         
-        /// Simple power approximation for integer calculations
-        fn _power_approximation(&self, base: Balance, exponent: u32) -> Balance {
-            if exponent == 0 {
-                return 1_000_000_000_000_000_000; // 1.0 with 18 decimals
-            }
-            
-            // Convert exponent from scaled integer (e.g., 120 = 1.2) to actual value
-            let exp_whole = exponent / 100;
-            let exp_frac = exponent % 100;
-            
-            let mut result: Balance = 1_000_000_000_000_000_000; // Start with 1.0
-            
-            // Handle whole part of exponent
-            for _ in 0..exp_whole {
-                result = result.saturating_mul(base).saturating_div(1_000_000_000_000_000_000);
+        fn _score_params(&self) -> ScoreParams {
+            ScoreParams {
+                alpha: self.alpha,
+                gamma: self.gamma,
+                delta: self.delta,
+                t_min: self.t_min,
+                theta: self.theta,
+                s_ref: self.s_ref,
+                floor_f: self.floor_f,
+                kappa: self.kappa,
+                epsilon: self.epsilon,
             }
-            
-            // Handle fractional part (simple approximation)
-            if exp_frac > 0 {
-                // For fractional exponents, use linear approximation: x^0.2 ≈ 1 + 0.2*(x-1)
-                let base_minus_one = base.saturating_sub(1_000_000_000_000_000_000);
-                let frac_adjustment = base_minus_one.saturating_mul(exp_frac as Balance).saturating_div(100);
-                let frac_result = 1_000_000_000_000_000_000_u128.saturating_add(frac_adjustment);
-                result = result.saturating_mul(frac_result).saturating_div(1_000_000_000_000_000_000);
+        }
+        
+        /// Contrato do motor de score; None volta à fórmula embutida (admin de bootstrap; depois via DAO)
+        #[ink(message)]
+        pub fn set_score_engine(&mut self, engine: Option<AccountId>) -> Result<(), SafeguardError> {
+            self._ensure_protocol_admin()?;
+            if let Some(engine) = engine {
+                validation::validate_account(engine)?;
             }
-            
-            result
+            self._set_score_engine(engine);
+            Ok(())
+        }
+        
+        fn _set_score_engine(&mut self, engine: Option<AccountId>) {
+            self.score_engine.set(&engine);
+            self.env().emit_event(ScoreEngineUpdated { engine });
+        }
+        
+        #[ink(message)]
+        pub fn get_score_engine(&self) -> Option<AccountId> {
+            self.score_engine.get_or_default()
+        }
+        
+        /// Versão da fórmula em uso (a do motor configurado ou a embutida); 0 se indisponível
+        #[ink(message)]
+        pub fn get_score_formula_version(&self) -> u32 {
+            if let Some(engine) = self.score_engine.get_or_default() {
+                let engine_ref: ScoreEngineRef = engine.into();
+                return match cross_call::invoke(&engine_ref.call().formula_version().gas_limit(0).params()) {
+                    Ok(Ok(version)) => version,
+                    _ => 0,
+                };
+            }
+            #[cfg(feature = "builtin-score")]
+            return score_engine::v1::VERSION;
+            #[cfg(not(feature = "builtin-score"))]
+            0
         }
         
         /// Internal function to calculate vesting time bonus (0-15 points)
@@ -2656,13 +5620,10 @@ pub mod safeguard {
             }
         }
 
-        /// Set Lunes token ID (owner-only)
+        /// Set Lunes token ID (Admin)
         #[ink(message)]
         pub fn set_lunes_token_id(&mut self, token_id: TokenId) -> Result<(), SafeguardError> {
-            let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
+            self._ensure_role(Role::Admin)?;
             
             // Validate that the token exists in supported tokens
             if !self.supported_tokens.contains(token_id) {
@@ -2673,19 +5634,16 @@ pub mod safeguard {
             Ok(())
         }
         
-        /// Update current Lunes supply (owner-only)
+        /// Update current Lunes supply (ScoreOracle)
         #[ink(message)]
         pub fn update_lunes_supply(&mut self, new_supply: Balance) -> Result<(), SafeguardError> {
-            let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
+            self._ensure_role(Role::ScoreOracle)?;
             
             self.current_lunes_supply = new_supply;
             Ok(())
         }
         
-        /// Set Score v1.1 governance parameters (owner-only)
+        /// Set Score v1.1 governance parameters (bootstrap admin; afterwards via timelock or DAO)
         #[ink(message)]
         pub fn set_score_parameters(
             &mut self,
@@ -2698,10 +5656,28 @@ pub mod safeguard {
             floor_f: Option<Balance>,
             kappa: Option<u32>,
         ) -> Result<(), SafeguardError> {
-            let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
+            self._ensure_protocol_admin()?;
+            self._set_score_parameters(&ScoreParameters { alpha, gamma, delta, t_min, theta, s_ref, floor_f, kappa })
+        }
+
+        fn _set_score_parameters(&mut self, params: &ScoreParameters) -> Result<(), SafeguardError> {
+            Self::_validate_score_parameters(params)?;
+            
+            // Update parameters
+            if let Some(a) = params.alpha { self.alpha = a; }
+            if let Some(g) = params.gamma { self.gamma = g; }
+            if let Some(d) = params.delta { self.delta = d; }
+            if let Some(t) = params.t_min { self.t_min = t; }
+            if let Some(th) = params.theta { self.theta = th; }
+            if let Some(s) = params.s_ref { self.s_ref = s; }
+            if let Some(f) = params.floor_f { self.floor_f = f; }
+            if let Some(k) = params.kappa { self.kappa = k; }
+            
+            Ok(())
+        }
+
+        fn _validate_score_parameters(params: &ScoreParameters) -> Result<(), SafeguardError> {
+            let ScoreParameters { gamma, delta, theta, kappa, .. } = *params;
             
             // Validate parameters ranges
             if let Some(g) = gamma {
@@ -2728,16 +5704,6 @@ pub mod safeguard {
                 }
             }
             
-            // Update parameters
-            if let Some(a) = alpha { self.alpha = a; }
-            if let Some(g) = gamma { self.gamma = g; }
-            if let Some(d) = delta { self.delta = d; }
-            if let Some(t) = t_min { self.t_min = t; }
-            if let Some(th) = theta { self.theta = th; }
-            if let Some(s) = s_ref { self.s_ref = s; }
-            if let Some(f) = floor_f { self.floor_f = f; }
-            if let Some(k) = kappa { self.kappa = k; }
-            
             Ok(())
         }
         
@@ -2763,732 +5729,3621 @@ pub mod safeguard {
             self.lunes_token_id
         }
 
-        /// Get contract owner
+        /// Get contract owner: the council signers and approval threshold
         #[ink(message)]
-        pub fn owner(&self) -> Option<AccountId> {
-            Some(self.contract_owner)
+        pub fn owner(&self) -> CouncilConfig {
+            self.council.get_or_default()
         }
 
-        // ==================== PAUSABILITY FUNCTIONS ====================
+        // ==================== COUNCIL (M-of-N) ====================
 
-        /// Pause the contract (owner only)
+        /// Propose a privileged action (signers only); the proposer's approval is counted
         #[ink(message)]
-        pub fn pause(&mut self) -> Result<(), SafeguardError> {
+        pub fn propose_council_action(&mut self, action: CouncilAction) -> Result<ProposalId, SafeguardError> {
             let caller = self.env().caller();
-            if caller != self.contract_owner {
+            if !self._is_council_signer(caller) {
                 return Err(SafeguardError::NotOwner);
             }
             
-            if self.is_paused {
-                return Err(SafeguardError::ContractPaused);
-            }
-            
-            self.is_paused = true;
-            self.paused_at = self.env().block_timestamp();
+            let proposal_id = self.next_council_proposal_id.get_or_default();
+            self.council_proposals.insert(proposal_id, &CouncilProposal {
+                proposer: caller,
+                action: action.clone(),
+                created_at: self.env().block_timestamp(),
+                is_executed: false,
+            });
+            self.next_council_proposal_id.set(&self.next_council_proposal_id.get_or_default().saturating_add(1));
             
-            self.env().emit_event(ContractPausedEvent {
-                paused_by: caller,
-                timestamp: self.paused_at,
+            self.env().emit_event(CouncilProposalCreated {
+                proposal_id,
+                proposer: caller,
+                action,
             });
             
-            Ok(())
+            self.approve_council_proposal(proposal_id)?;
+            Ok(proposal_id)
         }
 
-        /// Unpause the contract (owner only)
+        /// Approve a council proposal; it executes as soon as the threshold is met
         #[ink(message)]
-        pub fn unpause(&mut self) -> Result<(), SafeguardError> {
+        pub fn approve_council_proposal(&mut self, proposal_id: ProposalId) -> Result<(), SafeguardError> {
             let caller = self.env().caller();
-            if caller != self.contract_owner {
+            if !self._is_council_signer(caller) {
                 return Err(SafeguardError::NotOwner);
             }
             
-            if !self.is_paused {
-                return Err(SafeguardError::ContractNotPaused);
+            let mut proposal = self.council_proposals.get(proposal_id)
+                .ok_or(SafeguardError::ProposalNotFound)?;
+            if proposal.is_executed {
+                return Err(SafeguardError::ProposalAlreadyExecuted);
+            }
+            if self.council_approvals.contains((proposal_id, caller)) {
+                return Err(SafeguardError::AlreadyVoted);
             }
             
-            self.is_paused = false;
+            self.council_approvals.insert((proposal_id, caller), &true);
+            let approvals = self.get_council_approvals(proposal_id);
             
-            self.env().emit_event(ContractUnpausedEvent {
-                unpaused_by: caller,
-                timestamp: self.env().block_timestamp(),
+            self.env().emit_event(CouncilProposalApproved {
+                proposal_id,
+                signer: caller,
+                approvals,
+            });
+            
+            if approvals < self.council.get_or_default().threshold {
+                return Ok(());
+            }
+            
+            proposal.is_executed = true;
+            self.council_proposals.insert(proposal_id, &proposal);
+            
+            // Privileged checks pass only while the approved action runs
+            self.council_executing.set(&true);
+            let result = self._execute_council_action(proposal.action);
+            self.council_executing.set(&false);
+            result?;
+            
+            self.env().emit_event(CouncilProposalExecuted {
+                proposal_id,
+                executed_by: caller,
             });
             
             Ok(())
         }
 
-        /// Check if contract is paused
+        /// Get a council proposal
         #[ink(message)]
-        pub fn is_contract_paused(&self) -> bool {
-            self.is_paused
+        pub fn get_council_proposal(&self, proposal_id: ProposalId) -> Option<CouncilProposal> {
+            self.council_proposals.get(proposal_id)
         }
 
-        /// Get pause timestamp
+        /// Approvals from current signers (removed signers no longer count)
         #[ink(message)]
-        pub fn get_paused_at(&self) -> u64 {
-            self.paused_at
+        pub fn get_council_approvals(&self, proposal_id: ProposalId) -> u32 {
+            let approvals = self.council.get_or_default().signers.iter()
+                .filter(|signer| self.council_approvals.contains((proposal_id, **signer)))
+                .count();
+            u32::try_from(approvals).unwrap_or(u32::MAX)
         }
 
-        /// Internal modifier to check if contract is not paused
-        fn _ensure_not_paused(&self) -> Result<(), SafeguardError> {
-            if self.is_paused {
-                return Err(SafeguardError::ContractPaused);
+        /// Privileged calls: a 1-of-N council may call directly, otherwise only through an approved proposal
+        fn _ensure_owner(&self) -> Result<(), SafeguardError> {
+            if self.council_executing.get_or_default() {
+                return Ok(());
+            }
+            let caller = self.env().caller();
+            if !self._is_council_signer(caller) {
+                return Err(SafeguardError::NotOwner);
+            }
+            if self.council.get_or_default().threshold > 1 {
+                return Err(SafeguardError::CouncilApprovalRequired);
             }
             Ok(())
         }
 
-        // ==================== TIMELOCK FUNCTIONS ====================
+        fn _is_council_signer(&self, account: AccountId) -> bool {
+            self.council.get_or_default().signers.contains(&account)
+        }
 
-        /// Schedule a timelock operation (owner only)
-        #[ink(message)]
-        pub fn schedule_operation(
-            &mut self,
-            function_type: u8,
-            data: Vec<u8>,
-        ) -> Result<OperationId, SafeguardError> {
-            let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
+        fn _execute_council_action(&mut self, action: CouncilAction) -> Result<(), SafeguardError> {
+            match action {
+                CouncilAction::AddSigner(account) => {
+                    validation::validate_account(account)?;
+                    if self._is_council_signer(account) || self.is_guardian(account) {
+                        return Err(SafeguardError::InvalidInput);
+                    }
+                    let mut council = self.council.get_or_default();
+                    if council.signers.len() >= MAX_COUNCIL_SIGNERS {
+                        return Err(SafeguardError::InvalidInput);
+                    }
+                    council.signers.push(account);
+                    self.council.set(&council);
+                    self._emit_council_updated();
+                    Ok(())
+                }
+                CouncilAction::RemoveSigner(account) => {
+                    let mut council = self.council.get_or_default();
+                    let index = council.signers.iter().position(|signer| *signer == account)
+                        .ok_or(SafeguardError::InvalidInput)?;
+                    // Removal may not leave the threshold unreachable
+                    if council.signers.len() <= council.threshold as usize {
+                        return Err(SafeguardError::InvalidInput);
+                    }
+                    council.signers.remove(index);
+                    self.council.set(&council);
+                    self._emit_council_updated();
+                    Ok(())
+                }
+                CouncilAction::SetThreshold(threshold) => {
+                    let mut council = self.council.get_or_default();
+                    if threshold == 0 || threshold as usize > council.signers.len() {
+                        return Err(SafeguardError::InvalidInput);
+                    }
+                    council.threshold = threshold;
+                    self.council.set(&council);
+                    self._emit_council_updated();
+                    Ok(())
+                }
+                CouncilAction::Pause(reason) => self.pause(reason),
+                CouncilAction::Unpause => self.unpause(),
+                CouncilAction::PauseModule { module, reason } => self.pause_module(module, reason),
+                CouncilAction::UnpauseModule(module) => self.unpause_module(module),
+                CouncilAction::SetMaxPauseDuration(max_duration) => self.set_max_pause_duration(max_duration),
+                CouncilAction::EmergencyPauseProject { project_id, pause } => self.emergency_pause_project(project_id, pause),
+                CouncilAction::CloseProject(project_id) => self.close_project(project_id),
+                #[cfg(feature = "timelock")]
+                CouncilAction::ScheduleOperation { function_type, data, salt } => {
+                    self.schedule_operation(function_type, data, salt).map(|_| ())
+                }
+                #[cfg(feature = "timelock")]
+                CouncilAction::CancelOperation(operation_id) => self.cancel_operation(operation_id),
+                #[cfg(feature = "timelock")]
+                CouncilAction::ExecuteOperation(operation_id) => self.execute_operation(operation_id),
+                #[cfg(feature = "timelock")]
+                CouncilAction::SetTimelockDelay(delay) => self.set_timelock_delay(delay),
+                #[cfg(feature = "timelock")]
+                CouncilAction::SetMinDelay { function_type, min_delay } => self.set_min_delay(function_type, min_delay),
+                #[cfg(not(feature = "timelock"))]
+                CouncilAction::ScheduleOperation { .. }
+                | CouncilAction::CancelOperation(_)
+                | CouncilAction::ExecuteOperation(_)
+                | CouncilAction::SetTimelockDelay(_)
+                | CouncilAction::SetMinDelay { .. } => Err(SafeguardError::FeatureDisabled),
+                CouncilAction::SetLunesTokenId(token_id) => self.set_lunes_token_id(token_id),
+                CouncilAction::SetLusdtTokenId(token_id) => self.set_lusdt_token_id(token_id),
+                CouncilAction::UpdateLunesSupply(supply) => self.update_lunes_supply(supply),
+                CouncilAction::SetVoteFees { lunes_fee, lusdt_fee } => self.set_vote_fees(lunes_fee, lusdt_fee),
+                CouncilAction::SetVoterRewardShare(share_bps) => self.set_voter_reward_share(share_bps),
+                CouncilAction::SetDefaultVoteWeighting { vote_type, weighting } => {
+                    self.set_default_vote_weighting(vote_type, weighting)
+                }
+                CouncilAction::SetProjectVoteWeighting { project_id, vote_type, weighting } => {
+                    self.set_project_vote_weighting(project_id, vote_type, weighting)
+                }
+                CouncilAction::SetProjectTokenCheckpoints { project_id, enabled } => {
+                    self.set_project_token_checkpoints(project_id, enabled)
+                }
+                CouncilAction::EnableProtocolGovernance => self.enable_protocol_governance(),
+                CouncilAction::GrantRole { role, account } => self.grant_role(role, account),
+                CouncilAction::RevokeRole { role, account } => self.revoke_role(role, account),
             }
-            
-            let current_time = self.env().block_timestamp();
-            let operation_id = self.next_operation_id;
-            
-            let function = match function_type {
-                0 => TimelockFunction::TransferContractOwnership,
-                1 => TimelockFunction::SetTreasuryAddress,
-                2 => TimelockFunction::SetDepositFees,
-                3 => TimelockFunction::SetScoreParameters,
-                4 => TimelockFunction::EmergencyWithdraw,
-                5 => TimelockFunction::AddSupportedToken,
-                6 => TimelockFunction::AddNftCollection,
-                _ => return Err(SafeguardError::InvalidInput),
-            };
-            
-            let operation = TimelockOperation {
-                function_type: function,
-                scheduled_by: caller,
-                execute_after: current_time.saturating_add(self.timelock_delay),
-                expires_at: current_time.saturating_add(self.timelock_delay).saturating_add(self.timelock_expiry),
-                is_executed: false,
-                is_cancelled: false,
-                data,
-            };
-            
-            self.timelock_operations.insert(operation_id, &operation);
-            self.next_operation_id = self.next_operation_id.saturating_add(1);
-            
-            self.env().emit_event(TimelockOperationScheduled {
-                operation_id,
-                target_function: function_type,
-                scheduled_by: caller,
-                execute_after: operation.execute_after,
-                expires_at: operation.expires_at,
-            });
-            
-            Ok(operation_id)
         }
 
-        /// Cancel a pending timelock operation (owner only)
+        fn _emit_council_updated(&self) {
+            let CouncilConfig { signers, threshold } = self.council.get_or_default();
+            self.env().emit_event(CouncilUpdated { signers, threshold });
+        }
+
+        // ==================== ROLES ====================
+
+        /// Grant a role (Admin or council)
         #[ink(message)]
-        pub fn cancel_operation(&mut self, operation_id: OperationId) -> Result<(), SafeguardError> {
-            let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
-            
-            let mut operation = self.timelock_operations.get(operation_id)
-                .ok_or(SafeguardError::OperationNotFound)?;
-            
-            if operation.is_executed {
-                return Err(SafeguardError::OperationNotFound);
-            }
+        pub fn grant_role(&mut self, role: Role, account: AccountId) -> Result<(), SafeguardError> {
+            self._ensure_role(Role::Admin)?;
+            validation::validate_account(account)?;
             
-            if operation.is_cancelled {
-                return Err(SafeguardError::OperationNotFound);
+            if !self.has_role(role, account) {
+                self.roles.insert((role, account), &true);
+                self.env().emit_event(RoleGranted {
+                    role,
+                    account,
+                    sender: self.env().caller(),
+                });
             }
-            
-            operation.is_cancelled = true;
-            self.timelock_operations.insert(operation_id, &operation);
-            
-            self.env().emit_event(TimelockOperationCancelled {
-                operation_id,
-                cancelled_by: caller,
-            });
-            
             Ok(())
         }
 
-        /// Execute a timelock operation (owner only)
+        /// Revoke a role (Admin or council)
         #[ink(message)]
-        pub fn execute_operation(&mut self, operation_id: OperationId) -> Result<(), SafeguardError> {
+        pub fn revoke_role(&mut self, role: Role, account: AccountId) -> Result<(), SafeguardError> {
+            self._ensure_role(Role::Admin)?;
+            self._revoke_role(role, account);
+            Ok(())
+        }
+
+        /// Give up a role held by the caller
+        #[ink(message)]
+        pub fn renounce_role(&mut self, role: Role) -> Result<(), SafeguardError> {
             let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
-            
-            let mut operation = self.timelock_operations.get(operation_id)
-                .ok_or(SafeguardError::OperationNotFound)?;
-            
-            if operation.is_executed {
-                return Err(SafeguardError::OperationNotFound);
-            }
-            
-            if operation.is_cancelled {
-                return Err(SafeguardError::OperationNotFound);
-            }
-            
-            let current_time = self.env().block_timestamp();
-            
-            if current_time < operation.execute_after {
-                return Err(SafeguardError::OperationNotReady);
-            }
-            
-            if current_time > operation.expires_at {
-                return Err(SafeguardError::OperationExpired);
+            if !self.has_role(role, caller) {
+                return Err(SafeguardError::MissingRole);
             }
-            
-            // Mark as executed
-            operation.is_executed = true;
-            self.timelock_operations.insert(operation_id, &operation);
-            
-            self.env().emit_event(TimelockOperationExecuted {
-                operation_id,
-                executed_by: caller,
-                timestamp: current_time,
-            });
-            
+            self._revoke_role(role, caller);
             Ok(())
         }
 
-        /// Get timelock operation info
+        /// Check if an account holds a role
         #[ink(message)]
-        pub fn get_operation(&self, operation_id: OperationId) -> Option<TimelockOperation> {
-            self.timelock_operations.get(operation_id)
+        pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+            self.roles.get((role, account)).unwrap_or(false)
         }
 
-        /// Get timelock delay
-        #[ink(message)]
-        pub fn get_timelock_delay(&self) -> u64 {
-            self.timelock_delay
+        fn _revoke_role(&mut self, role: Role, account: AccountId) {
+            if self.has_role(role, account) {
+                self.roles.remove((role, account));
+                self.env().emit_event(RoleRevoked {
+                    role,
+                    account,
+                    sender: self.env().caller(),
+                });
+            }
+        }
+
+        /// Privileged calls: holders of `role` pass, and the council always does
+        fn _ensure_role(&self, role: Role) -> Result<(), SafeguardError> {
+            if self.has_role(role, self.env().caller()) {
+                return Ok(());
+            }
+            self._ensure_owner()
         }
 
-        /// Set timelock delay (owner only, requires timelock)
+        // ==================== PROTOCOL GOVERNANCE (DAO) ====================
+
+        /// Ativa a governança do protocolo (Admin, irreversível).
+        /// A partir daqui os parâmetros globais só mudam por propostas aprovadas
+        /// pelos holders de LUNES travados; o owner fica restrito a funções de emergência.
         #[ink(message)]
-        pub fn set_timelock_delay(&mut self, new_delay: u64) -> Result<(), SafeguardError> {
+        pub fn enable_protocol_governance(&mut self) -> Result<(), SafeguardError> {
             let caller = self.env().caller();
-            if caller != self.contract_owner {
-                return Err(SafeguardError::NotOwner);
-            }
-            
-            // Minimum 1 hour, maximum 7 days
-            if new_delay < 60 * 60 * 1000 || new_delay > 7 * 24 * 60 * 60 * 1000 {
+            self._ensure_role(Role::Admin)?;
+
+            if self.protocol_governance_enabled.get_or_default() {
                 return Err(SafeguardError::InvalidInput);
             }
-            
-            self.timelock_delay = new_delay;
+
+            self.protocol_governance_enabled.set(&true);
+
+            self.env().emit_event(ProtocolGovernanceEnabled {
+                enabled_by: caller,
+                timestamp: self.env().block_timestamp(),
+            });
+
             Ok(())
         }
 
-        // ==================== CROSS-CONTRACT PSP22 CALLS ====================
-
-        /// Internal function to transfer PSP22 tokens from user to contract
-        fn _psp22_transfer_from(
-            &mut self,
-            token_id: TokenId,
-            from: AccountId,
-            amount: Balance,
-        ) -> Result<(), SafeguardError> {
-            let token_info = self.supported_tokens.get(token_id)
-                .ok_or(SafeguardError::TokenNotSupported)?;
-            
-            // Build cross-contract call to PSP22 transfer_from
-            let result = ink::env::call::build_call::<ink::env::DefaultEnvironment>()
-                .call(token_info.contract_address)
-                .gas_limit(0) // Use remaining gas
-                .transferred_value(0)
-                .exec_input(
-                    ink::env::call::ExecutionInput::new(ink::env::call::Selector::new(
-                        // PSP22::transfer_from selector
-                        ink::selector_bytes!("transfer_from")
-                    ))
-                    .push_arg(from)
-                    .push_arg(self.env().account_id())
-                    .push_arg(amount)
-                    .push_arg::<Vec<u8>>(Vec::new()) // data
-                )
-                .returns::<Result<(), psp22::PSP22Error>>()
-                .try_invoke();
-            
-            match result {
-                Ok(Ok(Ok(()))) => {
-                    self.env().emit_event(TokenTransferExecuted {
-                        token_id,
-                        from,
-                        to: self.env().account_id(),
-                        amount,
-                    });
-                    Ok(())
-                },
-                _ => Err(SafeguardError::TransferFailed),
-            }
+        /// Configuração da governança do protocolo (ativa, duração da votação, quórum em bps)
+        #[ink(message)]
+        pub fn get_protocol_governance_config(&self) -> (bool, u64, u16) {
+            (self.protocol_governance_enabled.get_or_default(), self.protocol_voting_duration.get_or_default(), self.protocol_quorum_bps.get_or_default())
         }
 
-        /// Internal function to transfer PSP22 tokens from contract to user
-        fn _psp22_transfer(
-            &mut self,
-            token_id: TokenId,
-            to: AccountId,
-            amount: Balance,
-        ) -> Result<(), SafeguardError> {
-            let token_info = self.supported_tokens.get(token_id)
-                .ok_or(SafeguardError::TokenNotSupported)?;
-            
-            // Build cross-contract call to PSP22 transfer
-            let result = ink::env::call::build_call::<ink::env::DefaultEnvironment>()
-                .call(token_info.contract_address)
-                .gas_limit(0)
-                .transferred_value(0)
-                .exec_input(
-                    ink::env::call::ExecutionInput::new(ink::env::call::Selector::new(
-                        // PSP22::transfer selector
-                        ink::selector_bytes!("transfer")
-                    ))
-                    .push_arg(to)
-                    .push_arg(amount)
-                    .push_arg::<Vec<u8>>(Vec::new()) // data
-                )
-                .returns::<Result<(), psp22::PSP22Error>>()
-                .try_invoke();
-            
-            match result {
-                Ok(Ok(Ok(()))) => {
-                    self.env().emit_event(TokenTransferExecuted {
-                        token_id,
-                        from: self.env().account_id(),
-                        to,
-                        amount,
-                    });
-                    Ok(())
-                },
-                _ => Err(SafeguardError::TransferFailed),
+        /// LUNES travados no Safeguard pela conta (garantias em LUNES somadas entre projetos)
+        #[ink(message)]
+        pub fn get_locked_lunes(&self, account: AccountId) -> Balance {
+            match self.lunes_token_id {
+                Some(token_id) => self.user_token_balances.get((account, token_id)).unwrap_or(0),
+                None => 0,
             }
         }
 
-        /// Internal function to get PSP22 balance
-        fn _psp22_balance_of(
-            &self,
-            token_id: TokenId,
-            account: AccountId,
-        ) -> Result<Balance, SafeguardError> {
-            let token_info = self.supported_tokens.get(token_id)
-                .ok_or(SafeguardError::TokenNotSupported)?;
-            
-            let result = ink::env::call::build_call::<ink::env::DefaultEnvironment>()
-                .call(token_info.contract_address)
-                .gas_limit(0)
-                .transferred_value(0)
-                .exec_input(
-                    ink::env::call::ExecutionInput::new(ink::env::call::Selector::new(
-                        // PSP22::balance_of selector
-                        ink::selector_bytes!("balance_of")
-                    ))
-                    .push_arg(account)
-                )
-                .returns::<Balance>()
-                .try_invoke();
-            
-            match result {
-                Ok(Ok(balance)) => Ok(balance),
-                _ => Err(SafeguardError::TransferFailed),
+        /// LUNES travados pela conta ao final de um bloco passado
+        #[ink(message)]
+        pub fn get_locked_lunes_at(&self, account: AccountId, block: u64) -> Balance {
+            match self.lunes_token_id {
+                Some(token_id) => self._checkpoint_at(&CheckpointKey::UserTokenBalance(account, token_id), block),
+                None => 0,
             }
         }
 
-        /// Transfer fee to treasury using real PSP22 call
-        fn _transfer_fee_to_treasury(
+        /// Cria proposta de alteração de parâmetros globais (requer LUNES travados no snapshot)
+        #[ink(message)]
+        pub fn propose_protocol_change(
             &mut self,
-            token_id: TokenId,
-            from: AccountId,
-            amount: Balance,
-        ) -> Result<(), SafeguardError> {
-            // First transfer from user to contract
-            self._psp22_transfer_from(token_id, from, amount)?;
-            
-            // Then transfer from contract to treasury
-            self._psp22_transfer(token_id, self.treasury_address, amount)?;
-            
-            Ok(())
-        }
+            action: ProtocolAction,
+            description_uri: Vec<u8>,
+        ) -> Result<ProposalId, SafeguardError> {
+            let caller = self.env().caller();
+            let lunes_token_id = self.lunes_token_id.ok_or(SafeguardError::TokenNotSupported)?;
 
-        /// Execute claim transfers to user
-        fn _execute_claim_transfers(
-            &mut self,
-            project_id: ProjectId,
-            claimer: AccountId,
-            claim_info: &ClaimInfo,
-        ) -> Result<(), SafeguardError> {
-            // Transfer Lunes share if any
-            if claim_info.lunes_share > 0 {
-                if let Some(lunes_id) = self.lunes_token_id {
-                    self._psp22_transfer(lunes_id, claimer, claim_info.lunes_share)?;
-                }
-            }
-            
-            // Transfer LUSDT share if any
-            if claim_info.lusdt_share > 0 {
-                if let Some(lusdt_id) = self.lusdt_token_id {
-                    self._psp22_transfer(lusdt_id, claimer, claim_info.lusdt_share)?;
-                }
+            self._validate_protocol_action(&action)?;
+
+            // Snapshot no último bloco selado, como nas votações de projeto
+            let snapshot_block = self.env().block_number().saturating_sub(1) as u64;
+            if self.get_locked_lunes_at(caller, snapshot_block) == 0 {
+                return Err(SafeguardError::NotEligibleToVote);
             }
-            
-            // Emit claim paid event
-            self.env().emit_event(ClaimPaid {
-                project_id,
-                claimer,
-                project_tokens_returned: claim_info.project_tokens_held,
-                lunes_received: claim_info.lunes_share,
-                lusdt_received: claim_info.lusdt_share,
-                other_tokens_received: claim_info.other_tokens_share,
-            });
-            
-            Ok(())
-        }
 
-        // ==================== OPTIMIZED SCORE CALCULATION ====================
+            let start_timestamp = self.env().block_timestamp();
+            let end_timestamp = start_timestamp.saturating_add(self.protocol_voting_duration.get_or_default());
+            let proposal_id = self.next_protocol_proposal_id.get_or_default();
 
-        /// Calculate temporal target T' = T_base / u(π) for Score v1.1
-        fn _calculate_temporal_target(&self, project_id: ProjectId) -> Balance {
-            // Get project supply (total guarantees as proxy)
-            let lunes_id = self.lunes_token_id.unwrap_or(0);
-            let project_supply = self.project_total_guarantees.get((project_id, lunes_id)).unwrap_or(0);
-            
-            // Calculate r_proj = S_p / S_ref
-            let r_proj = project_supply.saturating_mul(1_000_000_000_000_000_000).saturating_div(self.s_ref.max(1));
-            
-            // Calculate T_base
-            let t_base = self._calculate_base_target(r_proj);
-            
-            // Calculate burn progress π
-            let pi = self._calculate_burn_progress();
-            
-            // Calculate uplift u(π)
-            let uplift = self._calculate_uplift(pi);
-            
-            // T' = T_base / u(π)
-            t_base.saturating_mul(1_000_000_000_000_000_000).saturating_div(uplift.max(1))
+            let proposal = ProtocolProposal {
+                proposer: caller,
+                action: action.clone(),
+                description_uri,
+                snapshot_block,
+                start_timestamp,
+                end_timestamp,
+                yes_weight: 0,
+                no_weight: 0,
+                total_eligible_weight: self._checkpoint_at(&CheckpointKey::TokenTotal(lunes_token_id), snapshot_block),
+                status: ProtocolProposalStatus::Active,
+            };
+
+            self.protocol_proposals.insert(proposal_id, &proposal);
+            self.next_protocol_proposal_id.set(&self.next_protocol_proposal_id.get_or_default().saturating_add(1));
+
+            self.env().emit_event(ProtocolProposalCreated {
+                proposal_id,
+                proposer: caller,
+                action,
+                snapshot_block,
+                end_timestamp,
+            });
+
+            Ok(proposal_id)
         }
 
-        /// Optimized score calculation with caching
-        fn _calculate_score_optimized(&self, project_id: ProjectId) -> u8 {
-            // Check if Lunes token is configured
-            let lunes_id = match self.lunes_token_id {
-                Some(id) => id,
-                None => return 0, // No score without Lunes configured
-            };
-            
-            // Get Lunes collateral for this project
-            let lunes_collateral = self.project_total_guarantees.get((project_id, lunes_id)).unwrap_or(0);
-            
-            // Score v1.1: Lunes is mandatory
-            if lunes_collateral == 0 {
-                return 0;
+        /// Vota em proposta do protocolo com peso igual aos LUNES travados no snapshot
+        #[ink(message)]
+        pub fn vote_protocol_proposal(&mut self, proposal_id: ProposalId, support: bool) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
+            let mut proposal = self.protocol_proposals.get(proposal_id).ok_or(SafeguardError::VotingNotFound)?;
+
+            if proposal.status != ProtocolProposalStatus::Active {
+                return Err(SafeguardError::VotingNotActive);
             }
-            
-            // Use cached token count for optimization
-            let token_count = self.project_token_count.get(project_id).unwrap_or(0);
-            let nft_count = self.project_nft_count.get(project_id).unwrap_or(0);
-            
-            // Calculate Lunes component (up to 95 points)
-            let t_prime = self._calculate_temporal_target(project_id);
-            let lunes_score = if t_prime > 0 {
-                let ratio = lunes_collateral.saturating_mul(95).saturating_div(t_prime);
-                ratio.min(95) as u8
+
+            let current_timestamp = self.env().block_timestamp();
+            if current_timestamp > proposal.end_timestamp {
+                return Err(SafeguardError::VotingExpired);
+            }
+
+            if self.protocol_ballots.contains((proposal_id, caller)) {
+                return Err(SafeguardError::AlreadyVoted);
+            }
+
+            let weight = self.get_locked_lunes_at(caller, proposal.snapshot_block);
+            if weight == 0 {
+                return Err(SafeguardError::NotEligibleToVote);
+            }
+
+            if support {
+                proposal.yes_weight = proposal.yes_weight.saturating_add(weight);
             } else {
-                0
-            };
-            
-            // Calculate other tokens component (up to 5 points)
-            // Only calculate if there are other tokens (optimization)
-            let other_score = if token_count > 1 || nft_count > 0 {
-                self._calculate_other_tokens_score_fast(project_id, lunes_id, token_count, nft_count)
+                proposal.no_weight = proposal.no_weight.saturating_add(weight);
+            }
+
+            self.protocol_proposals.insert(proposal_id, &proposal);
+            self.protocol_ballots.insert((proposal_id, caller), &Ballot {
+                support,
+                raw_weight: weight,
+                effective_weight: weight,
+                timestamp: current_timestamp,
+            });
+
+            self.env().emit_event(ProtocolVoteCast {
+                proposal_id,
+                voter: caller,
+                support,
+                weight,
+            });
+
+            Ok(())
+        }
+
+        /// Encerra proposta após o prazo de votação; se aprovada com quórum, aplica a alteração.
+        /// Pode ser chamada por qualquer conta.
+        #[ink(message)]
+        pub fn execute_protocol_proposal(&mut self, proposal_id: ProposalId) -> Result<ProtocolProposalStatus, SafeguardError> {
+            let mut proposal = self.protocol_proposals.get(proposal_id).ok_or(SafeguardError::VotingNotFound)?;
+
+            if proposal.status != ProtocolProposalStatus::Active {
+                return Err(SafeguardError::VotingNotActive);
+            }
+
+            if self.env().block_timestamp() <= proposal.end_timestamp {
+                return Err(SafeguardError::VotingStillActive);
+            }
+
+            let participation = proposal.yes_weight.saturating_add(proposal.no_weight);
+            let quorum = proposal.total_eligible_weight
+                .saturating_mul(Balance::from(self.protocol_quorum_bps.get_or_default()))
+                .saturating_div(10_000);
+
+            proposal.status = if participation > 0 && participation >= quorum && proposal.yes_weight > proposal.no_weight {
+                self._apply_protocol_action(&proposal.action)?;
+                ProtocolProposalStatus::Executed
             } else {
-                0
+                ProtocolProposalStatus::Defeated
             };
-            
-            // Total score capped at 100
-            (lunes_score as u32 + other_score as u32).min(100) as u8
+
+            self.protocol_proposals.insert(proposal_id, &proposal);
+
+            self.env().emit_event(ProtocolProposalFinalized {
+                proposal_id,
+                status: proposal.status,
+                yes_weight: proposal.yes_weight,
+                no_weight: proposal.no_weight,
+            });
+
+            Ok(proposal.status)
         }
 
-        /// Fast calculation for other tokens score
-        fn _calculate_other_tokens_score_fast(
-            &self,
-            project_id: ProjectId,
-            lunes_id: TokenId,
-            token_count: u32,
-            nft_count: u32,
-        ) -> u8 {
-            let mut other_value: Balance = 0;
-            
-            // Only iterate if there are tokens to check
-            if token_count > 1 {
-                // Iterate only up to token_count (optimization)
-                for token_id in 0..self.next_token_id.min(token_count as u64 + 1) {
-                    if token_id == lunes_id {
-                        continue;
+        /// Obter proposta do protocolo
+        #[ink(message)]
+        pub fn get_protocol_proposal(&self, proposal_id: ProposalId) -> Option<ProtocolProposal> {
+            self.protocol_proposals.get(proposal_id)
+        }
+
+        /// Obter voto de uma conta em proposta do protocolo
+        #[ink(message)]
+        pub fn get_protocol_ballot(&self, proposal_id: ProposalId, voter: AccountId) -> Option<Ballot> {
+            self.protocol_ballots.get((proposal_id, voter))
+        }
+
+        /// Setters globais diretos: admin de bootstrap, enquanto a governança do protocolo não foi ativada
+        fn _ensure_protocol_admin(&self) -> Result<(), SafeguardError> {
+            self._ensure_bootstrap_admin()?;
+            if self.protocol_governance_enabled.get_or_default() {
+                return Err(SafeguardError::GovernanceRequired);
+            }
+            Ok(())
+        }
+
+        /// Valida a alteração antes de abrir a votação
+        fn _validate_protocol_action(&self, action: &ProtocolAction) -> Result<(), SafeguardError> {
+            match action {
+                ProtocolAction::SetScoreParameters(params) => Self::_validate_score_parameters(params),
+                ProtocolAction::SetDepositFees { .. } => Ok(()),
+                ProtocolAction::AddSupportedToken { contract_address, min_guarantee_amount, .. } => {
+                    validation::validate_account(*contract_address)?;
+                    validation::validate_amount(*min_guarantee_amount)
+                }
+                #[cfg(feature = "nft-collateral")]
+                ProtocolAction::AddNftCollection { contract_address, base_value, .. } => {
+                    validation::validate_account(*contract_address)?;
+                    validation::validate_amount(*base_value)
+                }
+                #[cfg(not(feature = "nft-collateral"))]
+                ProtocolAction::AddNftCollection { .. } => Err(SafeguardError::FeatureDisabled),
+                ProtocolAction::SetTreasuryAddress(treasury) => validation::validate_account(*treasury),
+                ProtocolAction::SetGuardian { account, .. } => validation::validate_account(*account),
+                ProtocolAction::SetOutflowLimit { token_id, limit } => self._validate_outflow_limit(*token_id, limit),
+                ProtocolAction::SetEscapeHatchDelay(delay) => validation::validate_amount(*delay as Balance),
+                ProtocolAction::SetTokenSelectorScheme { token_id, scheme } => {
+                    self._validate_selector_scheme(*token_id, scheme)
+                }
+                ProtocolAction::SetProtocolComplianceHook(hook) => match hook {
+                    Some(hook) => validation::validate_account(*hook),
+                    None => Ok(()),
+                },
+                ProtocolAction::SetComplianceFailurePolicy(_) => Ok(()),
+                ProtocolAction::SetVaultCodeHash(code_hash) => Self::_validate_vault_code_hash(code_hash),
+                ProtocolAction::SetScoreEngine(engine) => match engine {
+                    Some(engine) => validation::validate_account(*engine),
+                    None => Ok(()),
+                },
+                ProtocolAction::UpgradeCode(code_hash) => {
+                    // Sem timelock não há fila para o upgrade aprovado
+                    if cfg!(not(feature = "timelock")) {
+                        return Err(SafeguardError::FeatureDisabled);
                     }
-                    
-                    let amount = self.project_total_guarantees.get((project_id, token_id)).unwrap_or(0);
-                    if amount > 0 {
-                        // Apply 90% haircut for other PSP22 tokens
-                        other_value = other_value.saturating_add(amount.saturating_mul(10).saturating_div(100));
+                    if *code_hash == Hash::from([0u8; 32]) {
+                        return Err(SafeguardError::InvalidInput);
                     }
-                }
-            }
-            
-            // Add NFT value with 50% haircut (only if there are NFTs)
-            if nft_count > 0 {
-                for collection_id in 0..self.next_nft_collection_id.min(nft_count as u64 + 1) {
-                    let nft_value = self.project_nft_guarantees.get((project_id, collection_id)).unwrap_or(0);
-                    if nft_value > 0 {
-                        other_value = other_value.saturating_add(nft_value.saturating_mul(50).saturating_div(100));
+                    // Upgrades encadeados só depois de migrar o anterior
+                    if self.storage_version.get_or_default() < STORAGE_VERSION {
+                        return Err(SafeguardError::MigrationPending);
                     }
+                    Ok(())
                 }
             }
-            
-            // Calculate score (max 5 points)
-            let score = other_value.saturating_mul(5).saturating_div(self.t_min.max(1));
-            score.min(5) as u8
         }
 
-        /// Update token count cache when adding guarantee
-        fn _update_token_count_cache(&mut self, project_id: ProjectId, token_id: TokenId, is_add: bool) {
-            let current_amount = self.project_total_guarantees.get((project_id, token_id)).unwrap_or(0);
-            let current_count = self.project_token_count.get(project_id).unwrap_or(0);
-            
-            if is_add && current_amount == 0 {
-                // First deposit of this token for this project
-                self.project_token_count.insert(project_id, &(current_count.saturating_add(1)));
-            } else if !is_add && current_amount == 0 && current_count > 0 {
-                // Last withdrawal of this token for this project
-                self.project_token_count.insert(project_id, &(current_count.saturating_sub(1)));
+        /// Aplica uma alteração de parâmetros globais já autorizada
+        fn _apply_protocol_action(&mut self, action: &ProtocolAction) -> Result<(), SafeguardError> {
+            match action {
+                ProtocolAction::SetScoreParameters(params) => self._set_score_parameters(params),
+                ProtocolAction::SetDepositFees { lunes_fee, lusdt_fee } => {
+                    self._set_deposit_fees(*lunes_fee, *lusdt_fee);
+                    Ok(())
+                }
+                ProtocolAction::AddSupportedToken { contract_address, symbol, decimals, min_guarantee_amount } => {
+                    self._add_supported_token(*contract_address, *symbol, *decimals, *min_guarantee_amount)
+                        .map(|_| ())
+                }
+                #[cfg(feature = "nft-collateral")]
+                ProtocolAction::AddNftCollection { contract_address, name, symbol, base_value, valuation_method } => {
+                    self._add_nft_collection(*contract_address, *name, *symbol, *base_value, valuation_method.clone())
+                        .map(|_| ())
+                }
+                #[cfg(not(feature = "nft-collateral"))]
+                ProtocolAction::AddNftCollection { .. } => Err(SafeguardError::FeatureDisabled),
+                ProtocolAction::SetTreasuryAddress(treasury) => {
+                    self._set_treasury_address(*treasury);
+                    Ok(())
+                }
+                ProtocolAction::SetGuardian { account, enabled } => self._set_guardian(*account, *enabled),
+                ProtocolAction::SetOutflowLimit { token_id, limit } => {
+                    self._set_outflow_limit(*token_id, *limit);
+                    Ok(())
+                }
+                ProtocolAction::SetEscapeHatchDelay(delay) => self._set_escape_hatch_delay(*delay),
+                ProtocolAction::SetTokenSelectorScheme { token_id, scheme } => {
+                    self._set_token_selector_scheme(*token_id, *scheme)
+                }
+                ProtocolAction::SetProtocolComplianceHook(hook) => {
+                    self._set_protocol_compliance_hook(*hook);
+                    Ok(())
+                }
+                ProtocolAction::SetComplianceFailurePolicy(policy) => {
+                    self._set_compliance_failure_policy(*policy);
+                    Ok(())
+                }
+                ProtocolAction::SetVaultCodeHash(code_hash) => {
+                    self._set_vault_code_hash(*code_hash);
+                    Ok(())
+                }
+                ProtocolAction::SetScoreEngine(engine) => {
+                    self._set_score_engine(*engine);
+                    Ok(())
+                }
+                ProtocolAction::UpgradeCode(code_hash) => self._queue_governed_upgrade(*code_hash),
             }
         }
 
-        /// Update NFT count cache
-        fn _update_nft_count_cache(&mut self, project_id: ProjectId, collection_id: NFTCollectionId, is_add: bool) {
-            let current_amount = self.project_nft_guarantees.get((project_id, collection_id)).unwrap_or(0);
-            let current_count = self.project_nft_count.get(project_id).unwrap_or(0);
-            
-            if is_add && current_amount == 0 {
-                self.project_nft_count.insert(project_id, &(current_count.saturating_add(1)));
-            } else if !is_add && current_amount == 0 && current_count > 0 {
-                self.project_nft_count.insert(project_id, &(current_count.saturating_sub(1)));
-            }
+        /// Upgrade aprovado pela DAO entra na fila do timelock, com o atraso mínimo de
+        /// UpgradeCode e o veto dos guardiões; o código só é trocado em `execute_operation`
+        #[cfg(feature = "timelock")]
+        fn _queue_governed_upgrade(&mut self, code_hash: Hash) -> Result<(), SafeguardError> {
+            // O contador de operações nunca se repete, então serve de salt
+            let mut salt = [0u8; 32];
+            salt[..4].copy_from_slice(&self.operation_count.get_or_default().to_le_bytes());
+            let core = self.env().account_id();
+            self._queue_operation(TimelockFunction::UpgradeCode, scale::Encode::encode(&code_hash), Hash::from(salt), core)
+                .map(|_| ())
         }
-    }
 
-    /// Tests module
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-        use ink::env::test::{default_accounts, set_caller};
+        #[cfg(not(feature = "timelock"))]
+        fn _queue_governed_upgrade(&mut self, _code_hash: Hash) -> Result<(), SafeguardError> {
+            Err(SafeguardError::FeatureDisabled)
+        }
 
-        fn setup() -> (Safeguard, ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment>) {
-            let accounts = default_accounts();
-            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
-            let contract = Safeguard::new();
-            (contract, accounts)
+        /// Troca o código do contrato; a próxima chamada já usa o novo código, que exige
+        /// `migrate` se a versão mudou
+        #[cfg(feature = "timelock")]
+        fn _upgrade_code(&mut self, code_hash: Hash) -> Result<(), SafeguardError> {
+            self.env().set_code_hash(&code_hash).map_err(|_| SafeguardError::CodeUpgradeFailed)?;
+            self.env().emit_event(CodeUpgraded {
+                code_hash,
+                storage_version: self.storage_version.get_or_default(),
+            });
+            Ok(())
         }
 
-        #[ink::test]
-        fn test_constructor() {
-            let (contract, accounts) = setup();
-            assert_eq!(contract.owner(), Some(accounts.alice));
-            assert_eq!(contract.next_project_id, 0);
+        // ==================== PAUSABILITY FUNCTIONS ====================
+
+        /// Pause the contract with a reason code (Pauser)
+        #[ink(message)]
+        pub fn pause(&mut self, reason: u16) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
+            self._ensure_role(Role::Pauser)?;
+            
+            if self.is_paused {
+                return Err(SafeguardError::ContractPaused);
+            }
+            
+            self.is_paused = true;
+            self.paused_at = self.env().block_timestamp();
+            self.pause_reason.set(&reason);
+            
+            self.env().emit_event(ContractPausedEvent {
+                paused_by: caller,
+                reason,
+                timestamp: self.paused_at,
+            });
+            
+            Ok(())
         }
 
-        #[ink::test]
-        fn test_project_registration() {
-            let (mut contract, accounts) = setup();
+        /// Unpause the contract (Pauser; anyone once the max pause duration has elapsed)
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
             
-            // Register a new project
-            let result = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            );
-            assert!(result.is_ok());
-            let project_id = result.unwrap();
-            assert_eq!(project_id, 0);
-            assert_eq!(contract.next_project_id, 1);
+            if !self.is_paused {
+                return Err(SafeguardError::ContractNotPaused);
+            }
+            self._ensure_no_escape_hatch()?;
+            self._ensure_can_unpause(self.paused_at)?;
+            
+            self.is_paused = false;
+            
+            self.env().emit_event(ContractUnpausedEvent {
+                unpaused_by: caller,
+                timestamp: self.env().block_timestamp(),
+            });
+            
+            Ok(())
         }
 
-        #[ink::test]
-        fn test_contract_ownership() {
-            let (mut contract, accounts) = setup();
+        /// Pausa apenas um subsistema, mantendo os demais operando (Pauser)
+        #[ink(message)]
+        pub fn pause_module(&mut self, module: PauseModule, reason: u16) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
+            self._ensure_role(Role::Pauser)?;
             
-            // Transfer contract ownership
-            let result = contract.transfer_contract_ownership(accounts.bob);
-            assert!(result.is_ok());
-            assert_eq!(contract.owner(), Some(accounts.bob));
+            if self.module_pauses.contains(module) {
+                return Err(SafeguardError::ModulePaused);
+            }
+            
+            let paused_at = self.env().block_timestamp();
+            self.module_pauses.insert(module, &PauseInfo { paused_by: caller, reason, paused_at });
+            
+            self.env().emit_event(ModulePausedEvent {
+                module,
+                paused_by: caller,
+                reason,
+                timestamp: paused_at,
+            });
+            
+            Ok(())
         }
 
-        #[ink::test]
-        fn test_vote_activation() {
-            let (mut contract, accounts) = setup();
+        /// Reativa um subsistema (Pauser; qualquer conta após o prazo máximo)
+        #[ink(message)]
+        pub fn unpause_module(&mut self, module: PauseModule) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
+            let info = self.module_pauses.get(module).ok_or(SafeguardError::ContractNotPaused)?;
+            self._ensure_can_unpause(info.paused_at)?;
             
-            // First register a project
-            let project_result = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            );
-            assert!(project_result.is_ok());
-            let project_id = project_result.unwrap();
+            self.module_pauses.remove(module);
             
-            // Activate voting for the project
-            let result = contract.vote_active(project_id, true, 1000);
-            assert!(result.is_ok());
+            self.env().emit_event(ModuleUnpausedEvent {
+                module,
+                unpaused_by: caller,
+                timestamp: self.env().block_timestamp(),
+            });
             
-            // Check project info
-            let info = contract.get_project_info(project_id);
-            assert!(info.is_some());
-            let project_info = info.unwrap();
-            assert!(project_info.status);
-            assert_eq!(project_info.balance_permission, 1000);
+            Ok(())
         }
 
-        #[ink::test]
-        fn test_unauthorized_access() {
-            let (mut contract, accounts) = setup();
+        /// Define a duração máxima das pausas; None desativa o desbloqueio público (Admin)
+        #[ink(message)]
+        pub fn set_max_pause_duration(&mut self, max_duration: Option<u64>) -> Result<(), SafeguardError> {
+            self._ensure_role(Role::Admin)?;
             
-            // Register a project as alice
-            let project_result = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            );
-            assert!(project_result.is_ok());
-            let project_id = project_result.unwrap();
+            if max_duration == Some(0) {
+                return Err(SafeguardError::InvalidInput);
+            }
             
-            // Try to activate voting as non-owner (bob)
-            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
-            let result = contract.vote_active(project_id, true, 1000);
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::NotOwner);
+            self.max_pause_duration.set(&max_duration);
+            self.env().emit_event(MaxPauseDurationUpdated { max_duration });
+            Ok(())
         }
 
-        #[ink::test]
-        fn test_arithmetic_overflow_protection() {
-            let result = safe_math::safe_add(u64::MAX, 1);
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::ArithmeticOverflow);
+        /// Check if contract is paused
+        #[ink(message)]
+        pub fn is_contract_paused(&self) -> bool {
+            self.is_paused
         }
 
-        #[ink::test]
-        fn test_input_validation() {
-            let zero_account = AccountId::from([0u8; 32]);
-            let result = validation::validate_account(zero_account);
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::ZeroAddress);
+        /// Get pause timestamp
+        #[ink(message)]
+        pub fn get_paused_at(&self) -> u64 {
+            self.paused_at
         }
 
-        #[ink::test]
-        fn test_reentrancy_protection() {
+        /// Código do motivo da pausa global mais recente
+        #[ink(message)]
+        pub fn get_pause_reason(&self) -> u16 {
+            self.pause_reason.get_or_default()
+        }
+
+        /// Pausa ativa de um subsistema, se houver
+        #[ink(message)]
+        pub fn get_module_pause(&self, module: PauseModule) -> Option<PauseInfo> {
+            self.module_pauses.get(module)
+        }
+
+        /// True se o subsistema está bloqueado (pausa global ou do módulo)
+        #[ink(message)]
+        pub fn is_module_paused(&self, module: PauseModule) -> bool {
+            self.is_paused || self.module_pauses.contains(module)
+        }
+
+        #[ink(message)]
+        pub fn get_max_pause_duration(&self) -> Option<u64> {
+            self.max_pause_duration.get_or_default()
+        }
+
+        /// Internal modifier to check if contract is not paused
+        fn _ensure_not_paused(&self) -> Result<(), SafeguardError> {
+            if self.is_paused {
+                return Err(SafeguardError::ContractPaused);
+            }
+            // Storage ainda no layout anterior ao upgrade
+            if self.storage_version.get_or_default() < STORAGE_VERSION {
+                return Err(SafeguardError::MigrationPending);
+            }
+            if self.import_state.get() == Some(ImportState::Active) {
+                return Err(SafeguardError::ImportInProgress);
+            }
+            Ok(())
+        }
+
+        // ==================== UPGRADE ====================
+
+        /// Executa as migrações pendentes após um upgrade de código.
+        /// Qualquer conta pode chamar: os passos são fixos no código e cada versão roda uma única vez.
+        #[ink(message)]
+        pub fn migrate(&mut self) -> Result<u32, SafeguardError> {
+            if self.storage_version.get_or_default() >= STORAGE_VERSION {
+                return Err(SafeguardError::NoMigrationPending);
+            }
+            while self.storage_version.get_or_default() < STORAGE_VERSION {
+                let from_version = self.storage_version.get_or_default();
+                self._migrate_from(from_version)?;
+                self.storage_version.set(&from_version.saturating_add(1));
+                self.env().emit_event(StorageMigrated {
+                    from_version,
+                    to_version: self.storage_version.get_or_default(),
+                });
+            }
+            Ok(self.storage_version.get_or_default())
+        }
+
+        /// Transformação de storage de `version` para `version + 1`
+        fn _migrate_from(&mut self, version: u32) -> Result<(), SafeguardError> {
+            match version {
+                0 => {
+                    self._migrate_v0_to_v1();
+                    Ok(())
+                }
+                _ => Ok(()),
+            }
+        }
+
+        /// Layout v0 (owner único) para v1. Deployments que já nasceram v1 têm
+        /// `contract_owner` zerado e não têm nada a converter.
+        fn _migrate_v0_to_v1(&mut self) {
+            let owner = self.contract_owner;
+            if owner == AccountId::from([0u8; 32]) {
+                return;
+            }
+
+            // O owner v0 vira o conselho 1-de-1, Admin e admin de bootstrap, como num deploy novo
+            self._seed_governance(owner);
+            if !self.has_role(Role::Admin, owner) {
+                self.roles.insert((Role::Admin, owner), &true);
+                self.env().emit_event(RoleGranted {
+                    role: Role::Admin,
+                    account: owner,
+                    sender: self.env().account_id(),
+                });
+            }
+            self.contract_owner = AccountId::from([0u8; 32]);
+
+            // Valores gravados com structs que ganharam campos: regravados no formato atual.
+            // Os tokens v0 eram chamados com seletores sem namespace (`Bare`).
+            let tokens_key = self.supported_tokens.key();
+            for token_id in 0..self.next_token_id {
+                if let Ok(Some(token)) = ink::env::get_contract_storage::<_, TokenInfoV0>(&(&tokens_key, token_id)) {
+                    self.supported_tokens.insert(token_id, &TokenInfo {
+                        contract_address: token.contract_address,
+                        symbol: token.symbol,
+                        decimals: token.decimals,
+                        is_active: token.is_active,
+                        min_guarantee_amount: token.min_guarantee_amount,
+                        price_feed_id: token.price_feed_id,
+                        selector_scheme: SelectorScheme::Bare,
+                    });
+                }
+            }
+            let votings_key = self.voting_infos.key();
+            for voting_id in 0..self.next_voting_id {
+                if let Ok(Some(voting)) = ink::env::get_contract_storage::<_, VotingInfoV0>(&(&votings_key, voting_id)) {
+                    self.voting_infos.insert(voting_id, &VotingInfo {
+                        vote_type: voting.vote_type,
+                        start_timestamp: voting.start_timestamp,
+                        end_timestamp: voting.end_timestamp,
+                        snapshot_block: voting.snapshot_block,
+                        yes_votes: voting.yes_votes,
+                        no_votes: voting.no_votes,
+                        total_eligible: 0,
+                        result: voting.result,
+                        proposal_uri: voting.proposal_uri,
+                        weighting: VoteWeighting::Linear,
+                        yes_weight: Balance::from(voting.yes_votes),
+                        no_weight: Balance::from(voting.no_votes),
+                        total_eligible_weight: Balance::from(voting.total_eligible),
+                    });
+                }
+            }
+        }
+
+        #[ink(message)]
+        pub fn get_storage_version(&self) -> u32 {
+            self.storage_version.get_or_default()
+        }
+
+        #[ink(message)]
+        pub fn is_migration_pending(&self) -> bool {
+            self.storage_version.get_or_default() < STORAGE_VERSION
+        }
+
+        // ==================== STATE EXPORT / IMPORT ====================
+
+        /// Número de posições exportáveis de um conjunto (páginas cobrem `0..count`)
+        #[ink(message)]
+        pub fn get_export_count(&self, kind: ExportKind) -> u64 {
+            match kind {
+                ExportKind::Vaults => self.next_project_id,
+                ExportKind::Votings => self.next_voting_id,
+                _ => self.export_counts.get(kind).unwrap_or(0) as u64,
+            }
+        }
+
+        /// Página SCALE de `Vec<*Export>` com as posições `start..start + limit`.
+        /// Posições sem dados atuais (ex.: garantia sacada) são omitidas.
+        #[ink(message)]
+        pub fn export_page(&self, kind: ExportKind, start: u64, limit: u32) -> Result<Vec<u8>, SafeguardError> {
+            if limit == 0 || limit > MAX_EXPORT_PAGE {
+                return Err(SafeguardError::InvalidInput);
+            }
+            let end = start.saturating_add(limit as u64).min(self.get_export_count(kind));
+            let data = match kind {
+                ExportKind::Vaults => {
+                    // Fundos em cofres dedicados não chegam ao novo core: retire_vault antes de exportar
+                    if (start..end).any(|id| self.project_vault_contracts.contains(id)) {
+                        return Err(SafeguardError::VaultAlreadyExists);
+                    }
+                    let records: Vec<VaultExport> = (start..end).filter_map(|id| self._export_vault(id)).collect();
+                    scale::Encode::encode(&records)
+                }
+                ExportKind::Votings => {
+                    let records: Vec<VotingExport> = (start..end).filter_map(|id| self._export_voting(id)).collect();
+                    scale::Encode::encode(&records)
+                }
+                ExportKind::Guarantees => {
+                    let records: Vec<GuaranteeExport> = self._export_keys(kind, start, end).into_iter()
+                        .filter_map(|key| match key {
+                            ExportKey::Guarantee(project_id, token_id, account) => self.token_guarantees
+                                .get((project_id, token_id, account))
+                                .map(|amount| GuaranteeExport { project_id, token_id, account, amount }),
+                            _ => None,
+                        })
+                        .collect();
+                    scale::Encode::encode(&records)
+                }
+                ExportKind::Nfts => {
+                    let records: Vec<NftExport> = self._export_keys(kind, start, end).into_iter()
+                        .filter_map(|key| match key {
+                            ExportKey::Nft(project_id, collection_id, account, nft_token_id) => self.nft_guarantees
+                                .get((project_id, collection_id, account, nft_token_id))
+                                .map(|value| NftExport { project_id, collection_id, account, nft_token_id, value }),
+                            _ => None,
+                        })
+                        .collect();
+                    scale::Encode::encode(&records)
+                }
+                ExportKind::Ballots => {
+                    let records: Vec<BallotExport> = self._export_keys(kind, start, end).into_iter()
+                        .filter_map(|key| match key {
+                            ExportKey::Ballot(voting_id, voter) => self.ballots.get((voting_id, voter)).map(|ballot| BallotExport {
+                                voting_id,
+                                voter,
+                                ballot,
+                                reward_claimed: self.voter_rewards_claimed.get((voting_id, voter)).unwrap_or(false),
+                            }),
+                            _ => None,
+                        })
+                        .collect();
+                    scale::Encode::encode(&records)
+                }
+                ExportKind::Claims => {
+                    let records: Vec<ClaimExport> = self._export_keys(kind, start, end).into_iter()
+                        .filter_map(|key| match key {
+                            ExportKey::Claim(project_id, account) => self.user_claims
+                                .get((project_id, account))
+                                .map(|claim| ClaimExport { project_id, account, claim }),
+                            _ => None,
+                        })
+                        .collect();
+                    scale::Encode::encode(&records)
+                }
+            };
+            Ok(data)
+        }
+
+        fn _export_keys(&self, kind: ExportKind, start: u64, end: u64) -> Vec<ExportKey> {
+            (start..end)
+                .filter_map(|position| u32::try_from(position).ok())
+                .filter_map(|position| self.export_keys.get((kind, position)))
+                .collect()
+        }
+
+        fn _export_vault(&self, project_id: ProjectId) -> Option<VaultExport> {
+            let vault = self.project_vaults.get(project_id)?;
+            let token_totals = (0..self.next_token_id)
+                .map(|token_id| (
+                    token_id,
+                    self.project_total_guarantees.get((project_id, token_id)).unwrap_or(0),
+                    self.project_donations.get((project_id, token_id)).unwrap_or(0),
+                ))
+                .filter(|(_, total, donations)| *total > 0 || *donations > 0)
+                .collect();
+            let nft_totals = (0..self.next_nft_collection_id)
+                .filter_map(|collection_id| {
+                    self.project_nft_guarantees.get((project_id, collection_id)).map(|value| (collection_id, value))
+                })
+                .collect();
+            Some(VaultExport {
+                vault,
+                score: self.project_scores.get(project_id).unwrap_or(0),
+                liquidation_voting: self.project_liquidation_votings.get(project_id),
+                pre_voting_status: self.pre_voting_statuses.get(project_id),
+                token_checkpoints: self.project_token_checkpoints.get(project_id).unwrap_or(false),
+                token_totals,
+                nft_totals,
+            })
+        }
+
+        fn _export_voting(&self, voting_id: VotingId) -> Option<VotingExport> {
+            let info = self.voting_infos.get(voting_id)?;
+            let reward_pools = (0..self.next_token_id)
+                .filter_map(|token_id| self.voter_reward_pools.get((voting_id, token_id)).map(|pool| (token_id, pool)))
+                .collect();
+            let reward_tokens = self.get_voter_reward_tokens(voting_id);
+            Some(VotingExport { voting_id, info, reward_pools, reward_tokens })
+        }
+
+        /// Registra a chave na primeira escrita para que a exportação possa paginá-la
+        fn _index_export_key(&mut self, kind: ExportKind, key: ExportKey) {
+            if self.export_key_positions.contains(&key) {
+                return;
+            }
+            let position = self.export_counts.get(kind).unwrap_or(0);
+            self.export_keys.insert((kind, position), &key);
+            self.export_key_positions.insert(&key, &position);
+            self.export_counts.insert(kind, &position.saturating_add(1));
+        }
+
+        /// Abre o modo de importação num deployment sem projetos (admin de bootstrap).
+        /// Tokens e coleções devem ser listados antes, na mesma ordem do contrato antigo,
+        /// e cada NFT transferido ao novo core antes da página que o importa.
+        #[ink(message)]
+        pub fn begin_import(&mut self) -> Result<(), SafeguardError> {
+            self._ensure_protocol_admin()?;
+            match self.import_state.get() {
+                Some(ImportState::Active) => return Err(SafeguardError::ImportInProgress),
+                Some(ImportState::Locked) => return Err(SafeguardError::ImportLocked),
+                None => {}
+            }
+            if self.next_project_id > 0 || self.next_voting_id > 0 {
+                return Err(SafeguardError::InvalidInput);
+            }
+            self.import_state.set(&ImportState::Active);
+            self.env().emit_event(ImportStarted { started_by: self.env().caller() });
+            Ok(())
+        }
+
+        /// Aplica uma página produzida por `export_page`; cofres e votações antes dos registros que os referenciam
+        #[ink(message)]
+        pub fn import_page(&mut self, kind: ExportKind, data: Vec<u8>) -> Result<u32, SafeguardError> {
+            self._ensure_protocol_admin()?;
+            if self.import_state.get() != Some(ImportState::Active) {
+                return Err(SafeguardError::ImportNotActive);
+            }
+            let records = match kind {
+                ExportKind::Vaults => {
+                    let records: Vec<VaultExport> = Self::_decode_args(&data)?;
+                    let count = records.len();
+                    for record in records {
+                        self._import_vault(record)?;
+                    }
+                    count
+                }
+                ExportKind::Guarantees => {
+                    let records: Vec<GuaranteeExport> = Self::_decode_args(&data)?;
+                    let count = records.len();
+                    for record in records {
+                        self._import_guarantee(record)?;
+                    }
+                    count
+                }
+                #[cfg(feature = "nft-collateral")]
+                ExportKind::Nfts => {
+                    let records: Vec<NftExport> = Self::_decode_args(&data)?;
+                    let count = records.len();
+                    for record in records {
+                        self._import_nft(record)?;
+                    }
+                    count
+                }
+                #[cfg(not(feature = "nft-collateral"))]
+                ExportKind::Nfts => return Err(SafeguardError::FeatureDisabled),
+                ExportKind::Votings => {
+                    let records: Vec<VotingExport> = Self::_decode_args(&data)?;
+                    let count = records.len();
+                    for record in records {
+                        self._import_voting(record)?;
+                    }
+                    count
+                }
+                ExportKind::Ballots => {
+                    let records: Vec<BallotExport> = Self::_decode_args(&data)?;
+                    let count = records.len();
+                    for record in records {
+                        if !self.voting_infos.contains(record.voting_id)
+                            || self.ballots.contains((record.voting_id, record.voter))
+                        {
+                            return Err(SafeguardError::InvalidInput);
+                        }
+                        self.ballots.insert((record.voting_id, record.voter), &record.ballot);
+                        if record.reward_claimed {
+                            self.voter_rewards_claimed.insert((record.voting_id, record.voter), &true);
+                        }
+                        self._index_export_key(ExportKind::Ballots, ExportKey::Ballot(record.voting_id, record.voter));
+                    }
+                    count
+                }
+                ExportKind::Claims => {
+                    let records: Vec<ClaimExport> = Self::_decode_args(&data)?;
+                    let count = records.len();
+                    for record in records {
+                        if !self.project_vaults.contains(record.project_id)
+                            || self.user_claims.contains((record.project_id, record.account))
+                        {
+                            return Err(SafeguardError::InvalidInput);
+                        }
+                        self.user_claims.insert((record.project_id, record.account), &record.claim);
+                        self._index_export_key(ExportKind::Claims, ExportKey::Claim(record.project_id, record.account));
+                    }
+                    count
+                }
+            };
+            let records = u32::try_from(records).unwrap_or(u32::MAX);
+            self.env().emit_event(ImportPageApplied { kind, records });
+            Ok(records)
+        }
+
+        fn _import_vault(&mut self, record: VaultExport) -> Result<(), SafeguardError> {
+            let project_id = record.vault.project_id;
+            if self.project_vaults.contains(project_id) {
+                return Err(SafeguardError::InvalidInput);
+            }
+            for (token_id, total, donations) in record.token_totals {
+                if !self.supported_tokens.contains(token_id) {
+                    return Err(SafeguardError::TokenNotSupported);
+                }
+                // O total inclui a parcela doada; o restante deve vir das garantias importadas
+                let deposits = total.checked_sub(donations).ok_or(SafeguardError::InvalidInput)?;
+                self._open_import_gap(ImportGap::Guarantee(project_id, token_id), deposits);
+                self._set_project_total_guarantee(project_id, token_id, total);
+                if donations > 0 {
+                    self.project_donations.insert((project_id, token_id), &donations);
+                }
+                if total > 0 {
+                    let count = self.project_token_count.get(project_id).unwrap_or(0);
+                    self.project_token_count.insert(project_id, &count.saturating_add(1));
+                }
+                let expected = self.import_expected_balances.get(token_id).unwrap_or(0);
+                self.import_expected_balances.insert(token_id, &safe_math::safe_add_balance(expected, total)?);
+            }
+            for (collection_id, value) in record.nft_totals {
+                if !self.supported_nft_collections.contains(collection_id) {
+                    return Err(SafeguardError::NFTNotSupported);
+                }
+                self.project_nft_guarantees.insert((project_id, collection_id), &value);
+                self._open_import_gap(ImportGap::Nft(project_id, collection_id), value);
+                let count = self.project_nft_count.get(project_id).unwrap_or(0);
+                self.project_nft_count.insert(project_id, &count.saturating_add(1));
+            }
+            if let Some(voting_id) = record.liquidation_voting {
+                self.project_liquidation_votings.insert(project_id, &voting_id);
+            }
+            if let Some(status) = record.pre_voting_status {
+                self.pre_voting_statuses.insert(project_id, &status);
+            }
+            if record.token_checkpoints {
+                self.project_token_checkpoints.insert(project_id, &true);
+            }
+            self.project_scores.insert(project_id, &record.score);
+            self.project_owners.insert(project_id, &record.vault.owner);
+            self.project_creation_timestamps.insert(project_id, &record.vault.creation_timestamp);
+            self.project_statuses.insert(project_id, &true);
+            self.project_vaults.insert(project_id, &record.vault);
+            self.next_project_id = self.next_project_id.max(project_id.saturating_add(1));
+            Ok(())
+        }
+
+        fn _import_guarantee(&mut self, record: GuaranteeExport) -> Result<(), SafeguardError> {
+            let GuaranteeExport { project_id, token_id, account, amount } = record;
+            if !self.project_vaults.contains(project_id) || self.token_guarantees.contains((project_id, token_id, account)) {
+                return Err(SafeguardError::InvalidInput);
+            }
+            if !self.supported_tokens.contains(token_id) {
+                return Err(SafeguardError::TokenNotSupported);
+            }
+            validation::validate_amount(amount)?;
+            // O total do projeto veio com o cofre; aqui só a posição do depositante
+            self._close_import_gap(ImportGap::Guarantee(project_id, token_id), amount)?;
+            self._set_user_guarantee(project_id, token_id, account, amount);
+            let balance = self.user_token_balances.get((account, token_id)).unwrap_or(0);
+            self._set_user_token_balance(account, token_id, safe_math::safe_add_balance(balance, amount)?);
+            Ok(())
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        fn _import_nft(&mut self, record: NftExport) -> Result<(), SafeguardError> {
+            let NftExport { project_id, collection_id, account, nft_token_id, value } = record;
+            let key = (project_id, collection_id, account, nft_token_id);
+            if !self.project_vaults.contains(project_id) || self.nft_custody.contains((collection_id, nft_token_id)) {
+                return Err(SafeguardError::InvalidInput);
+            }
+            let collection = self.supported_nft_collections.get(collection_id).ok_or(SafeguardError::NFTNotSupported)?;
+            // O NFT precisa ter sido transferido ao novo core antes da página
+            if Psp34Client::new(collection.contract_address).owner_of(nft_token_id)? != Some(self.env().account_id()) {
+                return Err(SafeguardError::ImportBalanceMismatch);
+            }
+            self._close_import_gap(ImportGap::Nft(project_id, collection_id), value)?;
+            self.nft_guarantees.insert(key, &value);
+            self.nft_custody.insert((collection_id, nft_token_id), &(project_id, account));
+            self._checkpoint_nft_value(project_id, account, value, true);
+            let count = self.user_nft_deposits.get((account, collection_id)).unwrap_or(0);
+            self.user_nft_deposits.insert((account, collection_id), &count.saturating_add(1));
+            self._index_export_key(ExportKind::Nfts, ExportKey::Nft(project_id, collection_id, account, nft_token_id));
+            Ok(())
+        }
+
+        fn _import_voting(&mut self, record: VotingExport) -> Result<(), SafeguardError> {
+            let voting_id = record.voting_id;
+            if self.voting_infos.contains(voting_id) {
+                return Err(SafeguardError::InvalidInput);
+            }
+            for (token_id, pool) in record.reward_pools {
+                if !self.supported_tokens.contains(token_id) {
+                    return Err(SafeguardError::TokenNotSupported);
+                }
+                self.voter_reward_pools.insert((voting_id, token_id), &pool);
+                let expected = self.import_expected_balances.get(token_id).unwrap_or(0);
+                self.import_expected_balances.insert(token_id, &safe_math::safe_add_balance(expected, pool)?);
+            }
+            if record.reward_tokens != (None, None) {
+                self.voter_reward_tokens.insert(voting_id, &record.reward_tokens);
+            }
+            self.voting_infos.insert(voting_id, &record.info);
+            self.next_voting_id = self.next_voting_id.max(voting_id.saturating_add(1));
+            Ok(())
+        }
+
+        /// Diferença que os registros por depositante ainda precisam cobrir
+        fn _open_import_gap(&mut self, gap: ImportGap, amount: Balance) {
+            if amount == 0 {
+                return;
+            }
+            match gap {
+                ImportGap::Guarantee(project_id, token_id) => self.import_guarantee_gaps.insert((project_id, token_id), &amount),
+                ImportGap::Nft(project_id, collection_id) => self.import_nft_gaps.insert((project_id, collection_id), &amount),
+            };
+            self.import_open_gaps.set(&self.import_open_gaps.get_or_default().saturating_add(1));
+        }
+
+        /// Abate um registro importado; exceder o total do cofre é rejeitado na hora
+        fn _close_import_gap(&mut self, gap: ImportGap, amount: Balance) -> Result<(), SafeguardError> {
+            let open = match gap {
+                ImportGap::Guarantee(project_id, token_id) => self.import_guarantee_gaps.get((project_id, token_id)),
+                ImportGap::Nft(project_id, collection_id) => self.import_nft_gaps.get((project_id, collection_id)),
+            }.unwrap_or(0);
+            let remaining = open.checked_sub(amount).ok_or(SafeguardError::ImportTotalsMismatch)?;
+            if remaining > 0 {
+                match gap {
+                    ImportGap::Guarantee(project_id, token_id) => self.import_guarantee_gaps.insert((project_id, token_id), &remaining),
+                    ImportGap::Nft(project_id, collection_id) => self.import_nft_gaps.insert((project_id, collection_id), &remaining),
+                };
+                return Ok(());
+            }
+            match gap {
+                ImportGap::Guarantee(project_id, token_id) => self.import_guarantee_gaps.remove((project_id, token_id)),
+                ImportGap::Nft(project_id, collection_id) => self.import_nft_gaps.remove((project_id, collection_id)),
+            }
+            self.import_open_gaps.set(&self.import_open_gaps.get_or_default().saturating_sub(1));
+            Ok(())
+        }
+
+        /// Confere os registros contra os totais dos cofres e os saldos recebidos, e trava a importação para sempre
+        #[ink(message)]
+        pub fn finalize_import(&mut self) -> Result<(), SafeguardError> {
+            self._ensure_protocol_admin()?;
+            if self.import_state.get() != Some(ImportState::Active) {
+                return Err(SafeguardError::ImportNotActive);
+            }
+            if self.import_open_gaps.get_or_default() > 0 {
+                return Err(SafeguardError::ImportTotalsMismatch);
+            }
+            let this = self.env().account_id();
+            for token_id in 0..self.next_token_id {
+                let expected = self.import_expected_balances.get(token_id).unwrap_or(0);
+                if expected > 0 && self._psp22_balance_of(token_id, this)? < expected {
+                    return Err(SafeguardError::ImportBalanceMismatch);
+                }
+            }
+            self.import_state.set(&ImportState::Locked);
+            self.env().emit_event(ImportFinalized {
+                finalized_by: self.env().caller(),
+                timestamp: self.env().block_timestamp(),
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_import_state(&self) -> Option<ImportState> {
+            self.import_state.get()
+        }
+
+        #[ink(message)]
+        pub fn get_import_expected_balance(&self, token_id: TokenId) -> Balance {
+            self.import_expected_balances.get(token_id).unwrap_or(0)
+        }
+
+        /// Pausa global e circuit breaker do subsistema
+        fn _ensure_module_active(&self, module: PauseModule) -> Result<(), SafeguardError> {
+            self._ensure_not_paused()?;
+            if self.module_pauses.contains(module) {
+                return Err(SafeguardError::ModulePaused);
+            }
+            Ok(())
+        }
+
+        /// Despausar exige Pauser, exceto quando a pausa excedeu a duração máxima
+        fn _ensure_can_unpause(&self, paused_at: u64) -> Result<(), SafeguardError> {
+            if let Some(max_duration) = self.max_pause_duration.get_or_default() {
+                if self.env().block_timestamp() >= paused_at.saturating_add(max_duration) {
+                    return Ok(());
+                }
+            }
+            self._ensure_role(Role::Pauser)
+        }
+
+        // ==================== OUTFLOW RATE LIMIT ====================
+
+        /// Define o limite de saída de um token; None remove o limite (admin de bootstrap; depois via timelock ou DAO)
+        #[ink(message)]
+        pub fn set_outflow_limit(&mut self, token_id: TokenId, limit: Option<OutflowLimit>) -> Result<(), SafeguardError> {
+            self._ensure_protocol_admin()?;
+            self._validate_outflow_limit(token_id, &limit)?;
+            self._set_outflow_limit(token_id, limit);
+            Ok(())
+        }
+
+        fn _validate_outflow_limit(&self, token_id: TokenId, limit: &Option<OutflowLimit>) -> Result<(), SafeguardError> {
+            if !self.supported_tokens.contains(token_id) {
+                return Err(SafeguardError::TokenNotSupported);
+            }
+            if let Some(limit) = limit {
+                validation::validate_amount(limit.max_amount)?;
+                if limit.window == 0 {
+                    return Err(SafeguardError::InvalidInput);
+                }
+            }
+            Ok(())
+        }
+
+        fn _set_outflow_limit(&mut self, token_id: TokenId, limit: Option<OutflowLimit>) {
+            if let Some(limit) = limit {
+                self.outflow_limits.insert(token_id, &limit);
+            } else {
+                self.outflow_limits.remove(token_id);
+            }
+            self.env().emit_event(OutflowLimitUpdated { token_id, limit });
+        }
+
+        #[ink(message)]
+        pub fn get_outflow_limit(&self, token_id: TokenId) -> Option<OutflowLimit> {
+            self.outflow_limits.get(token_id)
+        }
+
+        /// Capacidade disponível na janela atual (None = sem limite)
+        #[ink(message)]
+        pub fn get_available_outflow(&self, token_id: TokenId) -> Option<Balance> {
+            self.outflow_limits.get(token_id)
+                .map(|limit| limit.max_amount.saturating_sub(self._outflow_used(token_id, &limit)))
+        }
+
+        #[ink(message)]
+        pub fn get_queued_outflow(&self, queue_id: u64) -> Option<QueuedOutflow> {
+            self.queued_outflows.get(queue_id)
+        }
+
+        #[ink(message)]
+        pub fn get_next_queued_outflow_id(&self) -> u64 {
+            self.next_queued_outflow_id.get_or_default()
+        }
+
+        /// Libera uma saída enfileirada: qualquer conta quando houver capacidade, guardiões a qualquer momento
+        #[ink(message)]
+        pub fn release_queued_outflow(&mut self, queue_id: u64) -> Result<(), SafeguardError> {
+            self.reentrancy_guard.start()?;
+            let result = self._release_queued_outflow(queue_id);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _release_queued_outflow(&mut self, queue_id: u64) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
+            let queued = self.queued_outflows.get(queue_id).ok_or(SafeguardError::QueuedOutflowNotFound)?;
+            
+            match queued.kind {
+                OutflowKind::Withdrawal(_) => self._ensure_module_active(PauseModule::Withdrawals)?,
+                OutflowKind::Claim(_) | OutflowKind::VoterReward(_) => self._ensure_module_active(PauseModule::Claims)?,
+                OutflowKind::Emergency => {}
+            }
+            
+            if self.is_guardian(caller) || self.escape_hatch_activated_at.get_or_default().is_some() {
+                self._record_outflow(queued.token_id, queued.amount);
+            } else if !self._try_consume_outflow(queued.token_id, queued.amount) {
+                return Err(SafeguardError::OutflowLimitExceeded);
+            }
+            
+            self.queued_outflows.remove(queue_id);
+            
+            self.env().emit_event(OutflowReleased {
+                queue_id,
+                released_by: caller,
+                amount: queued.amount,
+            });
+            
+            self._pay_outflow(queued.kind, queued.token_id, queued.recipient, queued.amount)
+        }
+
+        /// Paga a saída se couber no limite do token; caso contrário enfileira
+        fn _send_outflow(
+            &mut self,
+            kind: OutflowKind,
+            token_id: TokenId,
+            recipient: AccountId,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            if self.escape_hatch_activated_at.get_or_default().is_some() || self._try_consume_outflow(token_id, amount) {
+                return self._pay_outflow(kind, token_id, recipient, amount);
+            }
+            
+            let queue_id = self.next_queued_outflow_id.get_or_default();
+            self.next_queued_outflow_id.set(&queue_id.saturating_add(1));
+            self.queued_outflows.insert(queue_id, &QueuedOutflow {
+                kind,
+                token_id,
+                recipient,
+                amount,
+                queued_at: self.env().block_timestamp(),
+            });
+            
+            self.env().emit_event(OutflowQueued {
+                queue_id,
+                token_id,
+                recipient,
+                amount,
+                kind,
+            });
+            
+            Ok(())
+        }
+
+        /// Garantias e claims saem da custódia do projeto; as demais saídas, do core
+        fn _pay_outflow(
+            &mut self,
+            kind: OutflowKind,
+            token_id: TokenId,
+            recipient: AccountId,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            match kind {
+                OutflowKind::Withdrawal(project_id) => {
+                    self._project_transfer(project_id, token_id, recipient, amount)?;
+                    self.env().emit_event(GuaranteeWithdrawn {
+                        project_id,
+                        token_id,
+                        account: recipient,
+                        amount,
+                    });
+                    Ok(())
+                }
+                OutflowKind::Claim(project_id) => self._project_transfer(project_id, token_id, recipient, amount),
+                _ => self._psp22_transfer(token_id, recipient, amount),
+            }
+        }
+
+        /// Consome capacidade da janela; false se a saída excede o limite
+        fn _try_consume_outflow(&mut self, token_id: TokenId, amount: Balance) -> bool {
+            if let Some(limit) = self.outflow_limits.get(token_id) {
+                let used = self._outflow_used(token_id, &limit).saturating_add(amount);
+                if used > limit.max_amount {
+                    return false;
+                }
+                self.outflow_usage.insert(token_id, &OutflowUsage { used, updated_at: self.env().block_timestamp() });
+            }
+            true
+        }
+
+        /// Registra a saída mesmo acima do limite (liberação dos guardiões)
+        fn _record_outflow(&mut self, token_id: TokenId, amount: Balance) {
+            if let Some(limit) = self.outflow_limits.get(token_id) {
+                let used = self._outflow_used(token_id, &limit).saturating_add(amount);
+                self.outflow_usage.insert(token_id, &OutflowUsage { used, updated_at: self.env().block_timestamp() });
+            }
+        }
+
+        /// Uso na janela móvel: a capacidade se recompõe linearmente em `window`
+        fn _outflow_used(&self, token_id: TokenId, limit: &OutflowLimit) -> Balance {
+            let usage = self.outflow_usage.get(token_id).unwrap_or_default();
+            let elapsed = self.env().block_timestamp().saturating_sub(usage.updated_at) as Balance;
+            let recovered = limit.max_amount.saturating_mul(elapsed)
+                .checked_div(limit.window as Balance)
+                .unwrap_or(limit.max_amount);
+            usage.used.saturating_sub(recovered)
+        }
+
+        // ==================== ESCAPE HATCH ====================
+        //
+        // Regras fixas de rateio após a ativação:
+        // - garantias em tokens: cada depositante retira exatamente o valor registrado em token_guarantees;
+        // - doações: rateadas entre os depositantes do mesmo (projeto, token), proporcionalmente à garantia;
+        // - pools de liquidação: pagos aos holders pelo snapshot da votação de liquidação (mesma regra do claim);
+        // - NFTs: devolvidos a quem depositou.
+
+        /// Ativa o escape hatch quando a pausa global excede escape_hatch_delay (qualquer conta)
+        #[ink(message)]
+        pub fn activate_escape_hatch(&mut self) -> Result<(), SafeguardError> {
+            self._ensure_no_escape_hatch()?;
+            if !self.is_paused {
+                return Err(SafeguardError::ContractNotPaused);
+            }
+            let deadline = self.paused_at.saturating_add(self.escape_hatch_delay.get_or_default());
+            if self.env().block_timestamp() < deadline {
+                return Err(SafeguardError::OperationNotReady);
+            }
+            self._activate_escape_hatch(EscapeHatchTrigger::PauseDeadline);
+            Ok(())
+        }
+
+        /// Prazo de pausa que libera o escape hatch (admin de bootstrap; depois via DAO)
+        #[ink(message)]
+        pub fn set_escape_hatch_delay(&mut self, delay: u64) -> Result<(), SafeguardError> {
+            self._ensure_protocol_admin()?;
+            validation::validate_amount(delay as Balance)?;
+            self._set_escape_hatch_delay(delay)
+        }
+
+        fn _set_escape_hatch_delay(&mut self, delay: u64) -> Result<(), SafeguardError> {
+            // O prazo não pode ser adiado durante um incidente
+            self._ensure_not_paused()?;
+            self.escape_hatch_delay.set(&delay);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_escape_hatch_delay(&self) -> u64 {
+            self.escape_hatch_delay.get_or_default()
+        }
+
+        // ==================== COMPLIANCE HOOKS ====================
+
+        /// Hook consultado para todos os projetos (admin de bootstrap; depois via DAO)
+        #[ink(message)]
+        pub fn set_protocol_compliance_hook(&mut self, hook: Option<AccountId>) -> Result<(), SafeguardError> {
+            self._ensure_protocol_admin()?;
+            if let Some(hook) = hook {
+                validation::validate_account(hook)?;
+            }
+            self._set_protocol_compliance_hook(hook);
+            Ok(())
+        }
+
+        fn _set_protocol_compliance_hook(&mut self, hook: Option<AccountId>) {
+            self.protocol_compliance_hook.set(&hook);
+            self.env().emit_event(ComplianceHookUpdated { project_id: None, hook });
+        }
+
+        /// Hook do emissor do projeto, consultado depois do hook do protocolo
+        #[ink(message)]
+        pub fn set_project_compliance_hook(&mut self, project_id: ProjectId, hook: Option<AccountId>) -> Result<(), SafeguardError> {
+            let owner = self.project_owners.get(project_id).ok_or(SafeguardError::ProjectNotFound)?;
+            if owner != self.env().caller() {
+                return Err(SafeguardError::NotOwner);
+            }
+            match hook {
+                Some(hook) => {
+                    validation::validate_account(hook)?;
+                    self.project_compliance_hooks.insert(project_id, &hook);
+                }
+                None => self.project_compliance_hooks.remove(project_id),
+            }
+            self.env().emit_event(ComplianceHookUpdated { project_id: Some(project_id), hook });
+            Ok(())
+        }
+
+        /// Resultado quando um hook configurado falha (admin de bootstrap; depois via DAO)
+        #[ink(message)]
+        pub fn set_compliance_failure_policy(&mut self, policy: ComplianceFailurePolicy) -> Result<(), SafeguardError> {
+            self._ensure_protocol_admin()?;
+            self._set_compliance_failure_policy(policy);
+            Ok(())
+        }
+
+        fn _set_compliance_failure_policy(&mut self, policy: ComplianceFailurePolicy) {
+            self.compliance_failure_policy.set(&policy);
+            self.env().emit_event(ComplianceFailurePolicyUpdated { policy });
+        }
+
+        #[ink(message)]
+        pub fn get_protocol_compliance_hook(&self) -> Option<AccountId> {
+            self.protocol_compliance_hook.get_or_default()
+        }
+
+        #[ink(message)]
+        pub fn get_project_compliance_hook(&self, project_id: ProjectId) -> Option<AccountId> {
+            self.project_compliance_hooks.get(project_id)
+        }
+
+        #[ink(message)]
+        pub fn get_compliance_failure_policy(&self) -> ComplianceFailurePolicy {
+            self.compliance_failure_policy.get_or_default()
+        }
+
+        /// Consulta o hook do protocolo e depois o do projeto; projetos sem hook não pagam a chamada
+        fn _check_compliance(
+            &self,
+            project_id: ProjectId,
+            account: AccountId,
+            action: ComplianceAction,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            let hooks = [self.protocol_compliance_hook.get_or_default(), self.project_compliance_hooks.get(project_id)];
+            for hook in hooks.into_iter().flatten() {
+                let hook_ref: ComplianceHookRef = hook.into();
+                match cross_call::invoke(&hook_ref.call().check(account, action, amount).gas_limit(0).params()) {
+                    Ok(Ok(ComplianceDecision::Allow)) => {}
+                    Ok(Ok(ComplianceDecision::Deny)) => return Err(SafeguardError::ComplianceDenied(None)),
+                    Ok(Ok(ComplianceDecision::DenyWithReason(reason))) => {
+                        return Err(SafeguardError::ComplianceDenied(Some(reason)))
+                    }
+                    _ => {
+                        if self.compliance_failure_policy.get_or_default() == ComplianceFailurePolicy::Deny {
+                            return Err(SafeguardError::ComplianceHookFailed);
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        /// Momento da ativação do escape hatch, se ativo
+        #[ink(message)]
+        pub fn get_escape_hatch_activated_at(&self) -> Option<u64> {
+            self.escape_hatch_activated_at.get_or_default()
+        }
+
+        #[ink(message)]
+        pub fn get_project_donations(&self, project_id: ProjectId, token_id: TokenId) -> Balance {
+            self.project_donations.get((project_id, token_id)).unwrap_or(0)
+        }
+
+        /// Retira a garantia registrada mais a parcela das doações do projeto
+        #[ink(message)]
+        pub fn escape_withdraw(&mut self, project_id: ProjectId, token_id: TokenId) -> Result<Balance, SafeguardError> {
+            self._ensure_escape_hatch()?;
+            self.reentrancy_guard.start()?;
+            let result = self._escape_withdraw(project_id, token_id);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        fn _escape_withdraw(&mut self, project_id: ProjectId, token_id: TokenId) -> Result<Balance, SafeguardError> {
+            let caller = self.env().caller();
+            
+            let vault = self.project_vaults.get(project_id);
+            if let Some(vault) = vault {
+                // Garantias de projetos em liquidação pertencem aos holders (escape_claim)
+                if matches!(vault.status, ProjectStatus::Rejected | ProjectStatus::Liquidation | ProjectStatus::ClaimsOpen) {
+                    return Err(SafeguardError::ClaimsStillOpen);
+                }
+            } else if !self.project_owners.contains(project_id) {
+                return Err(SafeguardError::ProjectNotFound);
+            }
+            
+            let guarantee = self.token_guarantees.get((project_id, token_id, caller)).unwrap_or(0);
+            if guarantee == 0 {
+                return Err(SafeguardError::NoClaimableAmount);
+            }
+            
+            let pool = self._escape_pool(project_id, token_id);
+            // Doações e garantias de 18 casas estouram u128 no produto: rateio em 256 bits
+            let donation_share = safe_math::mul_div(pool.donations, guarantee, pool.deposits);
+            let amount = guarantee.saturating_add(donation_share);
+            
+            self._set_user_guarantee(project_id, token_id, caller, 0);
+            let current_total = self.project_total_guarantees.get((project_id, token_id)).unwrap_or(0);
+            self._set_project_total_guarantee(project_id, token_id, current_total.saturating_sub(amount));
+            let current_balance = self.user_token_balances.get((caller, token_id)).unwrap_or(0);
+            self._set_user_token_balance(caller, token_id, current_balance.saturating_sub(guarantee));
+            self._release_vault_collateral(project_id, token_id, amount);
+            
+            self.env().emit_event(EscapeHatchWithdrawal {
+                project_id,
+                token_id,
+                account: caller,
+                guarantee,
+                donation_share,
+            });
+            self._pay_outflow(OutflowKind::Withdrawal(project_id), token_id, caller, amount)?;
+            
+            Ok(amount)
+        }
+
+        /// Retira um NFT depositado como garantia
+        #[cfg(feature = "nft-collateral")]
+        #[ink(message)]
+        pub fn escape_withdraw_nft(
+            &mut self,
+            project_id: ProjectId,
+            collection_id: NFTCollectionId,
+            nft_token_id: NFTTokenId,
+        ) -> Result<(), SafeguardError> {
+            self._ensure_escape_hatch()?;
+            let caller = self.env().caller();
+            self.reentrancy_guard.start()?;
+            let result = self._remove_nft_guarantee(project_id, collection_id, caller, nft_token_id);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        /// Claim do pool de liquidação sem depender de pausa ou do prazo de claims
+        #[ink(message)]
+        pub fn escape_claim(&mut self, project_id: ProjectId) -> Result<(), SafeguardError> {
+            self._ensure_escape_hatch()?;
+            let caller = self.env().caller();
+            self.reentrancy_guard.start()?;
+            let result = self._process_claim(project_id, caller, false);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        /// Fixa a base do rateio no primeiro saque; depósitos e saques já estão bloqueados pela pausa
+        fn _escape_pool(&mut self, project_id: ProjectId, token_id: TokenId) -> EscapePool {
+            if let Some(pool) = self.escape_pools.get((project_id, token_id)) {
+                return pool;
+            }
+            let total = self.project_total_guarantees.get((project_id, token_id)).unwrap_or(0);
+            let donations = self.project_donations.get((project_id, token_id)).unwrap_or(0).min(total);
+            let pool = EscapePool { deposits: total.saturating_sub(donations), donations };
+            self.escape_pools.insert((project_id, token_id), &pool);
+            pool
+        }
+
+        fn _activate_escape_hatch(&mut self, trigger: EscapeHatchTrigger) {
+            let now = self.env().block_timestamp();
+            self.escape_hatch_activated_at.set(&Some(now));
+            // Mantém o fluxo normal congelado para que o rateio seja determinístico
+            if !self.is_paused {
+                self.is_paused = true;
+                self.paused_at = now;
+            }
+            self.env().emit_event(EscapeHatchActivated {
+                activated_by: self.env().caller(),
+                trigger,
+                timestamp: now,
+            });
+        }
+
+        fn _ensure_escape_hatch(&self) -> Result<(), SafeguardError> {
+            if self.escape_hatch_activated_at.get_or_default().is_none() {
+                return Err(SafeguardError::EscapeHatchNotActive);
+            }
+            Ok(())
+        }
+
+        fn _ensure_no_escape_hatch(&self) -> Result<(), SafeguardError> {
+            if self.escape_hatch_activated_at.get_or_default().is_some() {
+                return Err(SafeguardError::EscapeHatchActive);
+            }
+            Ok(())
+        }
+
+        // ==================== TIMELOCK FUNCTIONS ====================
+
+        /// Schedule a timelock operation (Admin; TokenLister for listings; the council for
+        /// high-impact calls). `salt` distinguishes repeated schedules of the same call
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn schedule_operation(
+            &mut self,
+            function_type: u8,
+            data: Vec<u8>,
+            salt: Hash,
+        ) -> Result<OperationId, SafeguardError> {
+            let caller = self.env().caller();
+            let function = Self::_timelock_function(function_type)?;
+            
+            // Guardians can only veto, never schedule
+            self._ensure_can_schedule(&function)?;
+            if self.is_guardian(caller) {
+                return Err(SafeguardError::NotOwner);
+            }
+            
+            // Payload must decode into the function's argument type and pass validation
+            let call = self._decode_timelock_call(&function, &data)?;
+            self._ensure_timelock_call_allowed(&call, caller)?;
+            
+            self._queue_operation(function, data, salt, caller)
+        }
+
+        /// Record a validated operation with the function's effective delay
+        #[cfg(feature = "timelock")]
+        fn _queue_operation(
+            &mut self,
+            function: TimelockFunction,
+            data: Vec<u8>,
+            salt: Hash,
+            scheduled_by: AccountId,
+        ) -> Result<OperationId, SafeguardError> {
+            let function_type = function.clone() as u8;
+            let current_time = self.env().block_timestamp();
+            
+            // Ids are never reused: an executed or vetoed record cannot be overwritten
+            let operation_id = self.hash_operation(function_type, data.clone(), salt);
+            if self.timelock_operations.contains(operation_id) {
+                return Err(SafeguardError::OperationAlreadyExists);
+            }
+            self.operation_index.insert(self.operation_count.get_or_default(), &operation_id);
+            self.operation_count.set(&self.operation_count.get_or_default().saturating_add(1));
+            
+            let delay = self._effective_delay(&function);
+            let operation = TimelockOperation {
+                function_type: function,
+                scheduled_by,
+                execute_after: current_time.saturating_add(delay),
+                expires_at: current_time.saturating_add(delay).saturating_add(self.timelock_expiry),
+                is_executed: false,
+                is_cancelled: false,
+                data,
+            };
+            
+            self.timelock_operations.insert(operation_id, &operation);
+            
+            self.env().emit_event(TimelockOperationScheduled {
+                operation_id,
+                target_function: function_type,
+                scheduled_by,
+                execute_after: operation.execute_after,
+                expires_at: operation.expires_at,
+            });
+            
+            Ok(operation_id)
+        }
+
+        /// Cancel a pending timelock operation (Admin)
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn cancel_operation(&mut self, operation_id: OperationId) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
+            self._ensure_role(Role::Admin)?;
+            
+            let mut operation = self.timelock_operations.get(operation_id)
+                .ok_or(SafeguardError::OperationNotFound)?;
+            
+            if operation.is_executed {
+                return Err(SafeguardError::OperationNotFound);
+            }
+            
+            if operation.is_cancelled {
+                return Err(SafeguardError::OperationNotFound);
+            }
+            
+            operation.is_cancelled = true;
+            self.timelock_operations.insert(operation_id, &operation);
+            
+            self.env().emit_event(TimelockOperationCancelled {
+                operation_id,
+                cancelled_by: caller,
+            });
+            
+            Ok(())
+        }
+
+        /// Execute a timelock operation (Keeper)
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn execute_operation(&mut self, operation_id: OperationId) -> Result<(), SafeguardError> {
+            self.reentrancy_guard.start()?;
+            let result = self._execute_operation(operation_id);
+            self.reentrancy_guard.end();
+            result
+        }
+
+        #[cfg(feature = "timelock")]
+        fn _execute_operation(&mut self, operation_id: OperationId) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
+            // Guardians can only veto, never execute
+            self._ensure_role(Role::Keeper)?;
+            if self.is_guardian(caller) {
+                return Err(SafeguardError::NotOwner);
+            }
+            
+            let mut operation = self.timelock_operations.get(operation_id)
+                .ok_or(SafeguardError::OperationNotFound)?;
+            
+            if operation.is_executed {
+                return Err(SafeguardError::OperationNotFound);
+            }
+            
+            if operation.is_cancelled {
+                return Err(SafeguardError::OperationNotFound);
+            }
+            
+            let current_time = self.env().block_timestamp();
+            
+            if current_time < operation.execute_after {
+                return Err(SafeguardError::OperationNotReady);
+            }
+            
+            if current_time > operation.expires_at {
+                return Err(SafeguardError::OperationExpired);
+            }
+            
+            // Re-validate against current state (e.g. token still listed, governance handed off)
+            let call = self._decode_timelock_call(&operation.function_type, &operation.data)?;
+            self._ensure_timelock_call_allowed(&call, operation.scheduled_by)?;
+            
+            // Mark as executed before applying the change
+            operation.is_executed = true;
+            self.timelock_operations.insert(operation_id, &operation);
+            
+            match call {
+                TimelockCall::TransferContractOwnership(new_owner) => self._propose_contract_ownership(new_owner, None),
+                TimelockCall::EmergencyWithdraw { token_id, amount, recipient } => {
+                    self._send_outflow(OutflowKind::Emergency, token_id, recipient, amount)?
+                }
+                TimelockCall::ActivateEscapeHatch => self._activate_escape_hatch(EscapeHatchTrigger::Timelock),
+                TimelockCall::Protocol(ProtocolAction::UpgradeCode(code_hash)) => self._upgrade_code(code_hash)?,
+                TimelockCall::Protocol(action) => self._apply_protocol_action(&action)?,
+            }
+            
+            self.env().emit_event(TimelockOperationExecuted {
+                operation_id,
+                executed_by: caller,
+                timestamp: current_time,
+            });
+            
+            Ok(())
+        }
+
+        /// Decode and validate the SCALE payload of a timelock operation
+        #[cfg(feature = "timelock")]
+        fn _decode_timelock_call(&self, function: &TimelockFunction, data: &[u8]) -> Result<TimelockCall, SafeguardError> {
+            let call = match function {
+                TimelockFunction::TransferContractOwnership => {
+                    let new_owner: TransferOwnershipArgs = Self::_decode_args(data)?;
+                    validation::validate_account(new_owner)?;
+                    return Ok(TimelockCall::TransferContractOwnership(new_owner));
+                }
+                TimelockFunction::EmergencyWithdraw => {
+                    let (token_id, amount, recipient): EmergencyWithdrawArgs = Self::_decode_args(data)?;
+                    // Com o escape hatch ativo os fundos pertencem aos depositantes
+                    self._ensure_no_escape_hatch()?;
+                    if !self.supported_tokens.contains(token_id) {
+                        return Err(SafeguardError::TokenNotSupported);
+                    }
+                    validation::validate_amount(amount)?;
+                    validation::validate_account(recipient)?;
+                    return Ok(TimelockCall::EmergencyWithdraw { token_id, amount, recipient });
+                }
+                TimelockFunction::ActivateEscapeHatch => {
+                    Self::_decode_args::<ActivateEscapeHatchArgs>(data)?;
+                    self._ensure_no_escape_hatch()?;
+                    return Ok(TimelockCall::ActivateEscapeHatch);
+                }
+                TimelockFunction::UpgradeCode => {
+                    let code_hash: UpgradeCodeArgs = Self::_decode_args(data)?;
+                    ProtocolAction::UpgradeCode(code_hash)
+                }
+                TimelockFunction::SetTreasuryAddress => {
+                    let treasury: SetTreasuryAddressArgs = Self::_decode_args(data)?;
+                    ProtocolAction::SetTreasuryAddress(treasury)
+                }
+                TimelockFunction::SetDepositFees => {
+                    let (lunes_fee, lusdt_fee): SetDepositFeesArgs = Self::_decode_args(data)?;
+                    ProtocolAction::SetDepositFees { lunes_fee, lusdt_fee }
+                }
+                TimelockFunction::SetScoreParameters => {
+                    let params: SetScoreParametersArgs = Self::_decode_args(data)?;
+                    ProtocolAction::SetScoreParameters(params)
+                }
+                TimelockFunction::AddSupportedToken => {
+                    let (contract_address, symbol, decimals, min_guarantee_amount): AddSupportedTokenArgs =
+                        Self::_decode_args(data)?;
+                    ProtocolAction::AddSupportedToken { contract_address, symbol, decimals, min_guarantee_amount }
+                }
+                TimelockFunction::AddNftCollection => {
+                    let (contract_address, name, symbol, base_value, valuation_method): AddNftCollectionArgs =
+                        Self::_decode_args(data)?;
+                    ProtocolAction::AddNftCollection { contract_address, name, symbol, base_value, valuation_method }
+                }
+                TimelockFunction::SetOutflowLimit => {
+                    let (token_id, limit): SetOutflowLimitArgs = Self::_decode_args(data)?;
+                    ProtocolAction::SetOutflowLimit { token_id, limit }
+                }
+                TimelockFunction::SetTokenSelectorScheme => {
+                    let (token_id, scheme): SetTokenSelectorSchemeArgs = Self::_decode_args(data)?;
+                    ProtocolAction::SetTokenSelectorScheme { token_id, scheme }
+                }
+            };
+            
+            self._validate_protocol_action(&call)?;
+            Ok(TimelockCall::Protocol(call))
+        }
+
+        /// Global parameters and code upgrades belong to the DAO once protocol governance
+        /// is enabled, and council seats to the council itself; checked at schedule time
+        /// and again at execution. Operations queued by the contract itself come from an
+        /// approved DAO proposal
+        #[cfg(feature = "timelock")]
+        fn _ensure_timelock_call_allowed(&self, call: &TimelockCall, scheduled_by: AccountId) -> Result<(), SafeguardError> {
+            match call {
+                TimelockCall::Protocol(_)
+                    if self.protocol_governance_enabled.get_or_default() && scheduled_by != self.env().account_id() =>
+                {
+                    Err(SafeguardError::GovernanceRequired)
+                }
+                TimelockCall::TransferContractOwnership(_) => self._ensure_owner(),
+                _ => Ok(()),
+            }
+        }
+
+        /// Decode the whole payload (trailing bytes are rejected)
+        fn _decode_args<T: scale::Decode>(data: &[u8]) -> Result<T, SafeguardError> {
+            <T as scale::DecodeAll>::decode_all(&mut &data[..]).map_err(|_| SafeguardError::InvalidInput)
+        }
+
+        /// Veto a queued operation before `execute_after` (guardians only)
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn veto_operation(&mut self, operation_id: OperationId, reason: Vec<u8>) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
+            if !self.is_guardian(caller) {
+                return Err(SafeguardError::NotGuardian);
+            }
+            
+            let mut operation = self.timelock_operations.get(operation_id)
+                .ok_or(SafeguardError::OperationNotFound)?;
+            
+            match self._operation_status(&operation) {
+                OperationStatus::Pending => {}
+                OperationStatus::Executed | OperationStatus::Cancelled => return Err(SafeguardError::OperationNotFound),
+                OperationStatus::Ready | OperationStatus::Expired => return Err(SafeguardError::VetoWindowClosed),
+            }
+            
+            operation.is_cancelled = true;
+            self.timelock_operations.insert(operation_id, &operation);
+            
+            self.env().emit_event(TimelockOperationVetoed {
+                operation_id,
+                guardian: caller,
+                reason,
+            });
+            
+            Ok(())
+        }
+
+        /// Add or remove a guardian (bootstrap admin; afterwards via DAO)
+        #[ink(message)]
+        pub fn set_guardian(&mut self, account: AccountId, enabled: bool) -> Result<(), SafeguardError> {
+            self._ensure_protocol_admin()?;
+            validation::validate_account(account)?;
+            self._set_guardian(account, enabled)
+        }
+
+        fn _set_guardian(&mut self, account: AccountId, enabled: bool) -> Result<(), SafeguardError> {
+            // Council signers cannot veto their own operations
+            if enabled && self._is_council_signer(account) {
+                return Err(SafeguardError::InvalidInput);
+            }
+            
+            if self.is_guardian(account) == enabled {
+                return Ok(());
+            }
+            
+            if enabled {
+                self.guardians.insert(account, &true);
+                self.guardian_count.set(&self.guardian_count.get_or_default().saturating_add(1));
+            } else {
+                self.guardians.remove(account);
+                self.guardian_count.set(&self.guardian_count.get_or_default().saturating_sub(1));
+            }
+            
+            self.env().emit_event(GuardianUpdated { account, enabled });
+            Ok(())
+        }
+
+        /// Check if an account is a guardian
+        #[ink(message)]
+        pub fn is_guardian(&self, account: AccountId) -> bool {
+            self.guardians.get(account).unwrap_or(false)
+        }
+
+        /// Number of guardians
+        #[ink(message)]
+        pub fn get_guardian_count(&self) -> u32 {
+            self.guardian_count.get_or_default()
+        }
+
+        /// Get timelock operation info
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn get_operation(&self, operation_id: OperationId) -> Option<TimelockOperation> {
+            self.timelock_operations.get(operation_id)
+        }
+
+        /// Operation id for a payload (blake2x256 of the SCALE-encoded `(function_type, data, salt)`)
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn hash_operation(&self, function_type: u8, data: Vec<u8>, salt: Hash) -> OperationId {
+            let mut output = <ink::env::hash::Blake2x256 as ink::env::hash::HashOutput>::Type::default();
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&(function_type, data, salt), &mut output);
+            Hash::from(output)
+        }
+
+        /// Get the current status of a timelock operation
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn get_operation_status(&self, operation_id: OperationId) -> Option<OperationStatus> {
+            self.timelock_operations.get(operation_id).map(|op| self._operation_status(&op))
+        }
+
+        /// Number of distinct operations ever scheduled
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn get_operation_count(&self) -> u32 {
+            self.operation_count.get_or_default()
+        }
+
+        /// List operations in the given status, scanning the schedule index from `start`.
+        /// At most `limit` index entries are scanned (capped at `MAX_OPERATIONS_PAGE`);
+        /// the next page starts at `start + limit`.
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn get_operations(
+            &self,
+            status: OperationStatus,
+            start: u32,
+            limit: u32,
+        ) -> Vec<(OperationId, TimelockOperation)> {
+            let end = start
+                .saturating_add(limit.min(MAX_OPERATIONS_PAGE))
+                .min(self.operation_count.get_or_default());
+            let mut operations = Vec::new();
+            for index in start..end {
+                if let Some(operation_id) = self.operation_index.get(index) {
+                    if let Some(operation) = self.timelock_operations.get(operation_id) {
+                        if self._operation_status(&operation) == status {
+                            operations.push((operation_id, operation));
+                        }
+                    }
+                }
+            }
+            operations
+        }
+
+        /// Minimum delay for a timelock function (before applying the global delay floor)
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn get_min_delay(&self, function_type: u8) -> Result<u64, SafeguardError> {
+            let function = Self::_timelock_function(function_type)?;
+            Ok(self._min_delay(&function))
+        }
+
+        /// Raise the minimum delay of a timelock function (council; built-in default to 7 days).
+        /// Delays are never lowered below the built-in defaults, so a single key cannot
+        /// shorten the window guardians have to veto
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn set_min_delay(&mut self, function_type: u8, min_delay: u64) -> Result<(), SafeguardError> {
+            self._ensure_owner()?;
+            
+            let function = Self::_timelock_function(function_type)?;
+            if !(Self::_default_min_delay(&function)..=7 * 24 * 60 * 60 * 1000).contains(&min_delay) {
+                return Err(SafeguardError::InvalidInput);
+            }
+            
+            self.timelock_min_delays.insert(function, &min_delay);
+            self.env().emit_event(TimelockMinDelayUpdated {
+                target_function: function_type,
+                min_delay,
+            });
+            Ok(())
+        }
+
+        /// Listings may be queued by token listers and routine parameters by Admin.
+        /// Calls that move custody, replace code or hand over the contract need the
+        /// M-of-N council, so a single Admin key cannot start them
+        #[cfg(feature = "timelock")]
+        fn _ensure_can_schedule(&self, function: &TimelockFunction) -> Result<(), SafeguardError> {
+            match function {
+                TimelockFunction::TransferContractOwnership
+                | TimelockFunction::EmergencyWithdraw
+                | TimelockFunction::ActivateEscapeHatch
+                | TimelockFunction::UpgradeCode => self._ensure_owner(),
+                TimelockFunction::AddSupportedToken
+                | TimelockFunction::AddNftCollection
+                | TimelockFunction::SetTokenSelectorScheme => self._ensure_role(Role::TokenLister),
+                _ => self._ensure_role(Role::Admin),
+            }
+        }
+
+        #[cfg(feature = "timelock")]
+        fn _timelock_function(function_type: u8) -> Result<TimelockFunction, SafeguardError> {
+            match function_type {
+                0 => Ok(TimelockFunction::TransferContractOwnership),
+                1 => Ok(TimelockFunction::SetTreasuryAddress),
+                2 => Ok(TimelockFunction::SetDepositFees),
+                3 => Ok(TimelockFunction::SetScoreParameters),
+                4 => Ok(TimelockFunction::EmergencyWithdraw),
+                5 => Ok(TimelockFunction::AddSupportedToken),
+                6 => Ok(TimelockFunction::AddNftCollection),
+                7 => Ok(TimelockFunction::SetOutflowLimit),
+                8 => Ok(TimelockFunction::ActivateEscapeHatch),
+                9 => Ok(TimelockFunction::SetTokenSelectorScheme),
+                10 => Ok(TimelockFunction::UpgradeCode),
+                _ => Err(SafeguardError::InvalidInput),
+            }
+        }
+
+        /// Default minimums: ownership, fee changes and code upgrades wait 96 hours, everything else 48 hours
+        #[cfg(feature = "timelock")]
+        fn _default_min_delay(function: &TimelockFunction) -> u64 {
+            match function {
+                TimelockFunction::TransferContractOwnership
+                | TimelockFunction::SetDepositFees
+                | TimelockFunction::UpgradeCode => 96 * 60 * 60 * 1000,
+                _ => 48 * 60 * 60 * 1000,
+            }
+        }
+
+        /// Configured minimum, floored at the built-in default
+        #[cfg(feature = "timelock")]
+        fn _min_delay(&self, function: &TimelockFunction) -> u64 {
+            let default = Self::_default_min_delay(function);
+            self.timelock_min_delays.get(function).unwrap_or(default).max(default)
+        }
+
+        /// Delay applied when scheduling: the larger of the global delay and the function minimum
+        #[cfg(feature = "timelock")]
+        fn _effective_delay(&self, function: &TimelockFunction) -> u64 {
+            self._min_delay(function).max(self.timelock_delay)
+        }
+
+        #[cfg(feature = "timelock")]
+        fn _operation_status(&self, operation: &TimelockOperation) -> OperationStatus {
+            let current_time = self.env().block_timestamp();
+            if operation.is_executed {
+                OperationStatus::Executed
+            } else if operation.is_cancelled {
+                OperationStatus::Cancelled
+            } else if current_time > operation.expires_at {
+                OperationStatus::Expired
+            } else if current_time >= operation.execute_after {
+                OperationStatus::Ready
+            } else {
+                OperationStatus::Pending
+            }
+        }
+
+        /// Get timelock delay
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn get_timelock_delay(&self) -> u64 {
+            self.timelock_delay
+        }
+
+        /// Hand the bootstrap bypass to another account (bootstrap admin only)
+        #[ink(message)]
+        pub fn transfer_bootstrap_admin(&mut self, new_admin: AccountId) -> Result<(), SafeguardError> {
+            self._ensure_bootstrap_admin()?;
+            validation::validate_account(new_admin)?;
+            self._set_bootstrap_admin(Some(new_admin));
+            Ok(())
+        }
+
+        /// Close the bootstrap phase for good: sensitive setters then only run through the timelock
+        #[ink(message)]
+        pub fn end_bootstrap(&mut self) -> Result<(), SafeguardError> {
+            self._ensure_bootstrap_admin()?;
+            self._set_bootstrap_admin(None);
+            Ok(())
+        }
+
+        /// Get bootstrap admin (None once the bootstrap phase is over)
+        #[ink(message)]
+        pub fn get_bootstrap_admin(&self) -> Option<AccountId> {
+            self.bootstrap_admin.get_or_default()
+        }
+
+        fn _set_bootstrap_admin(&mut self, new_admin: Option<AccountId>) {
+            let previous_admin = self.env().caller();
+            self.bootstrap_admin.set(&new_admin);
+            self.env().emit_event(BootstrapAdminChanged {
+                previous_admin,
+                new_admin,
+            });
+        }
+
+        /// Direct calls to sensitive setters are only allowed for the bootstrap admin
+        fn _ensure_bootstrap_admin(&self) -> Result<(), SafeguardError> {
+            let caller = self.env().caller();
+            if self.bootstrap_admin.get_or_default() == Some(caller) {
+                return Ok(());
+            }
+            if self._is_council_signer(caller) {
+                return Err(SafeguardError::TimelockRequired);
+            }
+            Err(SafeguardError::NotOwner)
+        }
+
+        /// Set the global timelock delay (council). Per-function minimums still apply on top
+        #[cfg(feature = "timelock")]
+        #[ink(message)]
+        pub fn set_timelock_delay(&mut self, new_delay: u64) -> Result<(), SafeguardError> {
+            self._ensure_owner()?;
+            
+            // Minimum 1 hour, maximum 7 days
+            if new_delay < 60 * 60 * 1000 || new_delay > 7 * 24 * 60 * 60 * 1000 {
+                return Err(SafeguardError::InvalidInput);
+            }
+            
+            self.timelock_delay = new_delay;
+            Ok(())
+        }
+
+        // ==================== CROSS-CONTRACT PSP22 CALLS ====================
+
+        /// Client for a listed token, using the selector convention recorded at listing
+        fn _psp22_client(&self, token_id: TokenId) -> Result<Psp22Client, SafeguardError> {
+            let token_info = self.supported_tokens.get(token_id)
+                .ok_or(SafeguardError::TokenNotSupported)?;
+            Ok(Psp22Client::new(token_info.contract_address, token_info.selector_scheme))
+        }
+
+        /// Internal function to transfer PSP22 tokens from user to contract
+        fn _psp22_transfer_from(
+            &mut self,
+            token_id: TokenId,
+            from: AccountId,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            self._psp22_transfer_from_to(token_id, from, self.env().account_id(), amount)
+        }
+
+        /// Internal function to transfer PSP22 tokens from user to a custody account
+        fn _psp22_transfer_from_to(
+            &mut self,
+            token_id: TokenId,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            self._psp22_client(token_id)?.transfer_from(from, to, amount)?;
+            
+            self.env().emit_event(TokenTransferExecuted {
+                token_id,
+                from,
+                to,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Internal function to transfer PSP22 tokens from contract to user
+        fn _psp22_transfer(
+            &mut self,
+            token_id: TokenId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            self._psp22_client(token_id)?.transfer(to, amount)?;
+            
+            self.env().emit_event(TokenTransferExecuted {
+                token_id,
+                from: self.env().account_id(),
+                to,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Internal function to get PSP22 balance
+        fn _psp22_balance_of(
+            &self,
+            token_id: TokenId,
+            account: AccountId,
+        ) -> Result<Balance, SafeguardError> {
+            self._psp22_client(token_id)?.balance_of(account)
+        }
+
+        /// Internal function to read a historical balance from a PSP22Votes token
+        fn _psp22_past_balance(
+            &self,
+            token_contract: AccountId,
+            account: AccountId,
+            block: BlockNumber,
+        ) -> Result<Balance, SafeguardError> {
+            Psp22Client::new(token_contract, SelectorScheme::TraitNamespaced).past_balance(account, block)
+        }
+
+        /// Internal function to read a historical total supply from a PSP22Votes token
+        fn _psp22_past_total_supply(
+            &self,
+            token_contract: AccountId,
+            block: BlockNumber,
+        ) -> Result<Balance, SafeguardError> {
+            Psp22Client::new(token_contract, SelectorScheme::TraitNamespaced).past_total_supply(block)
+        }
+
+        /// Transfer fee to treasury using real PSP22 call
+        fn _transfer_fee_to_treasury(
+            &mut self,
+            token_id: TokenId,
+            from: AccountId,
+            amount: Balance,
+        ) -> Result<(), SafeguardError> {
+            // First transfer from user to contract
+            self._psp22_transfer_from(token_id, from, amount)?;
+            
+            // Then transfer from contract to treasury
+            self._psp22_transfer(token_id, self.treasury_address, amount)?;
+            
+            Ok(())
+        }
+
+        /// Execute claim transfers to user
+        fn _execute_claim_transfers(
+            &mut self,
+            project_id: ProjectId,
+            claimer: AccountId,
+            claim_info: &ClaimInfo,
+        ) -> Result<(), SafeguardError> {
+            // Transfer Lunes share if any
+            if claim_info.lunes_share > 0 {
+                if let Some(lunes_id) = self.lunes_token_id {
+                    self._send_outflow(OutflowKind::Claim(project_id), lunes_id, claimer, claim_info.lunes_share)?;
+                }
+            }
+            
+            // Transfer LUSDT share if any
+            if claim_info.lusdt_share > 0 {
+                if let Some(lusdt_id) = self.lusdt_token_id {
+                    self._send_outflow(OutflowKind::Claim(project_id), lusdt_id, claimer, claim_info.lusdt_share)?;
+                }
+            }
+            
+            Ok(())
+        }
+
+        // ==================== SCORE CACHES ====================
+
+        /// Update token count cache when adding guarantee
+        fn _update_token_count_cache(&mut self, project_id: ProjectId, token_id: TokenId, is_add: bool) {
+            let current_amount = self.project_total_guarantees.get((project_id, token_id)).unwrap_or(0);
+            let current_count = self.project_token_count.get(project_id).unwrap_or(0);
+            
+            if is_add && current_amount == 0 {
+                // First deposit of this token for this project
+                self.project_token_count.insert(project_id, &(current_count.saturating_add(1)));
+            } else if !is_add && current_amount == 0 && current_count > 0 {
+                // Last withdrawal of this token for this project
+                self.project_token_count.insert(project_id, &(current_count.saturating_sub(1)));
+            }
+        }
+
+        /// Update NFT count cache
+        fn _update_nft_count_cache(&mut self, project_id: ProjectId, collection_id: NFTCollectionId, is_add: bool) {
+            let current_amount = self.project_nft_guarantees.get((project_id, collection_id)).unwrap_or(0);
+            let current_count = self.project_nft_count.get(project_id).unwrap_or(0);
+            
+            if is_add && current_amount == 0 {
+                self.project_nft_count.insert(project_id, &(current_count.saturating_add(1)));
+            } else if !is_add && current_amount == 0 && current_count > 0 {
+                self.project_nft_count.insert(project_id, &(current_count.saturating_sub(1)));
+            }
+        }
+    }
+
+    /// Tests module
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::cross_call::mock_env::{self, psp22_ledger as test_ledger};
+        #[cfg(feature = "nft-collateral")]
+        use crate::cross_call::mock_env::psp34_ledger;
+        use ink::env::test::{default_accounts, set_caller};
+
+        fn setup() -> (Safeguard, ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment>) {
+            let accounts = default_accounts();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            // Endereço próprio do core, para que a custódia não se confunda com a carteira de alice
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(AccountId::from([0x40; 32]));
+            let contract = Safeguard::new();
+            (contract, accounts)
+        }
+
+        #[ink::test]
+        fn test_constructor() {
+            let (contract, accounts) = setup();
+            assert_eq!(contract.owner().signers, vec![accounts.alice]);
+            assert_eq!(contract.next_project_id, 0);
+        }
+
+        #[ink::test]
+        fn test_project_registration() {
+            let (mut contract, accounts) = setup();
+            
+            // Register a new project
+            let result = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            );
+            assert!(result.is_ok());
+            let project_id = result.unwrap();
+            assert_eq!(project_id, 0);
+            assert_eq!(contract.next_project_id, 1);
+        }
+
+        #[ink::test]
+        fn test_contract_ownership() {
+            let (mut contract, accounts) = setup();
+            
+            // Transfer contract ownership
+            let result = contract.transfer_contract_ownership(accounts.bob);
+            assert!(result.is_ok());
+            assert_eq!(contract.owner().signers, vec![accounts.alice]);
+            
+            // New owner accepts
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(contract.accept_contract_ownership().is_ok());
+            assert_eq!(contract.owner(), CouncilConfig { signers: vec![accounts.bob], threshold: 1 });
+        }
+
+        #[ink::test]
+        fn test_contract_ownership_two_step() {
+            let (mut contract, accounts) = setup();
+            
+            assert_eq!(contract.accept_contract_ownership(), Err(SafeguardError::NoPendingOwnership));
+            contract.transfer_contract_ownership(accounts.bob).unwrap();
+            
+            // Only the proposed owner may accept
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.accept_contract_ownership(), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.cancel_contract_ownership_transfer(), Err(SafeguardError::NotOwner));
+            
+            // Cancelled while pending
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.cancel_contract_ownership_transfer().unwrap();
+            assert!(contract.get_pending_contract_owner().is_none());
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.accept_contract_ownership(), Err(SafeguardError::NoPendingOwnership));
+            
+            // Expired offers cannot be accepted
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.transfer_contract_ownership(accounts.bob).unwrap();
+            let expires_at = contract.get_pending_contract_owner().unwrap().expires_at;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(expires_at + 1);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.accept_contract_ownership(), Err(SafeguardError::OwnershipTransferExpired));
+            assert_eq!(contract.owner().signers, vec![accounts.alice]);
+        }
+
+        #[ink::test]
+        fn test_project_ownership_two_step_keeps_vault_in_sync() {
+            let (mut contract, accounts) = setup();
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.transfer_project_ownership(project_id, accounts.bob), Err(SafeguardError::NotOwner));
+            
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.transfer_project_ownership(project_id, accounts.charlie).unwrap();
+            assert_eq!(contract.get_project_owner(project_id), Ok(accounts.alice));
+            contract.cancel_project_ownership_transfer(project_id).unwrap();
+            assert!(contract.get_pending_project_owner(project_id).is_none());
+            
+            contract.transfer_project_ownership(project_id, accounts.charlie).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.accept_project_ownership(project_id), Err(SafeguardError::NotOwner));
+            
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.accept_project_ownership(project_id).unwrap();
+            assert_eq!(contract.get_project_owner(project_id), Ok(accounts.charlie));
+            assert_eq!(contract.get_project_vault(project_id).unwrap().owner, accounts.charlie);
+            assert_eq!(contract.accept_project_ownership(project_id), Err(SafeguardError::NoPendingOwnership));
+        }
+
+        #[cfg(feature = "legacy-api")]
+        #[ink::test]
+        fn test_vote_activation() {
+            let (mut contract, accounts) = setup();
+            
+            // First register a project
+            let project_result = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            );
+            assert!(project_result.is_ok());
+            let project_id = project_result.unwrap();
+            
+            // Activate voting for the project
+            let result = contract.vote_active(project_id, true, 1000);
+            assert!(result.is_ok());
+            
+            // Check project info
+            let info = contract.get_project_info(project_id);
+            assert!(info.is_some());
+            let project_info = info.unwrap();
+            assert!(project_info.status);
+            assert_eq!(project_info.balance_permission, 1000);
+        }
+
+        #[cfg(feature = "legacy-api")]
+        #[ink::test]
+        fn test_unauthorized_access() {
+            let (mut contract, accounts) = setup();
+            
+            // Register a project as alice
+            let project_result = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            );
+            assert!(project_result.is_ok());
+            let project_id = project_result.unwrap();
+            
+            // Try to activate voting as non-owner (bob)
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let result = contract.vote_active(project_id, true, 1000);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::NotOwner);
+        }
+
+        #[ink::test]
+        fn test_arithmetic_overflow_protection() {
+            let result = safe_math::safe_add(u64::MAX, 1);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::ArithmeticOverflow);
+        }
+
+        #[ink::test]
+        fn test_input_validation() {
+            let zero_account = AccountId::from([0u8; 32]);
+            let result = validation::validate_account(zero_account);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::ZeroAddress);
+        }
+
+        #[ink::test]
+        fn test_reentrancy_protection() {
             let mut guard = ReentrancyGuard::default();
             
-            // First entry should succeed
-            let result = guard.start();
-            assert!(result.is_ok());
-            assert!(guard.is_entered());
+            // First entry should succeed
+            let result = guard.start();
+            assert!(result.is_ok());
+            assert!(guard.is_entered());
+            
+            // Second entry should fail
+            let result = guard.start();
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::ReentrancyDetected);
+            
+            // End should clear the guard
+            guard.end();
+            assert!(!guard.is_entered());
+        }
+
+        #[ink::test]
+        fn test_add_supported_token() {
+            let (mut contract, accounts) = setup();
+            
+            // Add a new token
+            let symbol = *b"USDT\0\0\0\0";
+            let result = contract.add_supported_token(
+                accounts.bob, // token contract address
+                symbol,
+                6, // decimals
+                1000, // min guarantee amount
+            );
+            
+            assert!(result.is_ok());
+            let token_id = result.unwrap();
+            assert_eq!(token_id, 0);
+            assert_eq!(contract.next_token_id, 1);
+            
+            // Verify token info
+            let token_info = contract.get_token_info(token_id);
+            assert!(token_info.is_some());
+            let info = token_info.unwrap();
+            assert_eq!(info.contract_address, accounts.bob);
+            assert_eq!(info.symbol, symbol);
+            assert_eq!(info.decimals, 6);
+            assert_eq!(info.min_guarantee_amount, 1000);
+            assert!(info.is_active);
+        }
+
+        #[ink::test]
+        fn test_add_guarantee() {
+            let (mut contract, accounts) = setup();
+            
+            // First register a project
+            let project_result = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            );
+            assert!(project_result.is_ok());
+            let project_id = project_result.unwrap();
+            
+            // Add a supported token
+            let symbol = *b"LUNES\0\0\0";
+            let token_result = contract.add_supported_token(
+                accounts.charlie,
+                symbol,
+                8,
+                500,
+            );
+            assert!(token_result.is_ok());
+            let token_id = token_result.unwrap();
+            
+            // Add guarantee
+            let guarantee_amount = 1000;
+            let result = contract.add_guarantee(project_id, token_id, guarantee_amount);
+            assert!(result.is_ok());
+            
+            // Verify guarantee was added
+            let user_guarantee = contract.get_user_guarantee(project_id, token_id, accounts.alice);
+            assert_eq!(user_guarantee, guarantee_amount);
+            
+            let total_guarantee = contract.get_project_total_guarantee(project_id, token_id);
+            assert_eq!(total_guarantee, guarantee_amount);
+            
+            let user_balance = contract.get_user_token_balance(accounts.alice, token_id);
+            assert_eq!(user_balance, guarantee_amount);
+        }
+
+        #[ink::test]
+        fn test_donate_to_guarantee() {
+            let (mut contract, accounts) = setup();
+            
+            // Setup project and token
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            let token_id = contract.add_supported_token(
+                accounts.charlie,
+                *b"DONATE\0\0",
+                8,
+                100,
+            ).unwrap();
+            
+            // Donate to guarantee pool
+            let donation_amount = 2000;
+            let result = contract.donate_to_guarantee(project_id, token_id, donation_amount);
+            assert!(result.is_ok());
+            
+            // Verify donation was added to project total
+            let total_guarantee = contract.get_project_total_guarantee(project_id, token_id);
+            assert_eq!(total_guarantee, donation_amount);
+            
+            // User guarantee should be 0 (donations don't count as user guarantees)
+            let user_guarantee = contract.get_user_guarantee(project_id, token_id, accounts.alice);
+            assert_eq!(user_guarantee, 0);
+        }
+
+        #[ink::test]
+        fn test_withdraw_guarantee() {
+            let (mut contract, accounts) = setup();
+            
+            // Setup project, token, and add guarantee
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            let token_id = contract.add_supported_token(
+                accounts.charlie,
+                *b"WITHDRAW",
+                8,
+                500,
+            ).unwrap();
+            
+            let initial_amount = 1500;
+            contract.add_guarantee(project_id, token_id, initial_amount).unwrap();
+            
+            // Withdraw part of the guarantee
+            let withdraw_amount = 500;
+            let result = contract.withdraw_guarantee(project_id, token_id, withdraw_amount);
+            assert!(result.is_ok());
+            
+            // Verify balances were updated
+            let remaining_guarantee = initial_amount - withdraw_amount;
+            let user_guarantee = contract.get_user_guarantee(project_id, token_id, accounts.alice);
+            assert_eq!(user_guarantee, remaining_guarantee);
+            
+            let total_guarantee = contract.get_project_total_guarantee(project_id, token_id);
+            assert_eq!(total_guarantee, remaining_guarantee);
+            
+            let user_balance = contract.get_user_token_balance(accounts.alice, token_id);
+            assert_eq!(user_balance, remaining_guarantee);
+        }
+
+        #[ink::test]
+        fn test_multi_asset_unauthorized_access() {
+            let (mut contract, accounts) = setup();
+            
+            // Try to add token as non-owner
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let result = contract.add_supported_token(
+                accounts.charlie,
+                *b"HACK\0\0\0\0",
+                8,
+                1000,
+            );
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::NotOwner);
+        }
+
+        #[ink::test]
+        fn test_invalid_token_operations() {
+            let (mut contract, accounts) = setup();
+            
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            let invalid_token_id = 999;
+            
+            // Try to add guarantee with invalid token
+            let result = contract.add_guarantee(project_id, invalid_token_id, 1000);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::InvalidInput);
+            
+            // Try to donate with invalid token
+            let result = contract.donate_to_guarantee(project_id, invalid_token_id, 1000);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::InvalidInput);
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        #[ink::test]
+        fn test_add_nft_collection() {
+            let (mut contract, accounts) = setup();
+            
+            // Add a new NFT collection
+            let name = *b"Test Collection\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+            let symbol = *b"TEST\0\0\0\0";
+            let base_value: Balance = 1000;
+            let collection_id = contract.add_nft_collection(
+                accounts.bob,
+                name,
+                symbol,
+                base_value,
+                NFTValuationMethod::FixedValue(1000),
+            );
+            
+            assert!(collection_id.is_ok());
+            let collection_id = collection_id.unwrap();
+            assert_eq!(collection_id, 0);
+            assert_eq!(contract.next_nft_collection_id, 1);
+            
+            // Verify collection info
+            let collection_info = contract.get_nft_collection_info(collection_id);
+            assert!(collection_info.is_some());
+            let info = collection_info.unwrap();
+            assert_eq!(info.contract_address, accounts.bob);
+            assert_eq!(info.name, name);
+            assert_eq!(info.symbol, symbol);
+            assert_eq!(info.base_value, base_value);
+            assert!(info.is_active);
+            assert!(matches!(info.valuation_method, NFTValuationMethod::FixedValue(_)));
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        #[ink::test]
+        fn test_deposit_nft_guarantee() {
+            let (mut contract, accounts) = setup();
+            
+            // Setup project and NFT collection
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            let collection_id = contract.add_nft_collection(
+                accounts.charlie,
+                *b"TestNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"TNFT\0\0\0\0",
+                2500,
+                NFTValuationMethod::FixedValue(1000),
+            ).unwrap();
+            
+            // Deposit NFT as guarantee
+            let nft_token_id = 123;
+            psp34_ledger::mint(accounts.charlie, nft_token_id, accounts.alice);
+            let result = contract.deposit_nft_guarantee(project_id, collection_id, nft_token_id);
+            assert!(result.is_ok());
+            
+            // Verify NFT guarantee was deposited
+            let nft_value = contract.get_nft_guarantee_value(project_id, collection_id, accounts.alice, nft_token_id);
+            assert_eq!(nft_value, 2500);
+            
+            let total_guarantee = contract.get_project_nft_guarantee_total(project_id, collection_id);
+            assert_eq!(total_guarantee, 2500);
+            
+            let deposit_count = contract.get_user_nft_deposit_count(accounts.alice, collection_id);
+            assert_eq!(deposit_count, 1);
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        #[ink::test]
+        fn test_withdraw_nft_guarantee() {
+            let (mut contract, accounts) = setup();
+            
+            // Setup project, collection, and deposit NFT
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            let collection_id = contract.add_nft_collection(
+                accounts.charlie,
+                *b"WithdrawNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"WNFT\0\0\0\0",
+                3000,
+                NFTValuationMethod::FixedValue(1000),
+            ).unwrap();
+            
+            let nft_token_id = 456;
+            psp34_ledger::mint(accounts.charlie, nft_token_id, accounts.alice);
+            contract.deposit_nft_guarantee(project_id, collection_id, nft_token_id).unwrap();
+            
+            // Withdraw NFT guarantee
+            let result = contract.withdraw_nft_guarantee(project_id, collection_id, nft_token_id);
+            assert!(result.is_ok());
+            
+            // Verify NFT guarantee was withdrawn
+            let nft_value = contract.get_nft_guarantee_value(project_id, collection_id, accounts.alice, nft_token_id);
+            assert_eq!(nft_value, 0);
+            
+            let total_guarantee = contract.get_project_nft_guarantee_total(project_id, collection_id);
+            assert_eq!(total_guarantee, 0);
+            
+            let deposit_count = contract.get_user_nft_deposit_count(accounts.alice, collection_id);
+            assert_eq!(deposit_count, 0);
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        #[ink::test]
+        fn test_multiple_nft_deposits() {
+            let (mut contract, accounts) = setup();
+            
+            // Setup project and collection
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            let collection_id = contract.add_nft_collection(
+                accounts.charlie,
+                *b"MultiNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"MNFT\0\0\0\0",
+                1000,
+                NFTValuationMethod::FixedValue(1000),
+            ).unwrap();
+            
+            // Deposit multiple NFTs
+            let nft_ids = [100, 200, 300];
+            for &nft_id in &nft_ids {
+                psp34_ledger::mint(accounts.charlie, nft_id, accounts.alice);
+                let result = contract.deposit_nft_guarantee(project_id, collection_id, nft_id);
+                assert!(result.is_ok());
+            }
+            
+            // Verify all NFTs were deposited
+            let total_guarantee = contract.get_project_nft_guarantee_total(project_id, collection_id);
+            assert_eq!(total_guarantee, 3000); // 3 NFTs * 1000 each
+            
+            let deposit_count = contract.get_user_nft_deposit_count(accounts.alice, collection_id);
+            assert_eq!(deposit_count, 3);
+            
+            // Verify individual NFT values
+            for &nft_id in &nft_ids {
+                let nft_value = contract.get_nft_guarantee_value(project_id, collection_id, accounts.alice, nft_id);
+                assert_eq!(nft_value, 1000);
+            }
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        #[ink::test]
+        fn test_nft_deposit_requires_owner_and_free_custody() {
+            let (mut contract, accounts) = setup();
+            let first = contract.register_project(
+                b"First Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            let second = contract.register_project(
+                b"Second Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            let collection_id = contract.add_nft_collection(
+                accounts.charlie,
+                *b"CustodyNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"CNFT\0\0\0\0",
+                2000,
+                NFTValuationMethod::FixedValue(2000),
+            ).unwrap();
+            let core = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            psp34_ledger::mint(accounts.charlie, 5, accounts.alice);
+            psp34_ledger::mint(accounts.charlie, 6, accounts.django);
+            contract.deposit_nft_guarantee(first, collection_id, 5).unwrap();
+            assert_eq!(psp34_ledger::owner_of(accounts.charlie, 5), Some(core));
+
+            // Outro depositante não registra o NFT já custodiado, em nenhum projeto
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.deposit_nft_guarantee(first, collection_id, 5), Err(SafeguardError::NFTAlreadyDeposited));
+            assert_eq!(contract.deposit_nft_guarantee(second, collection_id, 5), Err(SafeguardError::NFTAlreadyDeposited));
+            assert_eq!(contract.withdraw_nft_guarantee(first, collection_id, 5), Err(SafeguardError::InvalidInput));
+            // Nem um NFT que pertence a outra carteira
+            assert_eq!(contract.deposit_nft_guarantee(first, collection_id, 6), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.get_nft_guarantee_value(first, collection_id, accounts.bob, 5), 0);
+            assert_eq!(contract.get_project_nft_guarantee_total(second, collection_id), 0);
+            assert_eq!(psp34_ledger::owner_of(accounts.charlie, 5), Some(core));
+            assert_eq!(psp34_ledger::owner_of(accounts.charlie, 6), Some(accounts.django));
+
+            // Após o saque o NFT volta a poder ser depositado pelo dono
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.withdraw_nft_guarantee(first, collection_id, 5).unwrap();
+            assert_eq!(psp34_ledger::owner_of(accounts.charlie, 5), Some(accounts.alice));
+            contract.deposit_nft_guarantee(second, collection_id, 5).unwrap();
+            assert_eq!(contract.get_nft_guarantee_value(second, collection_id, accounts.alice, 5), 2000);
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        #[ink::test]
+        fn test_nft_unauthorized_access() {
+            let (mut contract, accounts) = setup();
+            
+            // Try to add NFT collection as non-owner
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let result = contract.add_nft_collection(
+                accounts.charlie,
+                *b"HackNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"HACK\0\0\0\0",
+                5000,
+                NFTValuationMethod::FixedValue(1000),
+            );
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::NotOwner);
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        #[ink::test]
+        fn test_invalid_nft_operations() {
+            let (mut contract, accounts) = setup();
+            
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            let invalid_collection_id = 999;
+            let nft_token_id = 123;
+            
+            // Try to deposit NFT with invalid collection
+            let result = contract.deposit_nft_guarantee(project_id, invalid_collection_id, nft_token_id);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::InvalidInput);
+            
+            // Try to withdraw non-existent NFT guarantee
+            let result = contract.withdraw_nft_guarantee(project_id, invalid_collection_id, nft_token_id);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::InvalidInput);
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        #[ink::test]
+        fn test_nft_valuation_methods() {
+            let (mut contract, accounts) = setup();
+            
+            // Test different valuation methods
+            let base_value = 1500;
+            
+            // Fixed value method
+            let fixed_collection = contract.add_nft_collection(
+                accounts.bob,
+                *b"FixedNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"FIXED\0\0\0",
+                base_value,
+                NFTValuationMethod::FixedValue(1000),
+            ).unwrap();
+            
+            // Trait-based method (currently uses base value)
+            let trait_collection = contract.add_nft_collection(
+                accounts.charlie,
+                *b"TraitNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"TRAIT\0\0\0",
+                base_value,
+                NFTValuationMethod::TraitBased,
+            ).unwrap();
+            
+            // Oracle price method (currently uses base value)
+            let oracle_collection = contract.add_nft_collection(
+                accounts.django,
+                *b"OracleNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"ORACLE\0\0",
+                base_value,
+                NFTValuationMethod::OraclePrice,
+            ).unwrap();
+            
+            // Verify collection info for each method
+            let fixed_info = contract.get_nft_collection_info(fixed_collection).unwrap();
+            assert!(matches!(fixed_info.valuation_method, NFTValuationMethod::FixedValue(_)));
+            
+            let trait_info = contract.get_nft_collection_info(trait_collection).unwrap();
+            assert_eq!(trait_info.valuation_method, NFTValuationMethod::TraitBased);
+            
+            let oracle_info = contract.get_nft_collection_info(oracle_collection).unwrap();
+            assert_eq!(oracle_info.valuation_method, NFTValuationMethod::OraclePrice);
+        }
+
+        #[ink::test]
+        fn test_project_creation_timestamp() {
+            let (mut contract, accounts) = setup();
+            
+            // Register a project and check if timestamp is set
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            
+            let timestamp = contract.get_project_creation_timestamp(project_id);
+            assert!(timestamp.is_some());
+            // In test environment, timestamp might be 0, so we just check it exists
+            let _timestamp_value = timestamp.unwrap(); // Just verify it doesn't panic
+        }
+
+        #[ink::test]
+        fn test_vesting_period_not_met() {
+            let (mut contract, accounts) = setup();
+            
+            // Register project and add guarantee
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            contract.project_guarantees.insert(&(project_id, accounts.alice), &1000);
+            
+            // Try to request guarantee release immediately (should fail)
+            let result = contract.request_guarantee_release(project_id);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::VestingPeriodNotMet);
+            
+            // Check vesting period status
+            assert!(!contract.is_vesting_period_met(project_id));
+            
+            // Check remaining vesting time
+            let remaining_time = contract.get_remaining_vesting_time(project_id);
+            assert!(remaining_time.is_some());
+            assert!(remaining_time.unwrap() > 0);
+        }
+
+        #[ink::test]
+        fn test_vesting_period_calculations() {
+            let (mut contract, accounts) = setup();
+            
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            
+            // Test vesting period calculation constants
+            let vesting_period: u64 = 5 * 365 * 24 * 60 * 60 * 1000; // 5 years in milliseconds
+            assert_eq!(vesting_period, 157_680_000_000); // 5 years in milliseconds
+            
+            // Verify remaining time calculation
+            let remaining_time = contract.get_remaining_vesting_time(project_id);
+            assert!(remaining_time.is_some());
+            
+            // Should be close to 5 years (allowing for small timestamp differences)
+            let remaining = remaining_time.unwrap();
+            assert!(remaining > vesting_period - 1000); // Within 1 second tolerance
+            assert!(remaining <= vesting_period);
+        }
+
+        #[ink::test]
+        fn test_request_guarantee_release_no_guarantees() {
+            let (mut contract, accounts) = setup();
+            
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            
+            // Try to request release without any guarantees
+            let result = contract.request_guarantee_release(project_id);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::InvalidInput);
+        }
+
+        #[ink::test]
+        fn test_request_guarantee_release_project_paused() {
+            let (mut contract, accounts) = setup();
+            
+            // Register project and add guarantee
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            contract.project_guarantees.insert(&(project_id, accounts.alice), &1000);
+            
+            // Pause the project
+            contract.emergency_pause_project(project_id, true).unwrap();
+            
+            // Try to request release from paused project (will fail due to vesting period first)
+            let result = contract.request_guarantee_release(project_id);
+            assert!(result.is_err());
+            // In test environment, vesting period check happens first, so we expect VestingPeriodNotMet
+            assert_eq!(result.unwrap_err(), SafeguardError::VestingPeriodNotMet);
+        }
+
+        #[ink::test]
+        fn test_request_guarantee_release_withdrawal_in_progress() {
+            let (mut contract, accounts) = setup();
+            
+            // Register project and add guarantee
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            contract.project_guarantees.insert(&(project_id, accounts.alice), &1000);
+            
+            // Set withdrawal in progress
+            contract.project_withdraw_statuses.insert(project_id, &true);
+            
+            // Try to request release while withdrawal is in progress (will fail due to vesting period first)
+            let result = contract.request_guarantee_release(project_id);
+            assert!(result.is_err());
+            // In test environment, vesting period check happens first, so we expect VestingPeriodNotMet
+            assert_eq!(result.unwrap_err(), SafeguardError::VestingPeriodNotMet);
+        }
+
+        #[ink::test]
+        fn test_vesting_period_functionality() {
+            let (mut contract, accounts) = setup();
+            
+            // Register project and add guarantee
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            contract.project_guarantees.insert(&(project_id, accounts.alice), &1000);
             
-            // Second entry should fail
-            let result = guard.start();
+            // Test that vesting period is not met initially
+            let result = contract.request_guarantee_release(project_id);
             assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::ReentrancyDetected);
+            assert_eq!(result.unwrap_err(), SafeguardError::VestingPeriodNotMet);
+            
+            // Test vesting period helper functions
+            assert!(!contract.is_vesting_period_met(project_id));
+            
+            let remaining_time = contract.get_remaining_vesting_time(project_id);
+            assert!(remaining_time.is_some());
+            
+            let creation_timestamp = contract.get_project_creation_timestamp(project_id);
+            assert!(creation_timestamp.is_some());
+        }
+
+        #[ink::test]
+        fn test_vesting_period_edge_cases() {
+            let (mut contract, _accounts) = setup();
+            
+            // Test with non-existent project
+            let non_existent_project = 999;
+            assert!(!contract.is_vesting_period_met(non_existent_project));
+            assert_eq!(contract.get_project_creation_timestamp(non_existent_project), None);
+            assert_eq!(contract.get_remaining_vesting_time(non_existent_project), None);
+        }
+
+        #[cfg(feature = "builtin-score")]
+        #[ink::test]
+        fn test_project_score_calculation() {
+            let (mut contract, accounts) = setup();
+            
+            // Register project
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            
+            // Initially, project should have score 0 (no guarantees)
+            let initial_score = contract.calculate_project_score(project_id);
+            assert_eq!(initial_score, 0);
+            
+            // Add Lunes token (mandatory for Score v1.1)
+            let lunes_token_id = contract.add_supported_token(
+                accounts.alice,
+                *b"LUNES\0\0\0",
+                18,
+                1000,
+            ).unwrap();
+            
+            // Configure Lunes token ID
+            contract.set_lunes_token_id(lunes_token_id).unwrap();
+            
+            // Add Lunes guarantee
+            let lunes_amount = 100_000 * 1_000_000_000_000_000_000; // 100K Lunes (T_min)
+            contract.add_guarantee(project_id, lunes_token_id, lunes_amount).unwrap();
+            
+            // Score should now be higher (Score v1.1 gives points for Lunes)
+            let score_with_guarantee = contract.get_project_score(project_id);
+            assert!(score_with_guarantee > initial_score);
+            assert!(score_with_guarantee > 0); // Should have some score from Lunes
+        }
+        
+        #[cfg(feature = "builtin-score")]
+        #[ink::test]
+        fn test_score_value_tiers() {
+            let (mut contract, accounts) = setup();
+            
+            // Register project
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            
+            // Add Lunes token (mandatory for Score v1.1)
+            let lunes_token_id = contract.add_supported_token(
+                accounts.alice,
+                *b"LUNES\0\0\0",
+                18,
+                1000,
+            ).unwrap();
+            
+            // Configure Lunes token ID
+            contract.set_lunes_token_id(lunes_token_id).unwrap();
+            
+            // Test different Lunes amounts - Score v1.1 gives up to 95 points from Lunes
+            let lunes_unit = 1_000_000_000_000_000_000; // 18 decimals
+            let test_cases = vec![
+                (10_000 * lunes_unit, 1), // Small amount should give some score
+                (100_000 * lunes_unit, 5), // T_min amount should give higher score  
+                (500_000 * lunes_unit, 20), // Should give significant score
+                (1_000_000 * lunes_unit, 30), // Large amount should give good score
+            ];
+            
+            for (amount, expected_min_score) in test_cases {
+                // Create fresh contract for each test
+                let (mut test_contract, test_accounts) = setup();
+                let project_id = test_contract.register_project(
+                    b"Test Project".to_vec(),
+                    b"ipfs://metadata".to_vec(),
+                    accounts.alice,
+                    accounts.bob
+                ).unwrap();
+                
+                let lunes_token_id = test_contract.add_supported_token(
+                    test_accounts.alice,
+                    *b"LUNES\0\0\0",
+                    18,
+                    1000,
+                ).unwrap();
+                test_contract.set_lunes_token_id(lunes_token_id).unwrap();
+                
+                test_contract.add_guarantee(project_id, lunes_token_id, amount).unwrap();
+                let score = test_contract.get_project_score(project_id);
+                assert!(score >= expected_min_score, "Amount: {}, Expected: {}, Got: {}", amount, expected_min_score, score);
+            }
+        }
+        
+        #[cfg(feature = "nft-collateral")]
+        #[ink::test]
+        fn test_score_asset_diversity() {
+            let (mut contract, accounts) = setup();
+            
+            // Register project
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            
+            // Add Lunes token first (required for Score v1.1)
+            let lunes_token_id = contract.add_supported_token(
+                accounts.alice,
+                *b"LUNES\0\0\0",
+                18,
+                1000,
+            ).unwrap();
+            contract.set_lunes_token_id(lunes_token_id).unwrap();
+            
+            // Add other PSP22 tokens
+            let token2_id = contract.add_supported_token(
+                accounts.alice,
+                *b"TK2\0\0\0\0\0",
+                18,
+                1000,
+            ).unwrap();
+            
+            // Add NFT collection
+            let collection_id = contract.add_nft_collection(
+                accounts.alice,
+                *b"Test NFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"TNFT\0\0\0\0",
+                1_000_000, // 1 LUSDT base value
+                NFTValuationMethod::FixedValue(1_000_000),
+            ).unwrap();
+            
+            let lunes_amount = 100_000 * 1_000_000_000_000_000_000; // 100K Lunes
+            
+            // Test with Lunes only
+            contract.add_guarantee(project_id, lunes_token_id, lunes_amount).unwrap();
+            let score_lunes_only = contract.get_project_score(project_id);
+            assert!(score_lunes_only > 0); // Score v1.1: Lunes gives score
+            
+            // Add second PSP22 token (other tokens give up to 5 extra points)
+            contract.add_guarantee(project_id, token2_id, 1_000_000).unwrap();
+            let score_with_other = contract.get_project_score(project_id);
+            assert!(score_with_other >= score_lunes_only); // May get diversity bonus
+            
+            // Add NFT guarantee (NFTs give up to 2.5 extra points)
+            psp34_ledger::mint(accounts.alice, 1, accounts.alice);
+            contract.deposit_nft_guarantee(project_id, collection_id, 1).unwrap();
+            let score_with_nft = contract.get_project_score(project_id);
+            assert!(score_with_nft >= score_with_other);
+        }
+        
+        #[cfg(feature = "builtin-score")]
+        #[ink::test]
+        fn test_score_vesting_bonus() {
+            let (mut contract, accounts) = setup();
+            
+            // Register project
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            
+            // Add Lunes token first (required for Score v1.1)
+            let lunes_token_id = contract.add_supported_token(
+                accounts.alice,
+                *b"LUNES\0\0\0",
+                18,
+                1000,
+            ).unwrap();
+            contract.set_lunes_token_id(lunes_token_id).unwrap();
+            
+            // Add Lunes guarantee
+            let lunes_amount = 100_000 * 1_000_000_000_000_000_000; // 100K Lunes
+            contract.add_guarantee(project_id, lunes_token_id, lunes_amount).unwrap();
+            
+            // Get initial score (should have score from Lunes)
+            let initial_score = contract.get_project_score(project_id);
+            
+            // Score v1.1: Lunes gives up to 95 points
+            // Vesting bonus is additional but requires time
+            // In test environment, timestamps might be 0 or very low
+            assert!(initial_score > 0);
+            assert!(initial_score <= 100);
+        }
+        
+        #[cfg(feature = "builtin-score")]
+        #[ink::test]
+        fn test_score_caching_and_updates() {
+            let (mut contract, accounts) = setup();
+            
+            // Register project
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
+            
+            // Add Lunes token (required for Score v1.1)
+            let lunes_token_id = contract.add_supported_token(
+                accounts.alice,
+                *b"LUNES\0\0\0",
+                18,
+                1000,
+            ).unwrap();
+            contract.set_lunes_token_id(lunes_token_id).unwrap();
+            
+            // Initially no cached score (no Lunes guarantee yet)
+            let initial_score = contract.get_project_score(project_id);
+            assert_eq!(initial_score, 0);
+            
+            // Update score manually (still 0, no Lunes)
+            let updated_score = contract.update_project_score(project_id).unwrap();
+            assert_eq!(updated_score, 0);
             
-            // End should clear the guard
-            guard.end();
-            assert!(!guard.is_entered());
+            // Add Lunes guarantee (should auto-update score)
+            let lunes_amount = 100_000 * 1_000_000_000_000_000_000; // 100K Lunes
+            contract.add_guarantee(project_id, lunes_token_id, lunes_amount).unwrap();
+            let score_after_guarantee = contract.get_project_score(project_id);
+            assert!(score_after_guarantee > 0);
+            
+            // Withdraw half Lunes guarantee (should auto-update score)
+            contract.withdraw_guarantee(project_id, lunes_token_id, lunes_amount / 2).unwrap();
+            let score_after_withdrawal = contract.get_project_score(project_id);
+            assert!(score_after_withdrawal <= score_after_guarantee); // May be equal or less
+            assert!(score_after_withdrawal > 0); // Still has some Lunes
         }
-
+        
+        #[cfg(feature = "nft-collateral")]
         #[ink::test]
-        fn test_add_supported_token() {
+        fn test_score_with_nft_guarantees() {
             let (mut contract, accounts) = setup();
             
-            // Add a new token
-            let symbol = *b"USDT\0\0\0\0";
-            let result = contract.add_supported_token(
-                accounts.bob, // token contract address
-                symbol,
-                6, // decimals
-                1000, // min guarantee amount
-            );
+            // Register project
+            let project_id = contract.register_project(
+                b"Test Project".to_vec(),
+                b"ipfs://metadata".to_vec(),
+                accounts.alice,
+                accounts.bob
+            ).unwrap();
             
-            assert!(result.is_ok());
-            let token_id = result.unwrap();
-            assert_eq!(token_id, 0);
-            assert_eq!(contract.next_token_id, 1);
+            // Add Lunes token first (required for Score v1.1)
+            let lunes_token_id = contract.add_supported_token(
+                accounts.alice,
+                *b"LUNES\0\0\0",
+                18,
+                1000,
+            ).unwrap();
+            contract.set_lunes_token_id(lunes_token_id).unwrap();
             
-            // Verify token info
-            let token_info = contract.get_token_info(token_id);
-            assert!(token_info.is_some());
-            let info = token_info.unwrap();
-            assert_eq!(info.contract_address, accounts.bob);
-            assert_eq!(info.symbol, symbol);
-            assert_eq!(info.decimals, 6);
-            assert_eq!(info.min_guarantee_amount, 1000);
-            assert!(info.is_active);
+            // Add NFT collection
+            let collection_id = contract.add_nft_collection(
+                accounts.alice,
+                *b"Valuable NFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"VNFT\0\0\0\0",
+                50_000 * 1_000_000, // 50K LUSDT base value
+                NFTValuationMethod::FixedValue(50_000 * 1_000_000),
+            ).unwrap();
+            
+            // Initially score is 0 (no Lunes guarantee)
+            let initial_score = contract.get_project_score(project_id);
+            assert_eq!(initial_score, 0);
+            
+            // Add Lunes first (required for any score in v1.1)
+            let lunes_amount = 100_000 * 1_000_000_000_000_000_000; // 100K Lunes
+            contract.add_guarantee(project_id, lunes_token_id, lunes_amount).unwrap();
+            let score_with_lunes = contract.get_project_score(project_id);
+            assert!(score_with_lunes > 0);
+            
+            // Deposit NFT (NFTs give up to 2.5 bonus points in Score v1.1)
+            psp34_ledger::mint(accounts.alice, 1, accounts.alice);
+            contract.deposit_nft_guarantee(project_id, collection_id, 1).unwrap();
+            let score_with_nft = contract.get_project_score(project_id);
+            assert!(score_with_nft >= score_with_lunes); // Should be same or higher with NFT
+            
+            // Withdraw NFT (should auto-update score)
+            contract.withdraw_nft_guarantee(project_id, collection_id, 1).unwrap();
+            let score_after_withdrawal = contract.get_project_score(project_id);
+            assert!(score_after_withdrawal > 0); // Still has Lunes
+        }
+        
+        #[ink::test]
+        fn test_score_edge_cases() {
+            let (mut contract, _accounts) = setup();
+            
+            // Test score for non-existent project
+            let non_existent_project = 999;
+            let score = contract.calculate_project_score(non_existent_project);
+            assert_eq!(score, 0);
+            
+            // Test update score for non-existent project
+            let result = contract.update_project_score(non_existent_project);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), SafeguardError::InvalidInput);
         }
 
         #[ink::test]
-        fn test_add_guarantee() {
+        fn test_vesting_helper_functions() {
             let (mut contract, accounts) = setup();
             
-            // First register a project
-            let project_result = contract.register_project(
+            // Register project
+            let project_id = contract.register_project(
                 b"Test Project".to_vec(),
                 b"ipfs://metadata".to_vec(),
                 accounts.alice,
                 accounts.bob
-            );
-            assert!(project_result.is_ok());
-            let project_id = project_result.unwrap();
-            
-            // Add a supported token
-            let symbol = *b"LUNES\0\0\0";
-            let token_result = contract.add_supported_token(
-                accounts.charlie,
-                symbol,
-                8,
-                500,
-            );
-            assert!(token_result.is_ok());
-            let token_id = token_result.unwrap();
-            
-            // Add guarantee
-            let guarantee_amount = 1000;
-            let result = contract.add_guarantee(project_id, token_id, guarantee_amount);
-            assert!(result.is_ok());
-            
-            // Verify guarantee was added
-            let user_guarantee = contract.get_user_guarantee(project_id, token_id, accounts.alice);
-            assert_eq!(user_guarantee, guarantee_amount);
+            ).unwrap();
             
-            let total_guarantee = contract.get_project_total_guarantee(project_id, token_id);
-            assert_eq!(total_guarantee, guarantee_amount);
+            // Test helper functions work correctly
+            assert!(!contract.is_vesting_period_met(project_id));
+            assert!(contract.get_project_creation_timestamp(project_id).is_some());
+            assert!(contract.get_remaining_vesting_time(project_id).is_some());
             
-            let user_balance = contract.get_user_token_balance(accounts.alice, token_id);
-            assert_eq!(user_balance, guarantee_amount);
+            let remaining_time = contract.get_remaining_vesting_time(project_id);
+            assert!(remaining_time.is_some());
         }
-
+        
         #[ink::test]
-        fn test_donate_to_guarantee() {
+        fn test_comprehensive_error_handling() {
             let (mut contract, accounts) = setup();
             
-            // Setup project and token
+            // Test operations on non-existent project
+            let non_existent_project = 999;
+            
+            // Should fail gracefully
+            assert_eq!(contract.add_guarantee(non_existent_project, 0, 1000), Err(SafeguardError::InvalidInput));
+            assert_eq!(contract.donate_to_guarantee(non_existent_project, 0, 1000), Err(SafeguardError::InvalidInput));
+            assert_eq!(contract.withdraw_guarantee(non_existent_project, 0, 1000), Err(SafeguardError::InvalidInput));
+            assert_eq!(contract.request_guarantee_release(non_existent_project), Err(SafeguardError::InvalidInput));
+            assert_eq!(contract.update_project_score(non_existent_project), Err(SafeguardError::InvalidInput));
+            
+            // Test with invalid amounts
             let project_id = contract.register_project(
                 b"Test Project".to_vec(),
                 b"ipfs://metadata".to_vec(),
@@ -3496,1060 +9351,2581 @@ pub mod safeguard {
                 accounts.bob
             ).unwrap();
             let token_id = contract.add_supported_token(
-                accounts.charlie,
-                *b"DONATE\0\0",
-                8,
-                100,
+                accounts.alice,
+                *b"TEST\0\0\0\0",
+                18,
+                1000,
             ).unwrap();
             
-            // Donate to guarantee pool
-            let donation_amount = 2000;
-            let result = contract.donate_to_guarantee(project_id, token_id, donation_amount);
-            assert!(result.is_ok());
-            
-            // Verify donation was added to project total
-            let total_guarantee = contract.get_project_total_guarantee(project_id, token_id);
-            assert_eq!(total_guarantee, donation_amount);
+            // Zero amount should fail
+            assert_eq!(contract.add_guarantee(project_id, token_id, 0), Err(SafeguardError::InvalidAmount));
+            assert_eq!(contract.donate_to_guarantee(project_id, token_id, 0), Err(SafeguardError::InvalidAmount));
             
-            // User guarantee should be 0 (donations don't count as user guarantees)
-            let user_guarantee = contract.get_user_guarantee(project_id, token_id, accounts.alice);
-            assert_eq!(user_guarantee, 0);
+            // Amount below minimum should fail
+            assert_eq!(contract.add_guarantee(project_id, token_id, 500), Err(SafeguardError::InvalidAmount));
         }
-
+        
+        #[cfg(feature = "builtin-score")]
         #[ink::test]
-        fn test_withdraw_guarantee() {
+        fn test_score_consistency_across_operations() {
             let (mut contract, accounts) = setup();
             
-            // Setup project, token, and add guarantee
+            // Setup project with guarantees
             let project_id = contract.register_project(
                 b"Test Project".to_vec(),
                 b"ipfs://metadata".to_vec(),
                 accounts.alice,
                 accounts.bob
             ).unwrap();
-            let token_id = contract.add_supported_token(
-                accounts.charlie,
-                *b"WITHDRAW",
-                8,
-                500,
+            
+            // Add Lunes token (required for Score v1.1)
+            let lunes_token_id = contract.add_supported_token(
+                accounts.alice,
+                *b"LUNES\0\0\0",
+                18,
+                1000,
             ).unwrap();
+            contract.set_lunes_token_id(lunes_token_id).unwrap();
             
-            let initial_amount = 1500;
-            contract.add_guarantee(project_id, token_id, initial_amount).unwrap();
+            // Test score consistency (initially 0, no Lunes guarantee)
+            let initial_calculated = contract.calculate_project_score(project_id);
+            let initial_cached = contract.get_project_score(project_id);
+            assert_eq!(initial_calculated, initial_cached);
+            assert_eq!(initial_cached, 0);
             
-            // Withdraw part of the guarantee
-            let withdraw_amount = 500;
-            let result = contract.withdraw_guarantee(project_id, token_id, withdraw_amount);
-            assert!(result.is_ok());
+            // Add Lunes guarantee and verify auto-update
+            let lunes_amount = 100_000 * 1_000_000_000_000_000_000; // 100K Lunes
+            contract.add_guarantee(project_id, lunes_token_id, lunes_amount).unwrap();
+            let score_after_add = contract.get_project_score(project_id);
+            let calculated_after_add = contract.calculate_project_score(project_id);
+            assert_eq!(score_after_add, calculated_after_add);
+            assert!(score_after_add > initial_cached);
             
-            // Verify balances were updated
-            let remaining_guarantee = initial_amount - withdraw_amount;
-            let user_guarantee = contract.get_user_guarantee(project_id, token_id, accounts.alice);
-            assert_eq!(user_guarantee, remaining_guarantee);
+            // Manual update should return same score
+            let manual_update = contract.update_project_score(project_id).unwrap();
+            assert_eq!(manual_update, score_after_add);
             
-            let total_guarantee = contract.get_project_total_guarantee(project_id, token_id);
-            assert_eq!(total_guarantee, remaining_guarantee);
+            // Withdraw and verify consistency
+            contract.withdraw_guarantee(project_id, lunes_token_id, lunes_amount / 2).unwrap();
+            let score_after_withdraw = contract.get_project_score(project_id);
+            let calculated_after_withdraw = contract.calculate_project_score(project_id);
+            assert_eq!(score_after_withdraw, calculated_after_withdraw);
+            assert!(score_after_withdraw <= score_after_add); // Allow equal scores due to rounding
+        }
+
+        // ==================== PAUSABILITY TESTS ====================
+
+        #[ink::test]
+        fn test_pause_unpause() {
+            let (mut contract, _accounts) = setup();
             
-            let user_balance = contract.get_user_token_balance(accounts.alice, token_id);
-            assert_eq!(user_balance, remaining_guarantee);
+            // Initially not paused
+            assert!(!contract.is_contract_paused());
+            assert_eq!(contract.get_paused_at(), 0);
+            
+            // Pause the contract
+            assert!(contract.pause(0).is_ok());
+            assert!(contract.is_contract_paused());
+            // In test environment, timestamp may be 0, so we just check it was set
+            // (paused_at is set to block_timestamp which is 0 in tests)
+            
+            // Cannot pause again
+            assert_eq!(contract.pause(0), Err(SafeguardError::ContractPaused));
+            
+            // Unpause the contract
+            assert!(contract.unpause().is_ok());
+            assert!(!contract.is_contract_paused());
+            
+            // Cannot unpause again
+            assert_eq!(contract.unpause(), Err(SafeguardError::ContractNotPaused));
         }
 
         #[ink::test]
-        fn test_multi_asset_unauthorized_access() {
+        fn test_pause_only_owner() {
             let (mut contract, accounts) = setup();
             
-            // Try to add token as non-owner
+            // Switch to non-owner
             set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
-            let result = contract.add_supported_token(
-                accounts.charlie,
-                *b"HACK\0\0\0\0",
-                8,
-                1000,
-            );
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::NotOwner);
+            
+            // Non-owner cannot pause
+            assert_eq!(contract.pause(0), Err(SafeguardError::NotOwner));
+            
+            // Switch back to owner
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            
+            // Owner can pause
+            assert!(contract.pause(0).is_ok());
+            
+            // Switch to non-owner
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            
+            // Non-owner cannot unpause
+            assert_eq!(contract.unpause(), Err(SafeguardError::NotOwner));
         }
 
+        #[cfg(all(feature = "legacy-api", feature = "nft-collateral"))]
         #[ink::test]
-        fn test_invalid_token_operations() {
+        fn test_pause_blocks_state_changing_messages() {
             let (mut contract, accounts) = setup();
-            
             let project_id = contract.register_project(
                 b"Test Project".to_vec(),
                 b"ipfs://metadata".to_vec(),
                 accounts.alice,
                 accounts.bob
             ).unwrap();
-            let invalid_token_id = 999;
-            
-            // Try to add guarantee with invalid token
-            let result = contract.add_guarantee(project_id, invalid_token_id, 1000);
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::InvalidInput);
             
-            // Try to donate with invalid token
-            let result = contract.donate_to_guarantee(project_id, invalid_token_id, 1000);
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::InvalidInput);
-        }
-
-        #[ink::test]
-        fn test_add_nft_collection() {
-            let (mut contract, accounts) = setup();
+            contract.pause(3).unwrap();
+            assert_eq!(contract.get_pause_reason(), 3);
             
-            // Add a new NFT collection
-            let name = *b"Test Collection\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
-            let symbol = *b"TEST\0\0\0\0";
-            let base_value: Balance = 1000;
-            let collection_id = contract.add_nft_collection(
-                accounts.bob,
-                name,
-                symbol,
-                base_value,
-                NFTValuationMethod::FixedValue(1000),
+            assert_eq!(
+                contract.register_project(b"Other".to_vec(), b"ipfs://other".to_vec(), accounts.alice, accounts.bob),
+                Err(SafeguardError::ContractPaused)
             );
+            assert_eq!(contract.add_guarantee(project_id, 0, 100), Err(SafeguardError::ContractPaused));
+            assert_eq!(contract.withdraw_guarantee(project_id, 0, 100), Err(SafeguardError::ContractPaused));
+            assert_eq!(contract.deposit_nft_guarantee(project_id, 0, 1), Err(SafeguardError::ContractPaused));
+            assert_eq!(contract.process_claim(project_id), Err(SafeguardError::ContractPaused));
+            assert_eq!(contract.vote_active(project_id, true, 1000), Err(SafeguardError::ContractPaused));
+            // A pausa global bloqueia todos os módulos
+            assert!(contract.is_module_paused(PauseModule::Nft));
             
-            assert!(collection_id.is_ok());
-            let collection_id = collection_id.unwrap();
-            assert_eq!(collection_id, 0);
-            assert_eq!(contract.next_nft_collection_id, 1);
-            
-            // Verify collection info
-            let collection_info = contract.get_nft_collection_info(collection_id);
-            assert!(collection_info.is_some());
-            let info = collection_info.unwrap();
-            assert_eq!(info.contract_address, accounts.bob);
-            assert_eq!(info.name, name);
-            assert_eq!(info.symbol, symbol);
-            assert_eq!(info.base_value, base_value);
-            assert!(info.is_active);
-            assert!(matches!(info.valuation_method, NFTValuationMethod::FixedValue(_)));
+            contract.unpause().unwrap();
+            assert!(contract.vote_active(project_id, true, 1000).is_ok());
         }
 
+        #[cfg(feature = "legacy-api")]
         #[ink::test]
-        fn test_deposit_nft_guarantee() {
+        fn test_module_circuit_breaker() {
             let (mut contract, accounts) = setup();
-            
-            // Setup project and NFT collection
             let project_id = contract.register_project(
                 b"Test Project".to_vec(),
                 b"ipfs://metadata".to_vec(),
                 accounts.alice,
                 accounts.bob
             ).unwrap();
-            let collection_id = contract.add_nft_collection(
-                accounts.charlie,
-                *b"TestNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
-                *b"TNFT\0\0\0\0",
-                2500,
-                NFTValuationMethod::FixedValue(1000),
-            ).unwrap();
             
-            // Deposit NFT as guarantee
-            let nft_token_id = 123;
-            let result = contract.deposit_nft_guarantee(project_id, collection_id, nft_token_id);
-            assert!(result.is_ok());
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.pause_module(PauseModule::Deposits, 7), Err(SafeguardError::NotOwner));
             
-            // Verify NFT guarantee was deposited
-            let nft_value = contract.get_nft_guarantee_value(project_id, collection_id, accounts.alice, nft_token_id);
-            assert_eq!(nft_value, 2500);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.pause_module(PauseModule::Deposits, 7).unwrap();
+            assert_eq!(contract.pause_module(PauseModule::Deposits, 7), Err(SafeguardError::ModulePaused));
+            assert_eq!(contract.get_module_pause(PauseModule::Deposits).unwrap().reason, 7);
+            assert!(contract.is_module_paused(PauseModule::Deposits));
+            assert!(!contract.is_module_paused(PauseModule::Voting));
+            assert!(!contract.is_contract_paused());
             
-            let total_guarantee = contract.get_project_nft_guarantee_total(project_id, collection_id);
-            assert_eq!(total_guarantee, 2500);
+            assert_eq!(contract.add_guarantee(project_id, 0, 100), Err(SafeguardError::ModulePaused));
+            assert_eq!(contract.donate_to_guarantee(project_id, 0, 100), Err(SafeguardError::ModulePaused));
+            // Os demais subsistemas continuam operando
+            assert!(contract.vote_active(project_id, true, 1000).is_ok());
             
-            let deposit_count = contract.get_user_nft_deposit_count(accounts.alice, collection_id);
-            assert_eq!(deposit_count, 1);
+            contract.unpause_module(PauseModule::Deposits).unwrap();
+            assert_eq!(contract.get_module_pause(PauseModule::Deposits), None);
+            assert_eq!(contract.unpause_module(PauseModule::Deposits), Err(SafeguardError::ContractNotPaused));
         }
 
         #[ink::test]
-        fn test_withdraw_nft_guarantee() {
+        fn test_max_pause_duration_allows_public_unpause() {
             let (mut contract, accounts) = setup();
             
-            // Setup project, collection, and deposit NFT
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            ).unwrap();
-            let collection_id = contract.add_nft_collection(
-                accounts.charlie,
-                *b"WithdrawNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
-                *b"WNFT\0\0\0\0",
-                3000,
-                NFTValuationMethod::FixedValue(1000),
-            ).unwrap();
-            
-            let nft_token_id = 456;
-            contract.deposit_nft_guarantee(project_id, collection_id, nft_token_id).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_max_pause_duration(Some(1_000)), Err(SafeguardError::NotOwner));
             
-            // Withdraw NFT guarantee
-            let result = contract.withdraw_nft_guarantee(project_id, collection_id, nft_token_id);
-            assert!(result.is_ok());
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.set_max_pause_duration(Some(0)), Err(SafeguardError::InvalidInput));
+            contract.set_max_pause_duration(Some(1_000)).unwrap();
+            assert_eq!(contract.get_max_pause_duration(), Some(1_000));
             
-            // Verify NFT guarantee was withdrawn
-            let nft_value = contract.get_nft_guarantee_value(project_id, collection_id, accounts.alice, nft_token_id);
-            assert_eq!(nft_value, 0);
+            contract.pause(1).unwrap();
+            contract.pause_module(PauseModule::Claims, 2).unwrap();
             
-            let total_guarantee = contract.get_project_nft_guarantee_total(project_id, collection_id);
-            assert_eq!(total_guarantee, 0);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.unpause(), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.unpause_module(PauseModule::Claims), Err(SafeguardError::NotOwner));
             
-            let deposit_count = contract.get_user_nft_deposit_count(accounts.alice, collection_id);
-            assert_eq!(deposit_count, 0);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert!(contract.unpause().is_ok());
+            assert!(contract.unpause_module(PauseModule::Claims).is_ok());
+            assert!(!contract.is_module_paused(PauseModule::Claims));
         }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_multiple_nft_deposits() {
+        fn test_reentrancy_guard_on_external_call_paths() {
             let (mut contract, accounts) = setup();
+            let operation_id = contract.hash_operation(1, Vec::new(), Hash::default());
+            
+            // Simula uma chamada reentrante vinda de um token durante a execução
+            contract.reentrancy_guard.entered = true;
+            assert_eq!(contract.deposit_guarantee_with_fees(0, 0, 1), Err(SafeguardError::ReentrancyDetected));
+            assert_eq!(contract.start_annual_voting(0), Err(SafeguardError::ReentrancyDetected));
+            assert_eq!(contract.vote_on_proposal(0, true), Err(SafeguardError::ReentrancyDetected));
+            assert_eq!(contract.claim_voter_reward(0), Err(SafeguardError::ReentrancyDetected));
+            assert_eq!(contract.process_claim(0), Err(SafeguardError::ReentrancyDetected));
+            assert_eq!(contract.release_queued_outflow(0), Err(SafeguardError::ReentrancyDetected));
+            assert_eq!(contract.execute_operation(operation_id), Err(SafeguardError::ReentrancyDetected));
+            // Registro e migração podem instanciar e financiar o cofre dedicado
+            assert_eq!(
+                contract.register_project(b"Reentrant".to_vec(), Vec::new(), accounts.django, accounts.django),
+                Err(SafeguardError::ReentrancyDetected)
+            );
+            #[cfg(feature = "legacy-api")]
+            assert_eq!(contract.register_project_legacy(None), Err(SafeguardError::ReentrancyDetected));
+            assert_eq!(contract.migrate_legacy_project(0), Err(SafeguardError::ReentrancyDetected));
             
-            // Setup project and collection
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            ).unwrap();
-            let collection_id = contract.add_nft_collection(
-                accounts.charlie,
-                *b"MultiNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
-                *b"MNFT\0\0\0\0",
-                1000,
-                NFTValuationMethod::FixedValue(1000),
-            ).unwrap();
+            // O guard é liberado mesmo quando a chamada falha
+            contract.reentrancy_guard.entered = false;
+            assert_eq!(contract.process_claim(0), Err(SafeguardError::ProjectNotFound));
+            assert_eq!(contract.execute_operation(operation_id), Err(SafeguardError::OperationNotFound));
+            assert!(!contract.reentrancy_guard.is_entered());
+        }
+
+        #[ink::test]
+        fn test_token_selector_scheme() {
+            let (mut contract, accounts) = setup();
+            let token_id = contract.add_supported_token(accounts.django, *b"USDT\0\0\0\0", 6, 1000).unwrap();
             
-            // Deposit multiple NFTs
-            let nft_ids = [100, 200, 300];
-            for &nft_id in &nft_ids {
-                let result = contract.deposit_nft_guarantee(project_id, collection_id, nft_id);
-                assert!(result.is_ok());
-            }
+            // Tokens novos usam os seletores namespaced do trait PSP22
+            assert_eq!(contract.get_token_info(token_id).unwrap().selector_scheme, SelectorScheme::TraitNamespaced);
             
-            // Verify all NFTs were deposited
-            let total_guarantee = contract.get_project_nft_guarantee_total(project_id, collection_id);
-            assert_eq!(total_guarantee, 3000); // 3 NFTs * 1000 each
+            let custom = crate::psp22_client::Psp22Selectors {
+                transfer: [0xdb, 0x20, 0xf9, 0xf5],
+                transfer_from: [0x54, 0xb3, 0xc7, 0x6e],
+                balance_of: [0x65, 0x68, 0x38, 0x2f],
+            };
+            let duplicated = crate::psp22_client::Psp22Selectors { balance_of: custom.transfer, ..custom };
+            assert_eq!(
+                contract.set_token_selector_scheme(token_id, SelectorScheme::Custom(duplicated)),
+                Err(SafeguardError::InvalidInput)
+            );
+            assert_eq!(
+                contract.set_token_selector_scheme(99, SelectorScheme::Bare),
+                Err(SafeguardError::TokenNotSupported)
+            );
             
-            let deposit_count = contract.get_user_nft_deposit_count(accounts.alice, collection_id);
-            assert_eq!(deposit_count, 3);
+            contract.set_token_selector_scheme(token_id, SelectorScheme::Custom(custom)).unwrap();
+            assert_eq!(contract.get_token_info(token_id).unwrap().selector_scheme, SelectorScheme::Custom(custom));
             
-            // Verify individual NFT values
-            for &nft_id in &nft_ids {
-                let nft_value = contract.get_nft_guarantee_value(project_id, collection_id, accounts.alice, nft_id);
-                assert_eq!(nft_value, 1000);
-            }
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.set_token_selector_scheme(token_id, SelectorScheme::Bare),
+                Err(SafeguardError::NotOwner)
+            );
         }
 
         #[ink::test]
-        fn test_nft_unauthorized_access() {
-            let (mut contract, accounts) = setup();
-            
-            // Try to add NFT collection as non-owner
+        fn test_token_calls_follow_selector_scheme() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            let core = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            let token = AccountId::from([0x51; 32]);
+            mock_env::register_psp22(token, SelectorScheme::Bare);
+            let token_id = contract.add_supported_token(token, *b"BARE\0\0\0\0", 6, 1).unwrap();
+
+            // O token não expõe os seletores namespaced: a chamada falha no dispatch
             set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
-            let result = contract.add_nft_collection(
-                accounts.charlie,
-                *b"HackNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
-                *b"HACK\0\0\0\0",
-                5000,
-                NFTValuationMethod::FixedValue(1000),
+            assert_eq!(
+                contract.add_guarantee(project_id, token_id, 500),
+                Err(SafeguardError::TokenCallFailed { token, stage: CallStage::Transfer, cause: CallFailure::Dispatch })
             );
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::NotOwner);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.set_token_selector_scheme(token_id, SelectorScheme::Bare).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.add_guarantee(project_id, token_id, 500).unwrap();
+            assert_eq!(test_ledger::balance_of(token, core), 500);
         }
 
         #[ink::test]
-        fn test_invalid_nft_operations() {
+        fn test_error_codes_and_token_causes() {
+            let accounts = default_accounts::<ink::env::DefaultEnvironment>();
+            
+            // Códigos estáveis usados pelo frontend
+            assert_eq!(SafeguardError::NotOwner.code(), 1);
+            assert_eq!(SafeguardError::InvalidInput.code(), 15);
+            assert_eq!(SafeguardError::TransferFailed.code(), 37);
+            assert_eq!(SafeguardError::EscapeHatchNotActive.code(), 53);
+            
+            // Mensagens customizadas do token não viram mais InvalidInput
+            let custom = psp22::PSP22Error::Custom(String::from("frozen"));
+            assert_eq!(SafeguardError::from(custom.clone()), SafeguardError::TokenError(custom.clone()));
+            assert_eq!(SafeguardError::from(psp22::PSP22Error::InsufficientAllowance), SafeguardError::InsufficientAllowance);
+            
+            let error = SafeguardError::TokenCallFailed {
+                token: accounts.django,
+                stage: crate::security::CallStage::Transfer,
+                cause: crate::security::CallFailure::Psp22(custom),
+            };
+            assert_eq!(error.code(), 54);
+            let encoded = scale::Encode::encode(&error);
+            assert_eq!(<SafeguardError as scale::Decode>::decode(&mut &encoded[..]), Ok(error));
+            assert_eq!(
+                crate::security::CallFailure::from(ink::env::Error::CalleeTrapped),
+                crate::security::CallFailure::CalleeTrapped
+            );
+        }
+
+        // ==================== TIMELOCK TESTS ====================
+
+        #[cfg(feature = "timelock")]
+        #[ink::test]
+        fn test_schedule_operation() {
             let (mut contract, accounts) = setup();
             
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            ).unwrap();
-            let invalid_collection_id = 999;
-            let nft_token_id = 123;
-            
-            // Try to deposit NFT with invalid collection
-            let result = contract.deposit_nft_guarantee(project_id, invalid_collection_id, nft_token_id);
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::InvalidInput);
+            // Schedule an operation
+            let result = contract.schedule_operation(0, scale::Encode::encode(&accounts.bob), Hash::default()); // TransferContractOwnership
+            assert!(result.is_ok());
+            let operation_id = result.unwrap();
+            assert_eq!(operation_id, contract.hash_operation(0, scale::Encode::encode(&accounts.bob), Hash::default()));
             
-            // Try to withdraw non-existent NFT guarantee
-            let result = contract.withdraw_nft_guarantee(project_id, invalid_collection_id, nft_token_id);
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::InvalidInput);
+            // Verify operation was stored
+            let operation = contract.get_operation(operation_id);
+            assert!(operation.is_some());
+            let op = operation.unwrap();
+            assert_eq!(op.function_type, TimelockFunction::TransferContractOwnership);
+            assert!(!op.is_executed);
+            assert!(!op.is_cancelled);
         }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_nft_valuation_methods() {
+        fn test_schedule_operation_only_owner() {
             let (mut contract, accounts) = setup();
             
-            // Test different valuation methods
-            let base_value = 1500;
-            
-            // Fixed value method
-            let fixed_collection = contract.add_nft_collection(
-                accounts.bob,
-                *b"FixedNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
-                *b"FIXED\0\0\0",
-                base_value,
-                NFTValuationMethod::FixedValue(1000),
-            ).unwrap();
+            // Switch to non-owner
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
             
-            // Trait-based method (currently uses base value)
-            let trait_collection = contract.add_nft_collection(
-                accounts.charlie,
-                *b"TraitNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
-                *b"TRAIT\0\0\0",
-                base_value,
-                NFTValuationMethod::TraitBased,
-            ).unwrap();
+            // Non-owner cannot schedule
+            assert_eq!(contract.schedule_operation(0, scale::Encode::encode(&accounts.bob), Hash::default()), Err(SafeguardError::NotOwner));
+        }
+
+        #[cfg(feature = "timelock")]
+        #[ink::test]
+        fn test_cancel_operation() {
+            let (mut contract, accounts) = setup();
             
-            // Oracle price method (currently uses base value)
-            let oracle_collection = contract.add_nft_collection(
-                accounts.django,
-                *b"OracleNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
-                *b"ORACLE\0\0",
-                base_value,
-                NFTValuationMethod::OraclePrice,
-            ).unwrap();
+            // Schedule an operation
+            let operation_id = contract.schedule_operation(0, scale::Encode::encode(&accounts.bob), Hash::default()).unwrap();
             
-            // Verify collection info for each method
-            let fixed_info = contract.get_nft_collection_info(fixed_collection).unwrap();
-            assert!(matches!(fixed_info.valuation_method, NFTValuationMethod::FixedValue(_)));
+            // Cancel the operation
+            assert!(contract.cancel_operation(operation_id).is_ok());
             
-            let trait_info = contract.get_nft_collection_info(trait_collection).unwrap();
-            assert_eq!(trait_info.valuation_method, NFTValuationMethod::TraitBased);
+            // Verify operation was cancelled
+            let operation = contract.get_operation(operation_id).unwrap();
+            assert!(operation.is_cancelled);
             
-            let oracle_info = contract.get_nft_collection_info(oracle_collection).unwrap();
-            assert_eq!(oracle_info.valuation_method, NFTValuationMethod::OraclePrice);
+            // Cannot cancel again
+            assert_eq!(contract.cancel_operation(operation_id), Err(SafeguardError::OperationNotFound));
         }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_project_creation_timestamp() {
+        fn test_execute_operation_not_ready() {
             let (mut contract, accounts) = setup();
             
-            // Register a project and check if timestamp is set
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            ).unwrap();
+            // Schedule an operation
+            let operation_id = contract.schedule_operation(0, scale::Encode::encode(&accounts.bob), Hash::default()).unwrap();
             
-            let timestamp = contract.get_project_creation_timestamp(project_id);
-            assert!(timestamp.is_some());
-            // In test environment, timestamp might be 0, so we just check it exists
-            let _timestamp_value = timestamp.unwrap(); // Just verify it doesn't panic
+            // Try to execute immediately (should fail - not ready)
+            assert_eq!(contract.execute_operation(operation_id), Err(SafeguardError::OperationNotReady));
         }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_vesting_period_not_met() {
+        fn test_timelock_delay() {
             let (mut contract, accounts) = setup();
             
-            // Register project and add guarantee
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            ).unwrap();
-            contract.project_guarantees.insert(&(project_id, accounts.alice), &1000);
+            // Get default delay
+            let default_delay = contract.get_timelock_delay();
+            assert_eq!(default_delay, 48 * 60 * 60 * 1000); // 48 hours
             
-            // Try to request guarantee release immediately (should fail)
-            let result = contract.request_guarantee_release(project_id);
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::VestingPeriodNotMet);
+            // Set new delay (2 hours)
+            let new_delay = 2 * 60 * 60 * 1000;
+            assert!(contract.set_timelock_delay(new_delay).is_ok());
+            assert_eq!(contract.get_timelock_delay(), new_delay);
             
-            // Check vesting period status
-            assert!(!contract.is_vesting_period_met(project_id));
+            // Invalid delay (too short)
+            assert_eq!(contract.set_timelock_delay(30 * 60 * 1000), Err(SafeguardError::InvalidInput));
             
-            // Check remaining vesting time
-            let remaining_time = contract.get_remaining_vesting_time(project_id);
-            assert!(remaining_time.is_some());
-            assert!(remaining_time.unwrap() > 0);
+            // Invalid delay (too long)
+            assert_eq!(contract.set_timelock_delay(8 * 24 * 60 * 60 * 1000), Err(SafeguardError::InvalidInput));
         }
 
+        #[cfg(all(feature = "nft-collateral", feature = "timelock"))]
         #[ink::test]
-        fn test_vesting_period_calculations() {
+        fn test_timelock_function_types() {
             let (mut contract, accounts) = setup();
             
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            ).unwrap();
-            
-            // Test vesting period calculation constants
-            let vesting_period: u64 = 5 * 365 * 24 * 60 * 60 * 1000; // 5 years in milliseconds
-            assert_eq!(vesting_period, 157_680_000_000); // 5 years in milliseconds
+            let token_id = contract.add_supported_token(accounts.charlie, *b"LUNES\0\0\0", 18, 1).unwrap();
+            let payloads: [Vec<u8>; 7] = [
+                scale::Encode::encode(&accounts.bob),
+                scale::Encode::encode(&accounts.bob),
+                scale::Encode::encode(&(1u128, 2u128)),
+                scale::Encode::encode(&ScoreParameters::default()),
+                scale::Encode::encode(&(token_id, 100u128, accounts.bob)),
+                scale::Encode::encode(&(accounts.django, *b"NEW\0\0\0\0\0", 18u8, 1u128)),
+                scale::Encode::encode(&(accounts.django, [0u8; 32], *b"NFT\0\0\0\0\0", 1u128, NFTValuationMethod::TraitBased)),
+            ];
             
-            // Verify remaining time calculation
-            let remaining_time = contract.get_remaining_vesting_time(project_id);
-            assert!(remaining_time.is_some());
+            // Test all valid function types
+            for (func_type, data) in payloads.into_iter().enumerate() {
+                let result = contract.schedule_operation(func_type as u8, data, Hash::default());
+                assert!(result.is_ok(), "Failed for function type {}", func_type);
+            }
             
-            // Should be close to 5 years (allowing for small timestamp differences)
-            let remaining = remaining_time.unwrap();
-            assert!(remaining > vesting_period - 1000); // Within 1 second tolerance
-            assert!(remaining <= vesting_period);
+            // Invalid function type
+            assert_eq!(contract.schedule_operation(7, Vec::new(), Hash::default()), Err(SafeguardError::InvalidInput));
+            assert_eq!(contract.schedule_operation(255, Vec::new(), Hash::default()), Err(SafeguardError::InvalidInput));
         }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_request_guarantee_release_no_guarantees() {
+        fn test_schedule_operation_validates_payload() {
             let (mut contract, accounts) = setup();
             
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            ).unwrap();
-            
-            // Try to request release without any guarantees
-            let result = contract.request_guarantee_release(project_id);
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::InvalidInput);
+            // Empty, truncated or oversized payloads do not decode
+            assert_eq!(contract.schedule_operation(0, Vec::new(), Hash::default()), Err(SafeguardError::InvalidInput));
+            assert_eq!(contract.schedule_operation(2, scale::Encode::encode(&1u128), Hash::default()), Err(SafeguardError::InvalidInput));
+            let mut trailing = scale::Encode::encode(&accounts.bob);
+            trailing.push(0);
+            assert_eq!(contract.schedule_operation(1, trailing, Hash::default()), Err(SafeguardError::InvalidInput));
+            
+            // Decoded arguments are validated
+            assert_eq!(
+                contract.schedule_operation(0, scale::Encode::encode(&AccountId::from([0u8; 32])), Hash::default()),
+                Err(SafeguardError::ZeroAddress)
+            );
+            let out_of_range = ScoreParameters { kappa: Some(101), ..Default::default() };
+            assert_eq!(contract.schedule_operation(3, scale::Encode::encode(&out_of_range), Hash::default()), Err(SafeguardError::InvalidInput));
+            assert_eq!(
+                contract.schedule_operation(4, scale::Encode::encode(&(99u64, 100u128, accounts.bob)), Hash::default()),
+                Err(SafeguardError::TokenNotSupported)
+            );
         }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_request_guarantee_release_project_paused() {
+        fn test_execute_operation_applies_changes() {
             let (mut contract, accounts) = setup();
             
-            // Register project and add guarantee
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
+            let fees_op = contract.schedule_operation(2, scale::Encode::encode(&(7u128, 3u128)), Hash::default()).unwrap();
+            let score_op = contract.schedule_operation(
+                3,
+                scale::Encode::encode(&ScoreParameters { gamma: Some(150), ..Default::default() }),
+                Hash::default(),
             ).unwrap();
-            contract.project_guarantees.insert(&(project_id, accounts.alice), &1000);
+            let token_op = contract.schedule_operation(
+                5,
+                scale::Encode::encode(&(accounts.django, *b"NEW\0\0\0\0\0", 12u8, 5u128)),
+                Hash::default(),
+            ).unwrap();
+            let owner_op = contract.schedule_operation(0, scale::Encode::encode(&accounts.bob), Hash::default()).unwrap();
             
-            // Pause the project
-            contract.emergency_pause_project(project_id, true).unwrap();
+            let op = contract.get_operation(owner_op).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(op.execute_after);
             
-            // Try to request release from paused project (will fail due to vesting period first)
-            let result = contract.request_guarantee_release(project_id);
-            assert!(result.is_err());
-            // In test environment, vesting period check happens first, so we expect VestingPeriodNotMet
-            assert_eq!(result.unwrap_err(), SafeguardError::VestingPeriodNotMet);
+            contract.execute_operation(fees_op).unwrap();
+            assert_eq!(contract.get_deposit_fees(), (7, 3));
+            assert!(contract.get_operation(fees_op).unwrap().is_executed);
+            assert_eq!(contract.execute_operation(fees_op), Err(SafeguardError::OperationNotFound));
+            
+            contract.execute_operation(score_op).unwrap();
+            assert_eq!(contract.get_score_parameters().1, 150);
+            
+            contract.execute_operation(token_op).unwrap();
+            let token = contract.get_token_info(0).unwrap();
+            assert_eq!(token.contract_address, accounts.django);
+            assert_eq!(token.min_guarantee_amount, 5);
+            
+            contract.execute_operation(owner_op).unwrap();
+            assert_eq!(contract.get_pending_contract_owner().unwrap().new_owner, accounts.bob);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.accept_contract_ownership().unwrap();
+            assert_eq!(contract.owner().signers, vec![accounts.alice, accounts.bob]);
         }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_request_guarantee_release_withdrawal_in_progress() {
+        fn test_timelock_respects_protocol_governance() {
             let (mut contract, accounts) = setup();
             
-            // Register project and add guarantee
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            ).unwrap();
-            contract.project_guarantees.insert(&(project_id, accounts.alice), &1000);
+            // Queued before the handoff
+            let fees = contract.schedule_operation(2, scale::Encode::encode(&(1u128, 1u128)), Hash::default()).unwrap();
+            let upgrade = contract.schedule_operation(10, scale::Encode::encode(&Hash::from([7u8; 32])), Hash::default()).unwrap();
+            contract.enable_protocol_governance().unwrap();
             
-            // Set withdrawal in progress
-            contract.project_withdraw_statuses.insert(project_id, &true);
+            // Global parameters and upgrades are DAO-only; ownership remains with the timelock
+            assert_eq!(
+                contract.schedule_operation(2, scale::Encode::encode(&(2u128, 2u128)), Hash::default()),
+                Err(SafeguardError::GovernanceRequired)
+            );
+            assert_eq!(
+                contract.schedule_operation(10, scale::Encode::encode(&Hash::from([8u8; 32])), Hash::default()),
+                Err(SafeguardError::GovernanceRequired)
+            );
+            assert!(contract.schedule_operation(0, scale::Encode::encode(&accounts.bob), Hash::default()).is_ok());
             
-            // Try to request release while withdrawal is in progress (will fail due to vesting period first)
-            let result = contract.request_guarantee_release(project_id);
-            assert!(result.is_err());
-            // In test environment, vesting period check happens first, so we expect VestingPeriodNotMet
-            assert_eq!(result.unwrap_err(), SafeguardError::VestingPeriodNotMet);
+            // Operations queued earlier cannot be executed after the handoff either
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(96 * 60 * 60 * 1000);
+            assert_eq!(contract.execute_operation(fees), Err(SafeguardError::GovernanceRequired));
+            assert_eq!(contract.execute_operation(upgrade), Err(SafeguardError::GovernanceRequired));
+            assert_eq!(contract.get_operation_status(fees), Some(OperationStatus::Ready));
         }
 
+        #[cfg(all(feature = "nft-collateral", feature = "timelock"))]
         #[ink::test]
-        fn test_vesting_period_functionality() {
+        fn test_sensitive_setters_require_timelock_after_bootstrap() {
             let (mut contract, accounts) = setup();
+            assert_eq!(contract.get_bootstrap_admin(), Some(accounts.alice));
             
-            // Register project and add guarantee
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            ).unwrap();
-            contract.project_guarantees.insert(&(project_id, accounts.alice), &1000);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.end_bootstrap(), Err(SafeguardError::NotOwner));
             
-            // Test that vesting period is not met initially
-            let result = contract.request_guarantee_release(project_id);
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::VestingPeriodNotMet);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.end_bootstrap().unwrap();
+            assert_eq!(contract.get_bootstrap_admin(), None);
+            assert_eq!(contract.end_bootstrap(), Err(SafeguardError::TimelockRequired));
+            
+            assert_eq!(contract.set_deposit_fees(1, 1), Err(SafeguardError::TimelockRequired));
+            assert_eq!(contract.set_treasury_address(accounts.bob), Err(SafeguardError::TimelockRequired));
+            assert_eq!(
+                contract.set_score_parameters(None, Some(150), None, None, None, None, None, None),
+                Err(SafeguardError::TimelockRequired)
+            );
+            assert_eq!(contract.transfer_contract_ownership(accounts.bob), Err(SafeguardError::TimelockRequired));
+            assert_eq!(
+                contract.add_supported_token(accounts.django, *b"NEW\0\0\0\0\0", 18, 1),
+                Err(SafeguardError::TimelockRequired)
+            );
+            assert_eq!(
+                contract.add_nft_collection(accounts.django, [0u8; 32], *b"NFT\0\0\0\0\0", 1, NFTValuationMethod::TraitBased),
+                Err(SafeguardError::TimelockRequired)
+            );
             
-            // Test vesting period helper functions
-            assert!(!contract.is_vesting_period_met(project_id));
+            // The same change still goes through the timelock
+            let operation_id = contract.schedule_operation(2, scale::Encode::encode(&(1u128, 1u128)), Hash::default()).unwrap();
+            let op = contract.get_operation(operation_id).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(op.execute_after);
+            contract.execute_operation(operation_id).unwrap();
+            assert_eq!(contract.get_deposit_fees(), (1, 1));
+        }
+
+        #[ink::test]
+        fn test_bootstrap_admin_is_separate_from_owner() {
+            let (mut contract, accounts) = setup();
+            contract.transfer_bootstrap_admin(accounts.charlie).unwrap();
             
-            let remaining_time = contract.get_remaining_vesting_time(project_id);
-            assert!(remaining_time.is_some());
+            // Owner no longer bypasses the timelock
+            assert_eq!(contract.set_deposit_fees(1, 1), Err(SafeguardError::TimelockRequired));
             
-            let creation_timestamp = contract.get_project_creation_timestamp(project_id);
-            assert!(creation_timestamp.is_some());
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.set_deposit_fees(2, 2).unwrap();
+            assert_eq!(contract.get_deposit_fees(), (2, 2));
+            // The bootstrap admin holds no seat, so the new owner joins the council
+            contract.transfer_contract_ownership(accounts.bob).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.accept_contract_ownership().unwrap();
+            assert_eq!(contract.owner().signers, vec![accounts.alice, accounts.bob]);
         }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_vesting_period_edge_cases() {
-            let (mut contract, _accounts) = setup();
-            
-            // Test with non-existent project
-            let non_existent_project = 999;
-            assert!(!contract.is_vesting_period_met(non_existent_project));
-            assert_eq!(contract.get_project_creation_timestamp(non_existent_project), None);
-            assert_eq!(contract.get_remaining_vesting_time(non_existent_project), None);
+        fn test_operation_delays_per_function() {
+            let (mut contract, accounts) = setup();
+            let hour = 60 * 60 * 1000;
+            
+            assert_eq!(contract.get_min_delay(0), Ok(96 * hour));
+            assert_eq!(contract.get_min_delay(2), Ok(96 * hour));
+            assert_eq!(contract.get_min_delay(1), Ok(48 * hour));
+            assert_eq!(contract.get_min_delay(7), Ok(48 * hour));
+            assert_eq!(contract.get_min_delay(9), Ok(48 * hour));
+            assert_eq!(contract.get_min_delay(10), Ok(96 * hour));
+            assert_eq!(contract.get_min_delay(11), Err(SafeguardError::InvalidInput));
+            
+            let owner_op = contract.schedule_operation(0, scale::Encode::encode(&accounts.bob), Hash::default()).unwrap();
+            let treasury_op = contract.schedule_operation(1, scale::Encode::encode(&accounts.bob), Hash::default()).unwrap();
+            assert_eq!(contract.get_operation(owner_op).unwrap().execute_after, 96 * hour);
+            assert_eq!(contract.get_operation(treasury_op).unwrap().execute_after, 48 * hour);
+            
+            // The global delay acts as a floor
+            contract.set_timelock_delay(72 * hour).unwrap();
+            let fees_op = contract.schedule_operation(2, scale::Encode::encode(&(1u128, 1u128)), Hash::default()).unwrap();
+            let score_op = contract.schedule_operation(3, scale::Encode::encode(&ScoreParameters::default()), Hash::default()).unwrap();
+            assert_eq!(contract.get_operation(fees_op).unwrap().execute_after, 96 * hour);
+            assert_eq!(contract.get_operation(score_op).unwrap().execute_after, 72 * hour);
+            
+            contract.set_min_delay(3, 120 * hour).unwrap();
+            assert_eq!(contract.get_min_delay(3), Ok(120 * hour));
+            assert_eq!(contract.set_min_delay(3, hour - 1), Err(SafeguardError::InvalidInput));
+            // Never below the built-in defaults
+            assert_eq!(contract.set_min_delay(0, 48 * hour), Err(SafeguardError::InvalidInput));
+            assert_eq!(contract.set_min_delay(3, 47 * hour), Err(SafeguardError::InvalidInput));
+            
+            // Admin role alone is not enough: delays change only with council approval
+            contract.grant_role(Role::Admin, accounts.bob).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_min_delay(3, 100 * hour), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.set_timelock_delay(hour), Err(SafeguardError::NotOwner));
         }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_project_score_calculation() {
+        fn test_duplicate_operations_rejected() {
             let (mut contract, accounts) = setup();
+            let payload = scale::Encode::encode(&accounts.bob);
+            let salt = Hash::default();
             
-            // Register project
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            ).unwrap();
-            
-            // Initially, project should have score 0 (no guarantees)
-            let initial_score = contract.calculate_project_score(project_id);
-            assert_eq!(initial_score, 0);
-            
-            // Add Lunes token (mandatory for Score v1.1)
-            let lunes_token_id = contract.add_supported_token(
-                accounts.alice,
-                *b"LUNES\0\0\0",
-                18,
-                1000,
-            ).unwrap();
-            
-            // Configure Lunes token ID
-            contract.set_lunes_token_id(lunes_token_id).unwrap();
+            let operation_id = contract.schedule_operation(1, payload.clone(), salt).unwrap();
+            assert_eq!(operation_id, contract.hash_operation(1, payload.clone(), salt));
+            assert_eq!(contract.schedule_operation(1, payload.clone(), salt), Err(SafeguardError::OperationAlreadyExists));
             
-            // Add Lunes guarantee
-            let lunes_amount = 100_000 * 1_000_000_000_000_000_000; // 100K Lunes (T_min)
-            contract.add_guarantee(project_id, lunes_token_id, lunes_amount).unwrap();
+            // Same payload for another function is a different operation
+            assert_ne!(contract.schedule_operation(0, payload.clone(), salt).unwrap(), operation_id);
             
-            // Score should now be higher (Score v1.1 gives points for Lunes)
-            let score_with_guarantee = contract.get_project_score(project_id);
-            assert!(score_with_guarantee > initial_score);
-            assert!(score_with_guarantee > 0); // Should have some score from Lunes
+            // A vetoed record is never overwritten; repeating the call needs a new salt and a new delay
+            contract.set_guardian(accounts.frank, true).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            contract.veto_operation(operation_id, b"bad treasury".to_vec()).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.schedule_operation(1, payload.clone(), salt), Err(SafeguardError::OperationAlreadyExists));
+            assert_eq!(contract.get_operation_status(operation_id), Some(OperationStatus::Cancelled));
+            let retry = contract.schedule_operation(1, payload, Hash::from([1u8; 32])).unwrap();
+            assert_ne!(retry, operation_id);
+            assert_eq!(contract.get_operation_status(retry), Some(OperationStatus::Pending));
+            assert_eq!(contract.get_operation_count(), 3);
         }
-        
+
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_score_value_tiers() {
+        fn test_list_operations_by_status() {
             let (mut contract, accounts) = setup();
+            let hour = 60 * 60 * 1000;
             
-            // Register project
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            ).unwrap();
-            
-            // Add Lunes token (mandatory for Score v1.1)
-            let lunes_token_id = contract.add_supported_token(
-                accounts.alice,
-                *b"LUNES\0\0\0",
-                18,
-                1000,
-            ).unwrap();
-            
-            // Configure Lunes token ID
-            contract.set_lunes_token_id(lunes_token_id).unwrap();
-            
-            // Test different Lunes amounts - Score v1.1 gives up to 95 points from Lunes
-            let lunes_unit = 1_000_000_000_000_000_000; // 18 decimals
-            let test_cases = vec![
-                (10_000 * lunes_unit, 1), // Small amount should give some score
-                (100_000 * lunes_unit, 5), // T_min amount should give higher score  
-                (500_000 * lunes_unit, 20), // Should give significant score
-                (1_000_000 * lunes_unit, 30), // Large amount should give good score
-            ];
+            let treasury_op = contract.schedule_operation(1, scale::Encode::encode(&accounts.bob), Hash::default()).unwrap();
+            let fees_op = contract.schedule_operation(2, scale::Encode::encode(&(1u128, 1u128)), Hash::default()).unwrap();
+            let score_op = contract.schedule_operation(3, scale::Encode::encode(&ScoreParameters::default()), Hash::default()).unwrap();
+            let owner_op = contract.schedule_operation(0, scale::Encode::encode(&accounts.bob), Hash::default()).unwrap();
+            contract.cancel_operation(score_op).unwrap();
             
-            for (amount, expected_min_score) in test_cases {
-                // Create fresh contract for each test
-                let (mut test_contract, test_accounts) = setup();
-                let project_id = test_contract.register_project(
-                    b"Test Project".to_vec(),
-                    b"ipfs://metadata".to_vec(),
-                    accounts.alice,
-                    accounts.bob
-                ).unwrap();
-                
-                let lunes_token_id = test_contract.add_supported_token(
-                    test_accounts.alice,
-                    *b"LUNES\0\0\0",
-                    18,
-                    1000,
-                ).unwrap();
-                test_contract.set_lunes_token_id(lunes_token_id).unwrap();
-                
-                test_contract.add_guarantee(project_id, lunes_token_id, amount).unwrap();
-                let score = test_contract.get_project_score(project_id);
-                assert!(score >= expected_min_score, "Amount: {}, Expected: {}, Got: {}", amount, expected_min_score, score);
-            }
+            assert_eq!(contract.get_operations(OperationStatus::Pending, 0, 10).len(), 3);
+            assert_eq!(contract.get_operation_status(score_op), Some(OperationStatus::Cancelled));
+            
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(48 * hour);
+            contract.execute_operation(treasury_op).unwrap();
+            
+            let executed = contract.get_operations(OperationStatus::Executed, 0, 10);
+            assert_eq!(executed.len(), 1);
+            assert_eq!(executed[0].0, treasury_op);
+            let pending: Vec<OperationId> = contract.get_operations(OperationStatus::Pending, 0, 10)
+                .into_iter().map(|(id, _)| id).collect();
+            assert_eq!(pending, vec![fees_op, owner_op]);
+            
+            // Pages scan the schedule index
+            assert!(contract.get_operations(OperationStatus::Pending, 0, 2).iter().all(|(id, _)| *id == fees_op));
+            assert_eq!(contract.get_operations(OperationStatus::Pending, 2, 2).len(), 1);
+            
+            // The 48h operation would have expired after its 7 day window
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>((96 + 7 * 24) * hour + 1);
+            assert_eq!(contract.get_operations(OperationStatus::Expired, 0, 10).len(), 2);
+            assert_eq!(contract.get_operation_status(fees_op), Some(OperationStatus::Expired));
         }
-        
+
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_score_asset_diversity() {
+        fn test_guardian_veto() {
             let (mut contract, accounts) = setup();
+            contract.set_guardian(accounts.charlie, true).unwrap();
+            assert!(contract.is_guardian(accounts.charlie));
+            assert_eq!(contract.get_guardian_count(), 1);
             
-            // Register project
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
-            ).unwrap();
+            let operation_id = contract.schedule_operation(1, scale::Encode::encode(&accounts.django), Hash::default()).unwrap();
             
-            // Add Lunes token first (required for Score v1.1)
-            let lunes_token_id = contract.add_supported_token(
-                accounts.alice,
-                *b"LUNES\0\0\0",
-                18,
-                1000,
-            ).unwrap();
-            contract.set_lunes_token_id(lunes_token_id).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.veto_operation(operation_id, b"no".to_vec()), Err(SafeguardError::NotGuardian));
             
-            // Add other PSP22 tokens
-            let token2_id = contract.add_supported_token(
-                accounts.alice,
-                *b"TK2\0\0\0\0\0",
-                18,
-                1000,
-            ).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.veto_operation(operation_id, b"treasury not approved by community".to_vec()).unwrap();
+            assert_eq!(contract.get_operation_status(operation_id), Some(OperationStatus::Cancelled));
+            assert_eq!(contract.veto_operation(operation_id, Vec::new()), Err(SafeguardError::OperationNotFound));
             
-            // Add NFT collection
-            let collection_id = contract.add_nft_collection(
-                accounts.alice,
-                *b"Test NFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
-                *b"TNFT\0\0\0\0",
-                1_000_000, // 1 LUSDT base value
-                NFTValuationMethod::FixedValue(1_000_000),
-            ).unwrap();
+            // Vetoed operations cannot be executed
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let op = contract.get_operation(operation_id).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(op.execute_after);
+            assert_eq!(contract.execute_operation(operation_id), Err(SafeguardError::OperationNotFound));
+        }
+
+        #[cfg(feature = "timelock")]
+        #[ink::test]
+        fn test_guardian_veto_window_and_limits() {
+            let (mut contract, accounts) = setup();
+            assert_eq!(contract.set_guardian(accounts.alice, true), Err(SafeguardError::InvalidInput));
+            contract.set_guardian(accounts.charlie, true).unwrap();
             
-            let lunes_amount = 100_000 * 1_000_000_000_000_000_000; // 100K Lunes
+            let operation_id = contract.schedule_operation(1, scale::Encode::encode(&accounts.django), Hash::default()).unwrap();
             
-            // Test with Lunes only
-            contract.add_guarantee(project_id, lunes_token_id, lunes_amount).unwrap();
-            let score_lunes_only = contract.get_project_score(project_id);
-            assert!(score_lunes_only > 0); // Score v1.1: Lunes gives score
+            // Guardians cannot schedule or execute
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.schedule_operation(1, scale::Encode::encode(&accounts.eve), Hash::default()), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.execute_operation(operation_id), Err(SafeguardError::NotOwner));
             
-            // Add second PSP22 token (other tokens give up to 5 extra points)
-            contract.add_guarantee(project_id, token2_id, 1_000_000).unwrap();
-            let score_with_other = contract.get_project_score(project_id);
-            assert!(score_with_other >= score_lunes_only); // May get diversity bonus
+            // No veto once the operation is ready
+            let op = contract.get_operation(operation_id).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(op.execute_after);
+            assert_eq!(contract.veto_operation(operation_id, Vec::new()), Err(SafeguardError::VetoWindowClosed));
             
-            // Add NFT guarantee (NFTs give up to 2.5 extra points)
-            contract.deposit_nft_guarantee(project_id, collection_id, 1).unwrap();
-            let score_with_nft = contract.get_project_score(project_id);
-            assert!(score_with_nft >= score_with_other);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.set_guardian(accounts.charlie, false).unwrap();
+            assert_eq!(contract.get_guardian_count(), 0);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_guardian(accounts.bob, true), Err(SafeguardError::NotOwner));
         }
-        
+
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_score_vesting_bonus() {
+        fn test_operation_not_found() {
             let (mut contract, accounts) = setup();
             
-            // Register project
+            let unknown = Hash::from([9u8; 32]);
+            
+            // Try to cancel non-existent operation
+            assert_eq!(contract.cancel_operation(unknown), Err(SafeguardError::OperationNotFound));
+            
+            // Try to execute non-existent operation
+            assert_eq!(contract.execute_operation(unknown), Err(SafeguardError::OperationNotFound));
+            
+            // Get non-existent operation
+            assert!(contract.get_operation(unknown).is_none());
+        }
+
+        // ==================== VOTE WEIGHTING TESTS ====================
+
+        /// Projeto com bob (10_000) e charlie (100) como depositantes
+        fn setup_weighted_project() -> (Safeguard, ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment>, ProjectId) {
+            let (mut contract, accounts) = setup();
             let project_id = contract.register_project(
                 b"Test Project".to_vec(),
                 b"ipfs://metadata".to_vec(),
                 accounts.alice,
                 accounts.bob
             ).unwrap();
-            
-            // Add Lunes token first (required for Score v1.1)
-            let lunes_token_id = contract.add_supported_token(
-                accounts.alice,
+            let token_id = contract.add_supported_token(
+                accounts.charlie,
                 *b"LUNES\0\0\0",
                 18,
-                1000,
+                1,
+            ).unwrap();
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.add_guarantee(project_id, token_id, 10_000).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.add_guarantee(project_id, token_id, 100).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+
+            (contract, accounts, project_id)
+        }
+
+        /// Campos empacotados do struct raiz no layout v0, na ordem original
+        #[derive(scale::Encode)]
+        struct V0Root {
+            contract_owner: AccountId,
+            next_project_id: ProjectId,
+            next_token_id: TokenId,
+            next_nft_collection_id: NFTCollectionId,
+            lunes_token_id: Option<TokenId>,
+            current_lunes_supply: Balance,
+            alpha: Balance,
+            gamma: u32,
+            delta: u32,
+            t_min: Balance,
+            theta: u32,
+            s_ref: Balance,
+            floor_f: Balance,
+            kappa: u32,
+            epsilon: Balance,
+            next_voting_id: VotingId,
+            treasury_address: AccountId,
+            lunes_deposit_fee: Balance,
+            lusdt_deposit_fee: Balance,
+            lusdt_token_id: Option<TokenId>,
+            annual_voting_duration: u64,
+            correction_window_duration: u64,
+            claims_period_duration: u64,
+            is_paused: bool,
+            paused_at: u64,
+            next_operation_id: u64,
+            timelock_delay: u64,
+            timelock_expiry: u64,
+            reentrancy_entered: bool,
+        }
+
+        #[ink::test]
+        fn test_upgrade_from_v0_layout() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let root = V0Root {
+                contract_owner: accounts.django,
+                next_project_id: 3,
+                next_token_id: 1,
+                next_nft_collection_id: 0,
+                lunes_token_id: Some(0),
+                current_lunes_supply: 200_000_000,
+                alpha: 500_000,
+                gamma: 120,
+                delta: 100,
+                t_min: 100_000,
+                theta: 20,
+                s_ref: 1_000_000_000,
+                floor_f: 50_000_000,
+                kappa: 0,
+                epsilon: 1,
+                next_voting_id: 1,
+                treasury_address: accounts.eve,
+                lunes_deposit_fee: 100,
+                lusdt_deposit_fee: 10,
+                lusdt_token_id: None,
+                annual_voting_duration: 7,
+                correction_window_duration: 30,
+                claims_period_duration: 90,
+                is_paused: false,
+                paused_at: 0,
+                next_operation_id: 4,
+                timelock_delay: 48,
+                timelock_expiry: 168,
+                reentrancy_entered: false,
+            };
+
+            // O código atual decodifica o struct raiz v0 inteiro, sem sobrar bytes
+            let encoded = scale::Encode::encode(&root);
+            let mut input = &encoded[..];
+            let mut contract = <Safeguard as ink::storage::traits::Storable>::decode(&mut input).unwrap();
+            assert!(input.is_empty());
+            assert_eq!(contract.contract_owner, accounts.django);
+            assert_eq!((contract.next_project_id, contract.next_voting_id), (3, 1));
+            assert_eq!(contract.treasury_address, accounts.eve);
+            assert_eq!((contract.timelock_delay, contract.timelock_expiry), (48, 168));
+            assert!(contract.is_migration_pending());
+
+            // Valores v0 de structs que ganharam campos
+            ink::env::set_contract_storage(&(&contract.supported_tokens.key(), 0u64), &TokenInfoV0 {
+                contract_address: accounts.charlie,
+                symbol: *b"LUNES\0\0\0",
+                decimals: 18,
+                is_active: true,
+                min_guarantee_amount: 1,
+                price_feed_id: None,
+            });
+            ink::env::set_contract_storage(&(&contract.voting_infos.key(), 0u64), &VotingInfoV0 {
+                vote_type: VoteType::Annual,
+                start_timestamp: 0,
+                end_timestamp: 10,
+                snapshot_block: 0,
+                yes_votes: 5,
+                no_votes: 2,
+                total_eligible: 9,
+                result: VoteResult::Pending,
+                proposal_uri: Vec::new(),
+            });
+
+            // Antes da migração o owner v0 não tem privilégios; qualquer conta pode migrar
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.grant_role(Role::Keeper, accounts.bob), Err(SafeguardError::NotOwner));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.migrate(), Ok(STORAGE_VERSION));
+
+            assert_eq!(contract.contract_owner, AccountId::from([0u8; 32]));
+            assert!(contract._is_council_signer(accounts.django));
+            assert!(contract.has_role(Role::Admin, accounts.django));
+            assert_eq!(contract.get_bootstrap_admin(), Some(accounts.django));
+            assert_eq!(contract.get_token_info(0).unwrap().selector_scheme, SelectorScheme::Bare);
+            let voting = contract.get_voting_info(0).unwrap();
+            assert_eq!((voting.yes_weight, voting.no_weight, voting.total_eligible_weight), (5, 2, 9));
+            assert_eq!(voting.total_eligible, 0);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            contract.grant_role(Role::Keeper, accounts.bob).unwrap();
+        }
+
+        #[cfg(feature = "timelock")]
+        #[ink::test]
+        fn test_migration_gates_state_changing_messages() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            assert_eq!(contract.get_storage_version(), STORAGE_VERSION);
+            assert_eq!(contract.migrate(), Err(SafeguardError::NoMigrationPending));
+
+            // Upgrade agendado pelo Admin, com o atraso de 96 horas
+            let upgrade = scale::Encode::encode(&Hash::from([7u8; 32]));
+            assert_eq!(contract.schedule_operation(10, scale::Encode::encode(&Hash::from([0u8; 32])), Hash::default()), Err(SafeguardError::InvalidInput));
+            let operation_id = contract.schedule_operation(10, upgrade.clone(), Hash::default()).unwrap();
+            assert_eq!(contract.get_operation(operation_id).unwrap().execute_after, 96 * 60 * 60 * 1000);
+
+            // Simula o código novo rodando sobre o storage da versão anterior
+            contract.storage_version.set(&(STORAGE_VERSION - 1));
+            assert!(contract.is_migration_pending());
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(96 * 60 * 60 * 1000);
+            assert_eq!(contract.execute_operation(operation_id), Err(SafeguardError::MigrationPending));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.add_guarantee(project_id, 0, 50), Err(SafeguardError::MigrationPending));
+            assert_eq!(contract.withdraw_guarantee(project_id, 0, 50), Err(SafeguardError::MigrationPending));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.migrate(), Ok(STORAGE_VERSION));
+            assert!(!contract.is_migration_pending());
+            assert_eq!(contract.migrate(), Err(SafeguardError::NoMigrationPending));
+            contract.add_guarantee(project_id, 0, 50).unwrap();
+        }
+
+        #[ink::test]
+        fn test_export_import_to_new_deployment() {
+            let (mut old, accounts, project_id) = setup_weighted_project();
+            old.donate_to_guarantee(project_id, 0, 500).unwrap();
+            assert_eq!(old.begin_import(), Err(SafeguardError::InvalidInput));
+            assert_eq!(old.get_export_count(ExportKind::Guarantees), 2);
+            assert_eq!(old.export_page(ExportKind::Guarantees, 0, MAX_EXPORT_PAGE + 1), Err(SafeguardError::InvalidInput));
+            let vaults = old.export_page(ExportKind::Vaults, 0, 10).unwrap();
+            let guarantees = old.export_page(ExportKind::Guarantees, 0, 10).unwrap();
+            let first_page: Vec<GuaranteeExport> = scale::Decode::decode(&mut &old.export_page(ExportKind::Guarantees, 0, 1).unwrap()[..]).unwrap();
+            assert_eq!(first_page, vec![GuaranteeExport { project_id, token_id: 0, account: accounts.bob, amount: 10_000 }]);
+
+            // Novo deployment em outro endereço
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(AccountId::from([0x42; 32]));
+            let mut new = Safeguard::new();
+            new.add_supported_token(accounts.charlie, *b"LUNES\0\0\0", 18, 1).unwrap();
+            assert_eq!(new.import_page(ExportKind::Vaults, vaults.clone()), Err(SafeguardError::ImportNotActive));
+            new.begin_import().unwrap();
+            assert_eq!(new.import_page(ExportKind::Guarantees, guarantees.clone()), Err(SafeguardError::InvalidInput));
+            assert_eq!(new.import_page(ExportKind::Vaults, vaults.clone()), Ok(1));
+
+            // Depósitos do cofre (10_600 menos 500 doados) ainda sem depositantes
+            assert_eq!(new.finalize_import(), Err(SafeguardError::ImportTotalsMismatch));
+            let inflated = vec![GuaranteeExport { project_id, token_id: 0, account: accounts.eve, amount: 10_101 }];
+            assert_eq!(
+                new.import_page(ExportKind::Guarantees, scale::Encode::encode(&inflated)),
+                Err(SafeguardError::ImportTotalsMismatch)
+            );
+            assert_eq!(new.import_page(ExportKind::Guarantees, guarantees.clone()), Ok(2));
+            assert_eq!(new.import_page(ExportKind::Guarantees, guarantees.clone()), Err(SafeguardError::InvalidInput));
+
+            assert_eq!(new.get_import_state(), Some(ImportState::Active));
+            assert_eq!(new.get_import_expected_balance(0), 10_600);
+            assert_eq!(new.export_page(ExportKind::Vaults, 0, 10), Ok(vaults));
+            assert_eq!(new.export_page(ExportKind::Guarantees, 0, 10), Ok(guarantees));
+            assert_eq!(new.get_project_donations(project_id, 0), 500);
+
+            // Mensagens normais ficam bloqueadas até a finalização
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(new.add_guarantee(project_id, 0, 50), Err(SafeguardError::ImportInProgress));
+            assert_eq!(new.finalize_import(), Err(SafeguardError::NotOwner));
+
+            // Registros fechados; falta o saldo, doações incluídas
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(new.finalize_import(), Err(SafeguardError::ImportBalanceMismatch));
+            test_ledger::set_balance(accounts.charlie, AccountId::from([0x42; 32]), 10_600);
+            new.finalize_import().unwrap();
+            assert_eq!(new.get_import_state(), Some(ImportState::Locked));
+
+            // Deployment sem saldos a conferir: finaliza e trava para sempre
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(AccountId::from([0x43; 32]));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut empty = Safeguard::new();
+            empty.begin_import().unwrap();
+            empty.finalize_import().unwrap();
+            assert_eq!(empty.get_import_state(), Some(ImportState::Locked));
+            assert_eq!(empty.begin_import(), Err(SafeguardError::ImportLocked));
+            assert_eq!(empty.import_page(ExportKind::Vaults, Vec::new()), Err(SafeguardError::ImportNotActive));
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        #[ink::test]
+        fn test_import_requires_nft_custody() {
+            let (mut old, accounts, project_id) = setup_weighted_project();
+            let collection_id = old.add_nft_collection(
+                accounts.django,
+                *b"MigratedNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"MNFT\0\0\0\0",
+                1_000,
+                NFTValuationMethod::FixedValue(1_000),
             ).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            psp34_ledger::mint(accounts.django, 7, accounts.bob);
+            old.deposit_nft_guarantee(project_id, collection_id, 7).unwrap();
+            let old_core = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            let vaults = old.export_page(ExportKind::Vaults, 0, 10).unwrap();
+            let guarantees = old.export_page(ExportKind::Guarantees, 0, 10).unwrap();
+            let nfts = old.export_page(ExportKind::Nfts, 0, 10).unwrap();
+
+            let new_core = AccountId::from([0x42; 32]);
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(new_core);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut new = Safeguard::new();
+            new.add_supported_token(accounts.charlie, *b"LUNES\0\0\0", 18, 1).unwrap();
+            new.add_nft_collection(
+                accounts.django,
+                *b"MigratedNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"MNFT\0\0\0\0",
+                1_000,
+                NFTValuationMethod::FixedValue(1_000),
+            ).unwrap();
+            new.begin_import().unwrap();
+            new.import_page(ExportKind::Vaults, vaults).unwrap();
+            new.import_page(ExportKind::Guarantees, guarantees).unwrap();
+            test_ledger::set_balance(accounts.charlie, new_core, 10_100);
+
+            // O NFT ainda está com o core antigo
+            assert_eq!(new.import_page(ExportKind::Nfts, nfts.clone()), Err(SafeguardError::ImportBalanceMismatch));
+            assert_eq!(new.finalize_import(), Err(SafeguardError::ImportTotalsMismatch));
+
+            psp34_ledger::transfer(accounts.django, old_core, new_core, 7).unwrap();
+            assert_eq!(new.import_page(ExportKind::Nfts, nfts), Ok(1));
+            new.finalize_import().unwrap();
+            assert_eq!(new.get_nft_guarantee_value(project_id, collection_id, accounts.bob, 7), 1_000);
+        }
+
+        #[ink::test]
+        fn test_vault_custody_rule() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            let core = AccountId::from([0x40; 32]);
+            let bare = AccountId::from([0x51; 32]);
+            mock_env::register_psp22(bare, SelectorScheme::Bare);
+            let bare_id = contract.add_supported_token(bare, *b"BARE\0\0\0\0", 6, 1).unwrap();
+            contract.set_token_selector_scheme(bare_id, SelectorScheme::Bare).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            contract.add_guarantee(project_id, bare_id, 300).unwrap();
+            assert_eq!(test_ledger::balance_of(bare, core), 300);
+
+            // Saldo fora do esquema namespaced no core: o cofre não é criado e nada se move
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.set_vault_code_hash(Some(Hash::from([5u8; 32]))).unwrap();
+            assert_eq!(contract.create_vault(project_id), Err(SafeguardError::VaultTokenSchemeUnsupported));
+            assert_eq!(contract.get_project_vault_contract(project_id), None);
+            assert_eq!(test_ledger::balance_of(accounts.charlie, core), 10_100);
+
+            // Projetos com cofre só recebem tokens namespaced
+            let fresh = contract.register_project(b"Fresh".to_vec(), Vec::new(), accounts.alice, accounts.bob).unwrap();
+            let vault = contract.get_project_vault_contract(fresh).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.add_guarantee(fresh, bare_id, 300), Err(SafeguardError::VaultTokenSchemeUnsupported));
+            contract.add_guarantee(fresh, 0, 500).unwrap();
+            assert_eq!(test_ledger::balance_of(accounts.charlie, vault), 500);
+            assert_eq!(test_ledger::balance_of(bare, core), 300);
+        }
+
+        #[ink::test]
+        fn test_vault_factory_configuration() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            assert_eq!(contract.get_vault_code_hash(), None);
+            assert_eq!(contract.get_project_vault_contract(project_id), None);
+
+            // Sem code hash a factory fica desligada
+            assert_eq!(contract.create_vault(project_id), Err(SafeguardError::InvalidInput));
+            assert_eq!(contract.set_vault_code_hash(Some(Hash::from([0u8; 32]))), Err(SafeguardError::InvalidInput));
+            contract.set_vault_code_hash(Some(Hash::from([5u8; 32]))).unwrap();
+            assert_eq!(contract.get_vault_code_hash(), Some(Hash::from([5u8; 32])));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_vault_code_hash(None), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.create_vault(project_id), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.create_vault(99), Err(SafeguardError::ProjectNotFound));
+
+            // O cofre criado recebe as garantias que o projeto já tinha no core
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let core = AccountId::from([0x40; 32]);
+            let token = accounts.charlie;
+            assert_eq!(test_ledger::balance_of(token, core), 10_100);
+            let vault = contract.create_vault(project_id).unwrap();
+            assert_eq!(contract.get_project_vault_contract(project_id), Some(vault));
+            assert_eq!(test_ledger::balance_of(token, core), 0);
+            assert_eq!(test_ledger::balance_of(token, vault), 10_100);
+
+            // Um projeto tem no máximo um cofre dedicado
+            assert_eq!(contract.create_vault(project_id), Err(SafeguardError::VaultAlreadyExists));
+
+            // Novos depósitos entram direto no cofre e os saques saem dele
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            contract.add_guarantee(project_id, 0, 500).unwrap();
+            assert_eq!(test_ledger::balance_of(token, vault), 10_600);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let bob_before = test_ledger::balance_of(token, accounts.bob);
+            contract.withdraw_guarantee(project_id, 0, 4_000).unwrap();
+            assert_eq!(test_ledger::balance_of(token, accounts.bob), bob_before.saturating_add(4_000));
+            assert_eq!(test_ledger::balance_of(token, vault), 6_600);
+            assert_eq!(test_ledger::balance_of(token, core), 0);
+
+            // Com cofre dedicado o projeto não pode ser exportado; aposentar devolve a custódia ao core
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.export_page(ExportKind::Vaults, 0, 10), Err(SafeguardError::VaultAlreadyExists));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(contract.retire_vault(project_id), Err(SafeguardError::NotOwner));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.retire_vault(project_id).unwrap();
+            assert_eq!(contract.get_project_vault_contract(project_id), None);
+            assert_eq!(test_ledger::balance_of(token, vault), 0);
+            assert_eq!(test_ledger::balance_of(token, core), 6_600);
+            assert!(contract.export_page(ExportKind::Vaults, 0, 10).is_ok());
+            assert_eq!(contract.retire_vault(project_id), Err(SafeguardError::InvalidInput));
+
+            contract.set_vault_code_hash(None).unwrap();
+            assert_eq!(contract.get_vault_code_hash(), None);
+        }
+
+        #[ink::test]
+        fn test_vault_not_created_during_liquidation() {
+            let (mut contract, _accounts, project_id) = setup_weighted_project();
+            contract.set_vault_code_hash(Some(Hash::from([5u8; 32]))).unwrap();
+            let mut vault = contract.get_project_vault(project_id).unwrap();
+            vault.status = ProjectStatus::ClaimsOpen;
+            contract.project_vaults.insert(project_id, &vault);
+            assert_eq!(contract.create_vault(project_id), Err(SafeguardError::ProjectNotActive));
+            assert_eq!(contract.get_project_vault_contract(project_id), None);
+        }
+
+        #[cfg(feature = "legacy-api")]
+        #[ink::test]
+        fn test_legacy_project_migration_and_adapters() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+
+            // Projeto registrado antes do ProjectVault: só os mapeamentos v1
+            contract.project_vaults.remove(project_id);
+            contract.project_vote_yes_counts.insert(project_id, &2);
+            contract.project_psp22_pairs.insert(project_id, &Some(accounts.django));
+            assert!(contract.is_legacy_project(project_id));
+            assert_eq!(contract.get_project_info(project_id).unwrap().qtd_vote_yes, 2);
+
+            // Votação v1 ainda aberta: a migração espera o fim para não perder a apuração
+            contract.project_vote_end_times.insert(project_id, &1_000);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(contract.migrate_legacy_project(project_id), Err(SafeguardError::VotingInProgress));
+            assert_eq!(contract.get_project_info(project_id).unwrap().qtd_vote_yes, 2);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+
+            // Migração permissionless
+            assert_eq!(contract.migrate_legacy_project(99), Err(SafeguardError::ProjectNotFound));
+            contract.migrate_legacy_project(project_id).unwrap();
+            assert_eq!(contract.migrate_legacy_project(project_id), Err(SafeguardError::ProjectAlreadyMigrated));
+            assert!(!contract.is_legacy_project(project_id));
+            let vault = contract.get_project_vault(project_id).unwrap();
+            assert_eq!(vault.owner, accounts.alice);
+            assert_eq!(vault.status, ProjectStatus::Active);
+            assert_eq!(vault.token_contract, accounts.django);
+            assert_eq!(contract.get_project_info(project_id).unwrap().qtd_vote_yes, 0);
+
+            // vote_active abre a votação anual v2
+            assert_eq!(contract.vote_active(project_id, true, 500), Err(SafeguardError::NotOwner));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.vote_active(project_id, true, 500).unwrap();
+            let voting_id = contract.get_project_vault(project_id).unwrap().current_voting_id.unwrap();
+            assert_eq!(contract.vote_active(project_id, false, 500), Err(SafeguardError::VotingInProgress));
+
+            // vote usa o peso do snapshot v2
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.vote(project_id, false).unwrap();
+            assert_eq!(contract.vote(project_id, false), Err(SafeguardError::AlreadyVoted));
+            assert_eq!(contract.get_ballot(voting_id, accounts.bob).unwrap().raw_weight, 10_000);
+            assert_eq!(contract.get_user_vote(project_id, accounts.bob), Ok(Some(voting_id)));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.vote(project_id, true).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.vote(project_id, true), Err(SafeguardError::NotEligibleToVote));
+
+            // vote_finish finaliza a votação v2
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.vote_finish(project_id, 1), Err(SafeguardError::VotingStillActive));
+            let end = contract.get_voting_info(voting_id).unwrap().end_timestamp;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(end + 1);
+            contract.vote_finish(project_id, 1).unwrap();
+            assert_eq!(contract.get_project_vault(project_id).unwrap().status, ProjectStatus::Rejected);
+            let info = contract.get_project_info(project_id).unwrap();
+            assert_eq!((info.data_vote_end, info.qtd_vote_yes, info.qtd_vote_no), (end, 1, 1));
+            assert_eq!(info.balance_permission, 500);
+
+            // withdraw abre os claims do projeto rejeitado, só pela posição LUNES inteira (aqui vazia)
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.withdraw(project_id, 0), Err(SafeguardError::NotOwner));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.withdraw(project_id, 7), Err(SafeguardError::InvalidAmount));
+            contract.withdraw(project_id, 0).unwrap();
+            assert_eq!(contract.withdraw(project_id, 0), Err(SafeguardError::WithdrawalInProgress));
+            let info = contract.get_project_info(project_id).unwrap();
+            assert!(info.status_withdraw);
+            assert_eq!(info.balance_withdraw_per_lunes, 0);
+
+            // Novos registros v1 já nascem com ProjectVault; adaptadores migram sob demanda
+            let legacy_id = contract.register_project_legacy(None).unwrap();
+            assert!(contract.get_project_vault(legacy_id).is_some());
+            contract.project_vaults.remove(legacy_id);
+            contract.vote_active(legacy_id, false, 1).unwrap();
+            assert!(!contract.is_legacy_project(legacy_id));
+            assert_eq!(contract.withdraw(legacy_id, 7), Err(SafeguardError::ClaimsNotOpen));
+        }
+
+        #[cfg(feature = "builtin-score")]
+        #[ink::test]
+        fn test_score_engine_configuration() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            assert_eq!(contract.get_score_engine(), None);
+            assert_eq!(contract.get_score_formula_version(), score_engine::v1::VERSION);
+
+            // Sem motor o core usa a fórmula v1 embutida sobre os saldos do projeto
+            let lunes_token_id = contract.add_supported_token(accounts.django, *b"LUNES\0\0\0", 18, 1000).unwrap();
             contract.set_lunes_token_id(lunes_token_id).unwrap();
-            
-            // Add Lunes guarantee
-            let lunes_amount = 100_000 * 1_000_000_000_000_000_000; // 100K Lunes
+            let lunes_amount = 100_000 * 1_000_000_000_000_000_000;
             contract.add_guarantee(project_id, lunes_token_id, lunes_amount).unwrap();
-            
-            // Get initial score (should have score from Lunes)
-            let initial_score = contract.get_project_score(project_id);
-            
-            // Score v1.1: Lunes gives up to 95 points
-            // Vesting bonus is additional but requires time
-            // In test environment, timestamps might be 0 or very low
-            assert!(initial_score > 0);
-            assert!(initial_score <= 100);
+            let inputs = contract._score_inputs(project_id, lunes_amount);
+            assert_eq!(inputs.lunes_collateral, lunes_amount);
+            assert_eq!(
+                contract.calculate_project_score(project_id),
+                score_engine::v1::compute_score(&inputs, &contract._score_params())
+            );
+
+            assert_eq!(contract.set_score_engine(Some(AccountId::from([0u8; 32]))), Err(SafeguardError::ZeroAddress));
+            contract.set_score_engine(Some(accounts.eve)).unwrap();
+            assert_eq!(contract.get_score_engine(), Some(accounts.eve));
+
+            // Motor configurado que não responde: erro explícito, sem cair na fórmula embutida
+            let cached = contract.get_project_score(project_id);
+            assert!(cached > 0);
+            assert_eq!(contract.update_project_score(project_id), Err(SafeguardError::ScoreEngineCallFailed));
+            assert_eq!(contract.calculate_project_score(project_id), cached);
+            assert_eq!(contract.get_project_score(project_id), cached);
+            // Depósitos seguem aceitos enquanto o motor está fora do ar, com a falha registrada
+            contract.add_guarantee(project_id, lunes_token_id, 1_000).unwrap();
+            assert_eq!(contract.get_project_score(project_id), cached);
+            type Event = <Safeguard as ink::reflect::ContractEventBase>::Type;
+            let events = ink::env::test::recorded_events().collect::<Vec<_>>();
+            let failures = events.iter().filter(|event| {
+                matches!(
+                    <Event as scale::Decode>::decode(&mut &event.data[..]),
+                    Ok(Event::ScoreEngineCallFailed(ScoreEngineCallFailed { project_id: id, engine }))
+                        if id == project_id && engine == accounts.eve
+                )
+            });
+            assert_eq!(failures.count(), 1);
+
+            // Motor no ar: o score vem dele, limitado a 100
+            mock_env::register_score_engine(accounts.eve, 150, 7);
+            assert_eq!(contract.update_project_score(project_id), Ok(100));
+            assert_eq!(contract.get_score_formula_version(), 7);
+
+            // Projetos sem LUNES nem chegam a consultar o motor
+            let empty = contract.register_project(b"Empty".to_vec(), b"ipfs://empty".to_vec(), accounts.alice, accounts.bob).unwrap();
+            assert_eq!(contract.calculate_project_score(empty), 0);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_score_engine(None), Err(SafeguardError::NotOwner));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.set_score_engine(None).unwrap();
+            assert_eq!(contract.get_score_engine(), None);
+        }
+
+        #[cfg(not(feature = "builtin-score"))]
+        #[ink::test]
+        fn test_score_requires_engine_without_builtin_formula() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            let lunes_token_id = contract.add_supported_token(accounts.django, *b"LUNES\0\0\0", 18, 1000).unwrap();
+            contract.set_lunes_token_id(lunes_token_id).unwrap();
+            contract.add_guarantee(project_id, lunes_token_id, 1_000).unwrap();
+            assert_eq!(contract.update_project_score(project_id), Err(SafeguardError::FeatureDisabled));
+            assert_eq!(contract.calculate_project_score(project_id), 0);
+            assert_eq!(contract.get_score_formula_version(), 0);
+        }
+
+        #[ink::test]
+        fn test_compliance_hook_configuration() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            assert_eq!(contract.get_protocol_compliance_hook(), None);
+            assert_eq!(contract.get_compliance_failure_policy(), ComplianceFailurePolicy::Deny);
+
+            // Sem hooks configurados nenhuma chamada externa é feita
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.add_guarantee(project_id, 0, 50).unwrap();
+            assert_eq!(contract.set_project_compliance_hook(project_id, Some(accounts.eve)), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.set_protocol_compliance_hook(Some(accounts.eve)), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.set_compliance_failure_policy(ComplianceFailurePolicy::Allow), Err(SafeguardError::NotOwner));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.set_project_compliance_hook(project_id, Some(accounts.eve)).unwrap();
+            assert_eq!(contract.get_project_compliance_hook(project_id), Some(accounts.eve));
+            contract.set_project_compliance_hook(project_id, None).unwrap();
+            assert_eq!(contract.get_project_compliance_hook(project_id), None);
+            assert_eq!(contract.set_project_compliance_hook(99, None), Err(SafeguardError::ProjectNotFound));
+
+            contract.set_protocol_compliance_hook(Some(accounts.frank)).unwrap();
+            assert_eq!(contract.get_protocol_compliance_hook(), Some(accounts.frank));
+            assert_eq!(contract.set_protocol_compliance_hook(Some(AccountId::from([0u8; 32]))), Err(SafeguardError::ZeroAddress));
+            contract.set_compliance_failure_policy(ComplianceFailurePolicy::Allow).unwrap();
+            assert_eq!(contract.get_compliance_failure_policy(), ComplianceFailurePolicy::Allow);
+
+            // Depois da migração para a DAO só a governança altera hook e política
+            contract.enable_protocol_governance().unwrap();
+            assert_eq!(contract.set_protocol_compliance_hook(None), Err(SafeguardError::GovernanceRequired));
+        }
+
+        #[ink::test]
+        fn test_outflow_limit_queues_excess_withdrawals() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            let limit = OutflowLimit { max_amount: 5_000, window: 1_000 };
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_outflow_limit(0, Some(limit)), Err(SafeguardError::NotOwner));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                contract.set_outflow_limit(0, Some(OutflowLimit { max_amount: 5_000, window: 0 })),
+                Err(SafeguardError::InvalidInput)
+            );
+            assert_eq!(contract.set_outflow_limit(99, Some(limit)), Err(SafeguardError::TokenNotSupported));
+            contract.set_outflow_limit(0, Some(limit)).unwrap();
+            assert_eq!(contract.get_available_outflow(0), Some(5_000));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.withdraw_guarantee(project_id, 0, 4_000).unwrap();
+            assert_eq!(contract.get_available_outflow(0), Some(1_000));
+
+            // Excede a janela: saldo debitado, pagamento enfileirado
+            contract.withdraw_guarantee(project_id, 0, 3_000).unwrap();
+            assert_eq!(contract.get_user_guarantee(project_id, 0, accounts.bob), 3_000);
+            let queued = contract.get_queued_outflow(0).unwrap();
+            assert_eq!(queued.amount, 3_000);
+            assert_eq!(queued.kind, OutflowKind::Withdrawal(project_id));
+            assert_eq!(test_ledger::balance_of(accounts.charlie, accounts.bob), 4_000);
+            assert_eq!(contract.release_queued_outflow(0), Err(SafeguardError::OutflowLimitExceeded));
+
+            // A capacidade se recompõe linearmente ao longo da janela
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(contract.release_queued_outflow(0), Err(SafeguardError::OutflowLimitExceeded));
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(400);
+            contract.release_queued_outflow(0).unwrap();
+            assert_eq!(test_ledger::balance_of(accounts.charlie, accounts.bob), 7_000);
+            assert_eq!(contract.get_queued_outflow(0), None);
+            assert_eq!(contract.get_available_outflow(0), Some(0));
+            assert_eq!(contract.release_queued_outflow(0), Err(SafeguardError::QueuedOutflowNotFound));
+        }
+
+        #[ink::test]
+        fn test_outflow_limit_holds_claims_and_voter_rewards() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            let lunes = accounts.charlie; // Contrato do token listado em setup_weighted_project
+            let lusdt_id = contract.add_supported_token(accounts.django, *b"LUSDT\0\0\0", 6, 1).unwrap();
+            contract.set_lunes_token_id(0).unwrap();
+            contract.set_lusdt_token_id(lusdt_id).unwrap();
+            contract.set_deposit_fees(0, 0).unwrap();
+            contract.set_treasury_address(accounts.eve).unwrap();
+            contract.set_vote_fees(1_000, 0).unwrap();
+            contract.set_voter_reward_share(10_000).unwrap();
+            contract.set_guardian(accounts.frank, true).unwrap();
+
+            // Colateral LUNES do cofre, distribuído nos claims de liquidação
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.deposit_guarantee_with_fees(project_id, 0, 5_000).unwrap();
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let voting_id = contract.start_voting(project_id, VoteType::Liquidation).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.vote_on_proposal(project_id, true).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.vote_on_proposal(project_id, true).unwrap();
+            let end = contract.get_voting_info(voting_id).unwrap().end_timestamp;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(end.saturating_add(1));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.finalize_voting(project_id).unwrap();
+            contract.set_outflow_limit(0, Some(OutflowLimit { max_amount: 1_000, window: 1_000_000 })).unwrap();
+
+            // Claim e recompensa acima do limite ficam na fila; nada sai do core
+            let core = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            let held = test_ledger::balance_of(lunes, core);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.process_claim(project_id).unwrap();
+            let claim_share = contract.get_user_claim(project_id, accounts.bob).unwrap().lunes_share;
+            assert!(claim_share > 1_000);
+            let (reward, _) = contract.claim_voter_reward(voting_id).unwrap();
+            assert!(reward > 1_000);
+            assert_eq!(contract.get_queued_outflow(0).unwrap().kind, OutflowKind::Claim(project_id));
+            assert_eq!(contract.get_queued_outflow(1).unwrap().kind, OutflowKind::VoterReward(voting_id));
+            assert_eq!(test_ledger::balance_of(lunes, accounts.bob), 0);
+            assert_eq!(test_ledger::balance_of(lunes, core), held);
+
+            // Dentro do limite o claim é pago na hora
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.process_claim(project_id).unwrap();
+            let charlie_share = contract.get_user_claim(project_id, accounts.charlie).unwrap().lunes_share;
+            assert_eq!(test_ledger::balance_of(lunes, accounts.charlie), charlie_share);
+            assert_eq!(contract.get_next_queued_outflow_id(), 2);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            contract.release_queued_outflow(0).unwrap();
+            contract.release_queued_outflow(1).unwrap();
+            assert_eq!(test_ledger::balance_of(lunes, accounts.bob), claim_share.saturating_add(reward));
+        }
+
+        #[ink::test]
+        fn test_withdraw_blocked_once_liquidation_opens_claims() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            let lunes = accounts.charlie; // Contrato do token listado em setup_weighted_project
+            let lusdt_id = contract.add_supported_token(accounts.django, *b"LUSDT\0\0\0", 6, 1).unwrap();
+            contract.set_lunes_token_id(0).unwrap();
+            contract.set_lusdt_token_id(lusdt_id).unwrap();
+            contract.set_deposit_fees(0, 0).unwrap();
+            contract.set_treasury_address(accounts.eve).unwrap();
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.deposit_guarantee_with_fees(project_id, 0, 5_000).unwrap();
+            assert_eq!(contract.get_project_vault(project_id).unwrap().total_lunes_collateral, 5_000);
+            // Saque com o projeto ativo reduz o colateral rateado nos claims
+            contract.withdraw_guarantee(project_id, 0, 2_000).unwrap();
+            assert_eq!(contract.get_project_vault(project_id).unwrap().total_lunes_collateral, 3_000);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let voting_id = contract.start_voting(project_id, VoteType::Liquidation).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.withdraw_guarantee(project_id, 0, 1_000), Err(SafeguardError::ProjectNotActive));
+            contract.vote_on_proposal(project_id, true).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.vote_on_proposal(project_id, true).unwrap();
+            let end = contract.get_voting_info(voting_id).unwrap().end_timestamp;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(end.saturating_add(1));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.finalize_voting(project_id).unwrap();
+
+            // Com os claims abertos o depositante não retira o colateral dos holders
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.withdraw_guarantee(project_id, 0, 1_000), Err(SafeguardError::ProjectNotActive));
+            let core = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            let held = test_ledger::balance_of(lunes, core);
+
+            // Os claims somados cabem no colateral do cofre e são pagos por inteiro
+            contract.process_claim(project_id).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.process_claim(project_id).unwrap();
+            let bob_share = contract.get_user_claim(project_id, accounts.bob).unwrap().lunes_share;
+            let charlie_share = contract.get_user_claim(project_id, accounts.charlie).unwrap().lunes_share;
+            assert!(bob_share > 0 && charlie_share > 0);
+            assert!(bob_share.saturating_add(charlie_share) <= 3_000);
+            assert_eq!(test_ledger::balance_of(lunes, accounts.charlie), charlie_share);
+            assert_eq!(test_ledger::balance_of(lunes, core), held.saturating_sub(bob_share).saturating_sub(charlie_share));
+        }
+
+        #[cfg(feature = "timelock")]
+        #[ink::test]
+        fn test_outflow_limit_holds_emergency_withdrawals() {
+            let (mut contract, accounts, _project_id) = setup_weighted_project();
+            let lunes = accounts.charlie;
+            contract.set_outflow_limit(0, Some(OutflowLimit { max_amount: 1_000, window: 1_000 })).unwrap();
+            contract.set_guardian(accounts.frank, true).unwrap();
+
+            let operation_id = contract.schedule_operation(4, scale::Encode::encode(&(0u64, 3_000u128, accounts.eve)), Hash::default()).unwrap();
+            let op = contract.get_operation(operation_id).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(op.execute_after);
+            contract.execute_operation(operation_id).unwrap();
+
+            let queued = contract.get_queued_outflow(0).unwrap();
+            assert_eq!(queued.kind, OutflowKind::Emergency);
+            assert_eq!(queued.amount, 3_000);
+            assert_eq!(test_ledger::balance_of(lunes, accounts.eve), 0);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            contract.release_queued_outflow(0).unwrap();
+            assert_eq!(test_ledger::balance_of(lunes, accounts.eve), 3_000);
+        }
+
+        #[ink::test]
+        fn test_guardian_releases_queued_outflow() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            contract.set_outflow_limit(0, Some(OutflowLimit { max_amount: 1_000, window: 1_000 })).unwrap();
+            contract.set_guardian(accounts.django, true).unwrap();
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.withdraw_guarantee(project_id, 0, 2_000).unwrap();
+            assert_eq!(contract.get_next_queued_outflow_id(), 1);
+            assert_eq!(contract.release_queued_outflow(0), Err(SafeguardError::OutflowLimitExceeded));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.pause_module(PauseModule::Withdrawals, 1).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.release_queued_outflow(0), Err(SafeguardError::ModulePaused));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.unpause_module(PauseModule::Withdrawals).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            contract.release_queued_outflow(0).unwrap();
+            assert_eq!(contract.get_available_outflow(0), Some(0));
+        }
+
+        #[ink::test]
+        fn test_escape_hatch_after_pause_deadline() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            contract.donate_to_guarantee(project_id, 0, 1_010).unwrap();
+            assert_eq!(contract.get_project_donations(project_id, 0), 1_010);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.escape_withdraw(project_id, 0), Err(SafeguardError::EscapeHatchNotActive));
+            assert_eq!(contract.activate_escape_hatch(), Err(SafeguardError::ContractNotPaused));
+            assert_eq!(contract.set_escape_hatch_delay(1_000), Err(SafeguardError::NotOwner));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.set_escape_hatch_delay(1_000).unwrap();
+            contract.pause(9).unwrap();
+            assert_eq!(contract.set_escape_hatch_delay(5_000), Err(SafeguardError::ContractPaused));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.activate_escape_hatch(), Err(SafeguardError::OperationNotReady));
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            contract.activate_escape_hatch().unwrap();
+            assert_eq!(contract.get_escape_hatch_activated_at(), Some(1_000));
+            assert_eq!(contract.activate_escape_hatch(), Err(SafeguardError::EscapeHatchActive));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.unpause(), Err(SafeguardError::EscapeHatchActive));
+
+            // Doações rateadas pela garantia: 10_000 / 10_100 e 100 / 10_100
+            let core = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            assert_eq!(test_ledger::balance_of(accounts.charlie, core), 11_110);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.escape_withdraw(project_id, 0), Ok(11_000));
+            assert_eq!(contract.escape_withdraw(project_id, 0), Err(SafeguardError::NoClaimableAmount));
+            assert_eq!(test_ledger::balance_of(accounts.charlie, accounts.bob), 11_000);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.escape_withdraw(project_id, 0), Ok(110));
+            assert_eq!(test_ledger::balance_of(accounts.charlie, core), 0);
+
+            assert_eq!(contract.get_user_guarantee(project_id, 0, accounts.bob), 0);
+            assert_eq!(contract.get_project_total_guarantee(project_id, 0), 0);
         }
-        
+
         #[ink::test]
-        fn test_score_caching_and_updates() {
+        fn test_escape_withdraw_donation_share_with_18_decimals() {
             let (mut contract, accounts) = setup();
-            
-            // Register project
             let project_id = contract.register_project(
                 b"Test Project".to_vec(),
                 b"ipfs://metadata".to_vec(),
                 accounts.alice,
                 accounts.bob
             ).unwrap();
-            
-            // Add Lunes token (required for Score v1.1)
-            let lunes_token_id = contract.add_supported_token(
-                accounts.alice,
-                *b"LUNES\0\0\0",
-                18,
-                1000,
-            ).unwrap();
-            contract.set_lunes_token_id(lunes_token_id).unwrap();
-            
-            // Initially no cached score (no Lunes guarantee yet)
-            let initial_score = contract.get_project_score(project_id);
-            assert_eq!(initial_score, 0);
-            
-            // Update score manually (still 0, no Lunes)
-            let updated_score = contract.update_project_score(project_id).unwrap();
-            assert_eq!(updated_score, 0);
-            
-            // Add Lunes guarantee (should auto-update score)
-            let lunes_amount = 100_000 * 1_000_000_000_000_000_000; // 100K Lunes
-            contract.add_guarantee(project_id, lunes_token_id, lunes_amount).unwrap();
-            let score_after_guarantee = contract.get_project_score(project_id);
-            assert!(score_after_guarantee > 0);
-            
-            // Withdraw half Lunes guarantee (should auto-update score)
-            contract.withdraw_guarantee(project_id, lunes_token_id, lunes_amount / 2).unwrap();
-            let score_after_withdrawal = contract.get_project_score(project_id);
-            assert!(score_after_withdrawal <= score_after_guarantee); // May be equal or less
-            assert!(score_after_withdrawal > 0); // Still has some Lunes
+            let lunes = contract.add_supported_token(accounts.charlie, *b"LUNES\0\0\0", 18, 1).unwrap();
+            contract.set_lunes_token_id(lunes).unwrap();
+            let unit: Balance = 1_000_000_000_000_000_000;
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.add_guarantee(project_id, lunes, 3_000_000 * unit).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.add_guarantee(project_id, lunes, 1_000_000 * unit).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.donate_to_guarantee(project_id, lunes, 400_000 * unit).unwrap();
+            let mut vault = contract.get_project_vault(project_id).unwrap();
+            vault.total_lunes_collateral = 4_400_000 * unit;
+            contract.project_vaults.insert(project_id, &vault);
+
+            contract.set_escape_hatch_delay(1_000).unwrap();
+            contract.pause(9).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            contract.activate_escape_hatch().unwrap();
+
+            // 400_000 * 3_000_000 em 18 casas passa de u128::MAX; o rateio não pode saturar
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.escape_withdraw(project_id, lunes), Ok(3_300_000 * unit));
+            assert_eq!(test_ledger::balance_of(accounts.charlie, accounts.bob), 3_300_000 * unit);
+            assert_eq!(contract.get_project_vault(project_id).unwrap().total_lunes_collateral, 1_100_000 * unit);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.escape_withdraw(project_id, lunes), Ok(1_100_000 * unit));
+            let core = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            assert_eq!(test_ledger::balance_of(accounts.charlie, core), 0);
+            assert_eq!(contract.get_project_vault(project_id).unwrap().total_lunes_collateral, 0);
+            assert_eq!(contract.get_project_total_guarantee(project_id, lunes), 0);
         }
-        
+
         #[ink::test]
-        fn test_score_with_nft_guarantees() {
+        fn test_guarantees_are_held_in_custody() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            let token = accounts.charlie; // Contrato do token listado em setup_weighted_project
+            let core = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            assert_eq!(test_ledger::balance_of(token, core), 10_100);
+
+            test_ledger::set_balance(token, accounts.django, 700);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            contract.add_guarantee(project_id, 0, 500).unwrap();
+            contract.donate_to_guarantee(project_id, 0, 200).unwrap();
+            assert_eq!(test_ledger::balance_of(token, accounts.django), 0);
+            assert_eq!(test_ledger::balance_of(token, core), 10_800);
+
+            contract.withdraw_guarantee(project_id, 0, 300).unwrap();
+            assert_eq!(test_ledger::balance_of(token, accounts.django), 300);
+            assert_eq!(test_ledger::balance_of(token, core), 10_500);
+        }
+
+        #[cfg(all(feature = "nft-collateral", feature = "timelock"))]
+        #[ink::test]
+        fn test_escape_hatch_via_timelock_returns_nfts() {
             let (mut contract, accounts) = setup();
-            
-            // Register project
             let project_id = contract.register_project(
                 b"Test Project".to_vec(),
                 b"ipfs://metadata".to_vec(),
                 accounts.alice,
                 accounts.bob
             ).unwrap();
-            
-            // Add Lunes token first (required for Score v1.1)
-            let lunes_token_id = contract.add_supported_token(
-                accounts.alice,
-                *b"LUNES\0\0\0",
-                18,
-                1000,
+            let collection_id = contract.add_nft_collection(
+                accounts.charlie,
+                *b"EscapeNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                *b"ENFT\0\0\0\0",
+                3000,
+                NFTValuationMethod::FixedValue(1000),
             ).unwrap();
-            contract.set_lunes_token_id(lunes_token_id).unwrap();
-            
-            // Add NFT collection
+            let core = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            psp34_ledger::mint(accounts.charlie, 7, accounts.bob);
+            contract.deposit_nft_guarantee(project_id, collection_id, 7).unwrap();
+            assert_eq!(psp34_ledger::owner_of(accounts.charlie, 7), Some(core));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.schedule_operation(8, vec![1], Hash::default()), Err(SafeguardError::InvalidInput));
+            let operation_id = contract.schedule_operation(8, Vec::new(), Hash::default()).unwrap();
+            let op = contract.get_operation(operation_id).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(op.execute_after);
+            contract.execute_operation(operation_id).unwrap();
+            assert!(contract.is_contract_paused());
+            assert!(contract.get_escape_hatch_activated_at().is_some());
+
+            // Saque de emergência do admin bloqueado: os fundos pertencem aos depositantes
+            assert_eq!(
+                contract.schedule_operation(4, scale::Encode::encode(&(0u64, 100u128, accounts.alice)), Hash::default()),
+                Err(SafeguardError::EscapeHatchActive)
+            );
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.withdraw_nft_guarantee(project_id, collection_id, 7), Err(SafeguardError::ContractPaused));
+            contract.escape_withdraw_nft(project_id, collection_id, 7).unwrap();
+            assert_eq!(psp34_ledger::owner_of(accounts.charlie, 7), Some(accounts.bob));
+            assert_eq!(contract.get_nft_guarantee_value(project_id, collection_id, accounts.bob, 7), 0);
+            assert_eq!(contract.get_project_nft_guarantee_total(project_id, collection_id), 0);
+        }
+
+        #[ink::test]
+        fn test_linear_vote_weighting_by_default() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            let voting_id = contract.start_annual_voting(project_id).unwrap();
+
+            let info = contract.get_voting_info(voting_id).unwrap();
+            assert_eq!(info.weighting, VoteWeighting::Linear);
+            assert_eq!(info.total_eligible_weight, 10_100);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.vote_on_proposal(project_id, false).unwrap();
+            assert_eq!(contract.vote_on_proposal(project_id, false), Err(SafeguardError::AlreadyVoted));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.vote_on_proposal(project_id, true).unwrap();
+
+            let ballot = contract.get_ballot(voting_id, accounts.bob).unwrap();
+            assert_eq!(ballot.raw_weight, 10_000);
+            assert_eq!(ballot.effective_weight, 10_000);
+
+            // Sem garantias => sem direito a voto
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.vote_on_proposal(project_id, true), Err(SafeguardError::NotEligibleToVote));
+
+            let end = contract.get_voting_info(voting_id).unwrap().end_timestamp;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(end + 1);
+            assert_eq!(contract.finalize_voting(project_id), Ok(VoteResult::Rejected));
+        }
+
+        #[ink::test]
+        fn test_quadratic_vote_weighting() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            contract.set_project_vote_weighting(project_id, VoteType::Annual, VoteWeighting::Quadratic).unwrap();
+            let voting_id = contract.start_annual_voting(project_id).unwrap();
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.vote_on_proposal(project_id, true).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.vote_on_proposal(project_id, false).unwrap();
+
+            let bob = contract.get_ballot(voting_id, accounts.bob).unwrap();
+            assert_eq!((bob.raw_weight, bob.effective_weight), (10_000, 100));
+            let charlie = contract.get_ballot(voting_id, accounts.charlie).unwrap();
+            assert_eq!((charlie.raw_weight, charlie.effective_weight), (100, 10));
+
+            let info = contract.get_voting_info(voting_id).unwrap();
+            assert_eq!((info.yes_weight, info.no_weight), (100, 10));
+        }
+
+        #[ink::test]
+        fn test_capped_vote_weighting() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+
+            // Padrão por tipo de votação, com limite de 10% do total elegível
+            let capped = VoteWeighting::CappedLinear { max_share_bps: 1_000 };
+            contract.set_default_vote_weighting(VoteType::Annual, capped).unwrap();
+            assert_eq!(contract.get_vote_weighting(project_id, VoteType::Annual), capped);
+
+            let voting_id = contract.start_annual_voting(project_id).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.vote_on_proposal(project_id, true).unwrap();
+
+            let bob = contract.get_ballot(voting_id, accounts.bob).unwrap();
+            assert_eq!(bob.raw_weight, 10_000);
+            assert_eq!(bob.effective_weight, 1_010);
+        }
+
+        #[ink::test]
+        fn test_vote_weighting_validation_and_access() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+
+            let invalid = VoteWeighting::CappedLinear { max_share_bps: 10_001 };
+            assert_eq!(contract.set_default_vote_weighting(VoteType::Annual, invalid), Err(SafeguardError::InvalidInput));
+            assert_eq!(
+                contract.set_project_vote_weighting(999, VoteType::Annual, VoteWeighting::Quadratic),
+                Err(SafeguardError::ProjectNotFound)
+            );
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.set_project_vote_weighting(project_id, VoteType::Annual, VoteWeighting::Linear),
+                Err(SafeguardError::NotOwner)
+            );
+        }
+
+        #[ink::test]
+        fn test_vote_weighting_applies_to_every_vote_type() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            contract.set_default_vote_weighting(VoteType::Liquidation, VoteWeighting::Quadratic).unwrap();
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.start_voting(project_id, VoteType::Liquidation), Err(SafeguardError::NotOwner));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let voting_id = contract.start_voting(project_id, VoteType::Liquidation).unwrap();
+            let info = contract.get_voting_info(voting_id).unwrap();
+            assert_eq!(info.vote_type, VoteType::Liquidation);
+            assert_eq!(info.weighting, VoteWeighting::Quadratic);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.vote_on_proposal(project_id, true).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.vote_on_proposal(project_id, false).unwrap();
+            assert_eq!(contract.get_ballot(voting_id, accounts.bob).unwrap().effective_weight, 100);
+
+            // Liquidação aprovada => claims abertos
+            let end = contract.get_voting_info(voting_id).unwrap().end_timestamp;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(end.saturating_add(1));
+            assert_eq!(contract.finalize_voting(project_id), Ok(VoteResult::Approved));
+            let vault = contract.get_project_vault(project_id).unwrap();
+            assert_eq!(vault.status, ProjectStatus::Rejected);
+            assert!(vault.claims_deadline.is_some());
+        }
+
+        #[ink::test]
+        fn test_finalize_outcome_depends_on_vote_type() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            // `bob_vote` None = ninguém vota
+            let run_vote = |contract: &mut Safeguard, vote_type: VoteType, bob_vote: Option<bool>| {
+                set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+                let voting_id = contract.start_voting(project_id, vote_type).unwrap();
+                if let Some(support) = bob_vote {
+                    set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+                    contract.vote_on_proposal(project_id, support).unwrap();
+                }
+                let end = contract.get_voting_info(voting_id).unwrap().end_timestamp;
+                ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(end.saturating_add(1));
+                set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+                contract.finalize_voting(project_id).unwrap()
+            };
+
+            // Votação ignorada expira sem efeito, inclusive a de emergência
+            assert_eq!(run_vote(&mut contract, VoteType::Emergency, None), VoteResult::Expired);
+            let vault = contract.get_project_vault(project_id).unwrap();
+            assert_eq!(vault.status, ProjectStatus::Active);
+            assert_eq!(vault.claims_deadline, None);
+            assert_eq!(contract.project_liquidation_votings.get(project_id), None);
+
+            // Liquidação rejeitada: o projeto segue, sem claims e sem reiniciar o ciclo anual
+            assert_eq!(run_vote(&mut contract, VoteType::Liquidation, Some(false)), VoteResult::Rejected);
+            let vault = contract.get_project_vault(project_id).unwrap();
+            assert_eq!(vault.status, ProjectStatus::Approved);
+            assert_eq!(vault.claims_deadline, None);
+            assert_eq!(vault.last_annual_vote, 0);
+            assert_eq!(contract.project_liquidation_votings.get(project_id), None);
+
+            // Extensão aprovada: projeto aprovado, ciclo anual intocado
+            assert_eq!(run_vote(&mut contract, VoteType::Extension, Some(true)), VoteResult::Approved);
+            let vault = contract.get_project_vault(project_id).unwrap();
+            assert_eq!(vault.status, ProjectStatus::Approved);
+            assert_eq!(vault.last_annual_vote, 0);
+
+            // Emergência e extensão rejeitadas só recusam o pedido
+            assert_eq!(run_vote(&mut contract, VoteType::Emergency, Some(false)), VoteResult::Rejected);
+            assert_eq!(run_vote(&mut contract, VoteType::Extension, Some(false)), VoteResult::Rejected);
+            let vault = contract.get_project_vault(project_id).unwrap();
+            assert_eq!(vault.status, ProjectStatus::Approved);
+            assert_eq!(vault.claims_deadline, None);
+
+            // Anual expirada não conta como votação anual feita
+            assert_eq!(run_vote(&mut contract, VoteType::Annual, None), VoteResult::Expired);
+            assert_eq!(contract.get_project_vault(project_id).unwrap().last_annual_vote, 0);
+
+            // Anual aprovada reinicia o ciclo; outra anual antes de um ano é recusada
+            assert_eq!(run_vote(&mut contract, VoteType::Annual, Some(true)), VoteResult::Approved);
+            let last_annual_vote = contract.get_project_vault(project_id).unwrap().last_annual_vote;
+            assert!(last_annual_vote > 0);
+            assert_eq!(contract.start_voting(project_id, VoteType::Annual), Err(SafeguardError::TooEarlyForVoting));
+
+            // Anual rejeitada liquida, com claims pela votação que liquidou
+            let one_year_ms = 365 * 24 * 60 * 60 * 1000u64;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(last_annual_vote.saturating_add(one_year_ms));
+            assert_eq!(run_vote(&mut contract, VoteType::Annual, Some(false)), VoteResult::Rejected);
+            let vault = contract.get_project_vault(project_id).unwrap();
+            assert_eq!(vault.status, ProjectStatus::Rejected);
+            assert!(vault.claims_deadline.is_some());
+            assert_eq!(vault.last_annual_vote, last_annual_vote);
+
+            // Projeto liquidado não abre novas votações
+            assert_eq!(contract.start_voting(project_id, VoteType::Extension), Err(SafeguardError::ProjectNotActive));
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        #[ink::test]
+        fn test_nft_only_depositor_can_vote() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
             let collection_id = contract.add_nft_collection(
-                accounts.alice,
-                *b"Valuable NFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                accounts.charlie,
+                *b"VoteNFTs\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
                 *b"VNFT\0\0\0\0",
-                50_000 * 1_000_000, // 50K LUSDT base value
-                NFTValuationMethod::FixedValue(50_000 * 1_000_000),
+                500,
+                NFTValuationMethod::FixedValue(500),
             ).unwrap();
-            
-            // Initially score is 0 (no Lunes guarantee)
-            let initial_score = contract.get_project_score(project_id);
-            assert_eq!(initial_score, 0);
-            
-            // Add Lunes first (required for any score in v1.1)
-            let lunes_amount = 100_000 * 1_000_000_000_000_000_000; // 100K Lunes
-            contract.add_guarantee(project_id, lunes_token_id, lunes_amount).unwrap();
-            let score_with_lunes = contract.get_project_score(project_id);
-            assert!(score_with_lunes > 0);
-            
-            // Deposit NFT (NFTs give up to 2.5 bonus points in Score v1.1)
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            psp34_ledger::mint(accounts.charlie, 1, accounts.django);
             contract.deposit_nft_guarantee(project_id, collection_id, 1).unwrap();
-            let score_with_nft = contract.get_project_score(project_id);
-            assert!(score_with_nft >= score_with_lunes); // Should be same or higher with NFT
-            
-            // Withdraw NFT (should auto-update score)
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let voting_id = contract.start_annual_voting(project_id).unwrap();
+            assert_eq!(contract.get_voting_info(voting_id).unwrap().total_eligible_weight, 10_600);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            contract.vote_on_proposal(project_id, true).unwrap();
+            assert_eq!(contract.get_ballot(voting_id, accounts.django).unwrap().raw_weight, 500);
+
+            // NFT retirado depois do snapshot não altera o peso já registrado
             contract.withdraw_nft_guarantee(project_id, collection_id, 1).unwrap();
-            let score_after_withdrawal = contract.get_project_score(project_id);
-            assert!(score_after_withdrawal > 0); // Still has Lunes
+            assert_eq!(contract.get_ballot(voting_id, accounts.django).unwrap().raw_weight, 500);
         }
-        
+
+        // ==================== VOTER REWARD TESTS ====================
+
         #[ink::test]
-        fn test_score_edge_cases() {
-            let (mut contract, _accounts) = setup();
-            
-            // Test score for non-existent project
-            let non_existent_project = 999;
-            let score = contract.calculate_project_score(non_existent_project);
-            assert_eq!(score, 0);
-            
-            // Test update score for non-existent project
-            let result = contract.update_project_score(non_existent_project);
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), SafeguardError::InvalidInput);
+        fn test_vote_fee_configuration() {
+            let (mut contract, accounts) = setup();
+
+            assert_eq!(contract.get_vote_fee_config(), (0, 0, 0));
+            contract.set_vote_fees(5, 1).unwrap();
+            contract.set_voter_reward_share(2_500).unwrap();
+            assert_eq!(contract.get_vote_fee_config(), (5, 1, 2_500));
+
+            assert_eq!(contract.set_voter_reward_share(10_001), Err(SafeguardError::InvalidInput));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_vote_fees(0, 0), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.set_voter_reward_share(0), Err(SafeguardError::NotOwner));
         }
 
         #[ink::test]
-        fn test_vesting_helper_functions() {
+        fn test_voter_reward_proportional_to_weight() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            let lunes_id = 0;
+            let lunes = accounts.charlie; // Contrato do token listado em setup_weighted_project
+            let core = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            contract.set_lunes_token_id(lunes_id).unwrap();
+            contract.set_treasury_address(accounts.eve).unwrap();
+            contract.set_vote_fees(10_100, 0).unwrap();
+            contract.set_voter_reward_share(5_000).unwrap();
+
+            let voting_id = contract.start_annual_voting(project_id).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.vote_on_proposal(project_id, true).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.vote_on_proposal(project_id, false).unwrap();
+
+            // Metade de cada taxa vai para o pool, o restante para a treasury
+            assert_eq!(contract.get_voter_reward_pool(voting_id, lunes_id), 10_100);
+            assert_eq!(contract.get_voter_reward_tokens(voting_id), (Some(lunes_id), None));
+            assert_eq!(test_ledger::balance_of(lunes, accounts.eve), 10_100);
+            // O core guarda o pool e as garantias depositadas (10_100)
+            assert_eq!(test_ledger::balance_of(lunes, core), 20_200);
+
+            // Trocar o token LUNES depois das taxas não muda o token do pool
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let new_lunes_id = contract.add_supported_token(accounts.django, *b"LUNES2\0\0", 18, 1).unwrap();
+            contract.set_lunes_token_id(new_lunes_id).unwrap();
+
+            // Antes da finalização nada pode ser resgatado
+            assert_eq!(contract.get_unclaimed_voter_reward(voting_id, accounts.bob), (0, 0));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.claim_voter_reward(voting_id), Err(SafeguardError::VotingStillActive));
+
+            let end = contract.get_voting_info(voting_id).unwrap().end_timestamp;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(end.saturating_add(1));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.finalize_voting(project_id).unwrap();
+
+            assert_eq!(contract.get_unclaimed_voter_reward(voting_id, accounts.bob), (10_000, 0));
+            assert_eq!(contract.get_unclaimed_voter_reward(voting_id, accounts.charlie), (100, 0));
+            assert_eq!(contract.get_unclaimed_voter_reward(voting_id, accounts.django), (0, 0));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.claim_voter_reward(voting_id), Err(SafeguardError::NotEligibleToVote));
+
+            // Resgate pago pelo caminho de saída, no token em que o pool foi formado
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.claim_voter_reward(voting_id), Ok((10_000, 0)));
+            assert_eq!(test_ledger::balance_of(lunes, accounts.bob), 10_000);
+            assert_eq!(test_ledger::balance_of(lunes, core), 10_200);
+            assert_eq!(contract.get_unclaimed_voter_reward(voting_id, accounts.bob), (0, 0));
+            assert_eq!(contract.claim_voter_reward(voting_id), Err(SafeguardError::AlreadyClaimed));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.claim_voter_reward(voting_id), Ok((100, 0)));
+            assert_eq!(test_ledger::balance_of(lunes, core), 10_100);
+        }
+
+        #[ink::test]
+        fn test_voter_reward_with_18_decimal_balances() {
             let (mut contract, accounts) = setup();
-            
-            // Register project
+            let unit: Balance = 1_000_000_000_000_000_000;
             let project_id = contract.register_project(
                 b"Test Project".to_vec(),
                 b"ipfs://metadata".to_vec(),
                 accounts.alice,
                 accounts.bob
             ).unwrap();
-            
-            // Test helper functions work correctly
-            assert!(!contract.is_vesting_period_met(project_id));
-            assert!(contract.get_project_creation_timestamp(project_id).is_some());
-            assert!(contract.get_remaining_vesting_time(project_id).is_some());
-            
-            let remaining_time = contract.get_remaining_vesting_time(project_id);
-            assert!(remaining_time.is_some());
+            let lunes_id = contract.add_supported_token(accounts.charlie, *b"LUNES\0\0\0", 18, 1).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.add_guarantee(project_id, lunes_id, 1_000_000 * unit).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.add_guarantee(project_id, lunes_id, 10_000 * unit).unwrap();
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.set_lunes_token_id(lunes_id).unwrap();
+            contract.set_treasury_address(accounts.eve).unwrap();
+            contract.set_vote_fees(1_000 * unit, 0).unwrap();
+            contract.set_voter_reward_share(10_000).unwrap();
+
+            let voting_id = contract.start_annual_voting(project_id).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.vote_on_proposal(project_id, true).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.vote_on_proposal(project_id, false).unwrap();
+            let end = contract.get_voting_info(voting_id).unwrap().end_timestamp;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(end.saturating_add(1));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.finalize_voting(project_id).unwrap();
+
+            // Pool × peso passa de u128; as parcelas continuam proporcionais e cabem no pool
+            let pool = contract.get_voter_reward_pool(voting_id, lunes_id);
+            assert_eq!(pool, 2_000 * unit);
+            let (bob_reward, _) = contract.get_unclaimed_voter_reward(voting_id, accounts.bob);
+            let (charlie_reward, _) = contract.get_unclaimed_voter_reward(voting_id, accounts.charlie);
+            assert_eq!(bob_reward, 1_980_198_019_801_980_198_019);
+            assert_eq!(charlie_reward, 19_801_980_198_019_801_980);
+            assert!(bob_reward.saturating_add(charlie_reward) <= pool);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.claim_voter_reward(voting_id), Ok((bob_reward, 0)));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.claim_voter_reward(voting_id), Ok((charlie_reward, 0)));
         }
-        
+
+        // ==================== SNAPSHOT ELIGIBILITY TESTS ====================
+
         #[ink::test]
-        fn test_comprehensive_error_handling() {
-            let (mut contract, accounts) = setup();
-            
-            // Test operations on non-existent project
-            let non_existent_project = 999;
-            
-            // Should fail gracefully
-            assert_eq!(contract.add_guarantee(non_existent_project, 0, 1000), Err(SafeguardError::InvalidInput));
-            assert_eq!(contract.donate_to_guarantee(non_existent_project, 0, 1000), Err(SafeguardError::InvalidInput));
-            assert_eq!(contract.withdraw_guarantee(non_existent_project, 0, 1000), Err(SafeguardError::InvalidInput));
-            assert_eq!(contract.request_guarantee_release(non_existent_project), Err(SafeguardError::InvalidInput));
-            assert_eq!(contract.update_project_score(non_existent_project), Err(SafeguardError::InvalidInput));
-            
-            // Test with invalid amounts
+        fn test_voting_snapshot_is_last_sealed_block() {
+            let (mut contract, _accounts, project_id) = setup_weighted_project();
+            for _ in 0..5 {
+                ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            }
+
+            let voting_id = contract.start_annual_voting(project_id).unwrap();
+            let info = contract.get_voting_info(voting_id).unwrap();
+            assert_eq!(info.snapshot_block, 4);
+        }
+
+        #[ink::test]
+        fn test_set_project_token_checkpoints() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            assert!(!contract.uses_token_checkpoints(project_id));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_project_token_checkpoints(project_id, true), Err(SafeguardError::NotOwner));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.set_project_token_checkpoints(999, true), Err(SafeguardError::ProjectNotFound));
+            contract.set_project_token_checkpoints(project_id, true).unwrap();
+            assert!(contract.uses_token_checkpoints(project_id));
+            contract.set_project_token_checkpoints(project_id, false).unwrap();
+
+            // A fonte de peso não muda durante uma votação
+            contract.start_annual_voting(project_id).unwrap();
+            assert_eq!(contract.set_project_token_checkpoints(project_id, true), Err(SafeguardError::VotingInProgress));
+        }
+
+        #[ink::test]
+        fn test_guarantee_checkpoints_by_block() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.withdraw_guarantee(project_id, 0, 4_000).unwrap();
+            contract.add_guarantee(project_id, 0, 1_000).unwrap();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            contract.withdraw_guarantee(project_id, 0, 7_000).unwrap();
+
+            // Bloco 0: depósitos iniciais
+            assert_eq!(contract.get_user_guarantee_at(project_id, 0, accounts.bob, 0), 10_000);
+            assert_eq!(contract.get_project_total_guarantee_at(project_id, 0, 0), 10_100);
+            // Bloco 1: apenas o saldo final do bloco é registrado
+            assert_eq!(contract.get_user_guarantee_at(project_id, 0, accounts.bob, 1), 7_000);
+            assert_eq!(contract.get_project_total_guarantee_at(project_id, 0, 1), 7_100);
+            // Bloco 2 em diante: saque total
+            assert_eq!(contract.get_user_guarantee_at(project_id, 0, accounts.bob, 2), 0);
+            assert_eq!(contract.get_user_guarantee_at(project_id, 0, accounts.bob, 100), 0);
+            assert_eq!(contract.get_project_total_guarantee_at(project_id, 0, 100), 100);
+            // Conta sem histórico
+            assert_eq!(contract.get_user_guarantee_at(project_id, 0, accounts.django, 1), 0);
+        }
+
+        #[ink::test]
+        fn test_vote_weight_normalizes_token_decimals() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            // 5 LUSDT (6 casas) pesam o mesmo que 5 unidades de um token de 18 casas
+            let lusdt_id = contract.add_supported_token(accounts.django, *b"LUSDT\0\0\0", 6, 1).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            contract.add_guarantee(project_id, lusdt_id, 5_000_000).unwrap();
+            let five_units: Balance = 5 * 1_000_000_000_000_000_000;
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let voting_id = contract.start_annual_voting(project_id).unwrap();
+            assert_eq!(contract.get_voting_info(voting_id).unwrap().total_eligible_weight, five_units.saturating_add(10_100));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            contract.vote_on_proposal(project_id, true).unwrap();
+            assert_eq!(contract.get_ballot(voting_id, accounts.django).unwrap().raw_weight, five_units);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.vote_on_proposal(project_id, false).unwrap();
+            assert_eq!(contract.get_ballot(voting_id, accounts.bob).unwrap().raw_weight, 10_000);
+        }
+
+        #[ink::test]
+        fn test_deposit_after_snapshot_has_no_vote_weight() {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            let voting_id = contract.start_annual_voting(project_id).unwrap();
+
+            // Depósito feito após o snapshot não concede peso
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            contract.add_guarantee(project_id, 0, 50_000).unwrap();
+            assert_eq!(contract.vote_on_proposal(project_id, true), Err(SafeguardError::NotEligibleToVote));
+
+            // Durante a votação o colateral fica travado; o peso segue o snapshot
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.withdraw_guarantee(project_id, 0, 10_000), Err(SafeguardError::ProjectNotActive));
+            contract.vote_on_proposal(project_id, false).unwrap();
+            assert_eq!(contract.get_ballot(voting_id, accounts.bob).unwrap().raw_weight, 10_000);
+
+            let info = contract.get_voting_info(voting_id).unwrap();
+            assert_eq!(info.total_eligible_weight, 10_100);
+            assert_eq!(info.total_eligible, 0);
+        }
+
+        #[ink::test]
+        fn test_total_eligible_weight_with_18_decimal_balances() {
+            let (mut contract, accounts) = setup();
+            let unit: Balance = 1_000_000_000_000_000_000;
             let project_id = contract.register_project(
                 b"Test Project".to_vec(),
                 b"ipfs://metadata".to_vec(),
                 accounts.alice,
                 accounts.bob
             ).unwrap();
-            let token_id = contract.add_supported_token(
+            let lunes_id = contract.add_supported_token(accounts.charlie, *b"LUNES\0\0\0", 18, 1).unwrap();
+            let lusdt_id = contract.add_supported_token(accounts.django, *b"LUSDT\0\0\0", 6, 1).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.add_guarantee(project_id, lunes_id, 250_000 * unit).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.add_guarantee(project_id, lusdt_id, 40_000 * 1_000_000).unwrap();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+
+            // 290 mil tokens de 18 casas passam de u64; o peso vai inteiro em total_eligible_weight
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let voting_id = contract.start_annual_voting(project_id).unwrap();
+            let info = contract.get_voting_info(voting_id).unwrap();
+            assert_eq!(info.total_eligible_weight, 290_000 * unit);
+            assert!(info.total_eligible_weight > Balance::from(u64::MAX));
+            assert_eq!(info.total_eligible, 0);
+        }
+
+        fn setup_protocol_dao() -> (Safeguard, ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment>) {
+            let (mut contract, accounts, project_id) = setup_weighted_project();
+            contract.set_lunes_token_id(0).unwrap();
+
+            // bob: 10_000 + 2_000 em outro projeto; charlie: 100
+            let second_project = contract.register_project(
+                b"Second Project".to_vec(),
+                b"ipfs://metadata2".to_vec(),
                 accounts.alice,
-                *b"TEST\0\0\0\0",
-                18,
-                1000,
+                accounts.bob
             ).unwrap();
-            
-            // Zero amount should fail
-            assert_eq!(contract.add_guarantee(project_id, token_id, 0), Err(SafeguardError::InvalidAmount));
-            assert_eq!(contract.donate_to_guarantee(project_id, token_id, 0), Err(SafeguardError::InvalidAmount));
-            
-            // Amount below minimum should fail
-            assert_eq!(contract.add_guarantee(project_id, token_id, 500), Err(SafeguardError::InvalidAmount));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.add_guarantee(second_project, 0, 2_000).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_ne!(project_id, second_project);
+
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            (contract, accounts)
         }
-        
+
         #[ink::test]
-        fn test_score_consistency_across_operations() {
-            let (mut contract, accounts) = setup();
-            
-            // Setup project with guarantees
-            let project_id = contract.register_project(
-                b"Test Project".to_vec(),
-                b"ipfs://metadata".to_vec(),
-                accounts.alice,
-                accounts.bob
+        fn test_locked_lunes_weight() {
+            let (mut contract, accounts) = setup_protocol_dao();
+            assert_eq!(contract.get_locked_lunes(accounts.bob), 12_000);
+            assert_eq!(contract.get_locked_lunes_at(accounts.bob, 0), 12_000);
+            assert_eq!(contract.get_locked_lunes_at(accounts.charlie, 0), 100);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.withdraw_guarantee(0, 0, 2_000).unwrap();
+            assert_eq!(contract.get_locked_lunes(accounts.bob), 10_000);
+            assert_eq!(contract.get_locked_lunes_at(accounts.bob, 0), 12_000);
+            assert_eq!(contract.get_locked_lunes_at(accounts.bob, 1), 10_000);
+        }
+
+        #[ink::test]
+        fn test_protocol_proposal_executes_deposit_fees() {
+            let (mut contract, accounts) = setup_protocol_dao();
+            contract.enable_protocol_governance().unwrap();
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let proposal_id = contract.propose_protocol_change(
+                ProtocolAction::SetDepositFees { lunes_fee: 5, lusdt_fee: 1 },
+                b"ipfs://proposal".to_vec(),
             ).unwrap();
-            
-            // Add Lunes token (required for Score v1.1)
-            let lunes_token_id = contract.add_supported_token(
-                accounts.alice,
-                *b"LUNES\0\0\0",
-                18,
-                1000,
+            contract.vote_protocol_proposal(proposal_id, true).unwrap();
+            assert_eq!(contract.vote_protocol_proposal(proposal_id, true), Err(SafeguardError::AlreadyVoted));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.vote_protocol_proposal(proposal_id, false).unwrap();
+
+            // Sem LUNES travados no snapshot
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.vote_protocol_proposal(proposal_id, true), Err(SafeguardError::NotEligibleToVote));
+
+            let proposal = contract.get_protocol_proposal(proposal_id).unwrap();
+            assert_eq!(proposal.yes_weight, 12_000);
+            assert_eq!(proposal.no_weight, 100);
+            assert_eq!(proposal.total_eligible_weight, 12_100);
+            assert_eq!(contract.execute_protocol_proposal(proposal_id), Err(SafeguardError::VotingStillActive));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(proposal.end_timestamp + 1);
+            assert_eq!(contract.execute_protocol_proposal(proposal_id), Ok(ProtocolProposalStatus::Executed));
+            assert_eq!(contract.get_deposit_fees(), (5, 1));
+            assert_eq!(contract.execute_protocol_proposal(proposal_id), Err(SafeguardError::VotingNotActive));
+        }
+
+        /// Aprova pela DAO um upgrade para `code_hash` e devolve a operação enfileirada
+        #[cfg(feature = "timelock")]
+        fn approve_upgrade(
+            contract: &mut Safeguard,
+            accounts: &ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment>,
+            code_hash: Hash,
+        ) -> OperationId {
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let proposal_id = contract.propose_protocol_change(
+                ProtocolAction::UpgradeCode(code_hash),
+                b"ipfs://upgrade".to_vec(),
             ).unwrap();
-            contract.set_lunes_token_id(lunes_token_id).unwrap();
-            
-            // Test score consistency (initially 0, no Lunes guarantee)
-            let initial_calculated = contract.calculate_project_score(project_id);
-            let initial_cached = contract.get_project_score(project_id);
-            assert_eq!(initial_calculated, initial_cached);
-            assert_eq!(initial_cached, 0);
-            
-            // Add Lunes guarantee and verify auto-update
-            let lunes_amount = 100_000 * 1_000_000_000_000_000_000; // 100K Lunes
-            contract.add_guarantee(project_id, lunes_token_id, lunes_amount).unwrap();
-            let score_after_add = contract.get_project_score(project_id);
-            let calculated_after_add = contract.calculate_project_score(project_id);
-            assert_eq!(score_after_add, calculated_after_add);
-            assert!(score_after_add > initial_cached);
-            
-            // Manual update should return same score
-            let manual_update = contract.update_project_score(project_id).unwrap();
-            assert_eq!(manual_update, score_after_add);
-            
-            // Withdraw and verify consistency
-            contract.withdraw_guarantee(project_id, lunes_token_id, lunes_amount / 2).unwrap();
-            let score_after_withdraw = contract.get_project_score(project_id);
-            let calculated_after_withdraw = contract.calculate_project_score(project_id);
-            assert_eq!(score_after_withdraw, calculated_after_withdraw);
-            assert!(score_after_withdraw <= score_after_add); // Allow equal scores due to rounding
+            contract.vote_protocol_proposal(proposal_id, true).unwrap();
+            let end = contract.get_protocol_proposal(proposal_id).unwrap().end_timestamp;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(end + 1);
+            assert_eq!(contract.execute_protocol_proposal(proposal_id), Ok(ProtocolProposalStatus::Executed));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.operation_index.get(contract.get_operation_count() - 1).unwrap()
         }
 
-        // ==================== PAUSABILITY TESTS ====================
+        #[cfg(feature = "timelock")]
+        #[ink::test]
+        fn test_protocol_upgrade_is_timelocked_and_vetoable() {
+            let (mut contract, accounts) = setup_protocol_dao();
+            contract.set_guardian(accounts.eve, true).unwrap();
+            contract.enable_protocol_governance().unwrap();
 
+            // A proposta aprovada só agenda: o atraso de 96h do UpgradeCode vale para a DAO
+            let operation_id = approve_upgrade(&mut contract, &accounts, Hash::from([7u8; 32]));
+            let operation = contract.get_operation(operation_id).unwrap();
+            let now = ink::env::block_timestamp::<ink::env::DefaultEnvironment>();
+            assert_eq!(operation.function_type, TimelockFunction::UpgradeCode);
+            assert_eq!(operation.scheduled_by, ink::env::test::callee::<ink::env::DefaultEnvironment>());
+            assert_eq!(operation.execute_after, now + 96 * 60 * 60 * 1000);
+            assert_eq!(contract.execute_operation(operation_id), Err(SafeguardError::OperationNotReady));
+
+            // Guardiões vetam o upgrade da DAO como qualquer outra operação
+            set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            contract.veto_operation(operation_id, b"bad code".to_vec()).unwrap();
+            assert_eq!(contract.get_operation_status(operation_id), Some(OperationStatus::Cancelled));
+
+            // O mesmo código pode ser aprovado de novo: cada fila usa um salt próprio
+            let again = approve_upgrade(&mut contract, &accounts, Hash::from([7u8; 32]));
+            assert_ne!(again, operation_id);
+            assert_eq!(contract.get_operation_status(again), Some(OperationStatus::Pending));
+        }
+
+        /// O ambiente off-chain não troca código: chegar ao `set_code_hash` prova que a
+        /// operação da DAO passa pela checagem de governança na execução
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_pause_unpause() {
-            let (mut contract, _accounts) = setup();
-            
-            // Initially not paused
+        #[should_panic(expected = "set_code_hash")]
+        fn test_protocol_upgrade_executes_after_delay() {
+            let (mut contract, accounts) = setup_protocol_dao();
+            contract.enable_protocol_governance().unwrap();
+            let operation_id = approve_upgrade(&mut contract, &accounts, Hash::from([7u8; 32]));
+            let ready = contract.get_operation(operation_id).unwrap().execute_after;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(ready);
+            let _ = contract.execute_operation(operation_id);
+        }
+
+        #[ink::test]
+        fn test_protocol_proposal_defeated_without_majority() {
+            let (mut contract, accounts) = setup_protocol_dao();
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            let proposal_id = contract.propose_protocol_change(
+                ProtocolAction::SetTreasuryAddress(accounts.eve),
+                Vec::new(),
+            ).unwrap();
+            contract.vote_protocol_proposal(proposal_id, true).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.vote_protocol_proposal(proposal_id, false).unwrap();
+
+            let end = contract.get_protocol_proposal(proposal_id).unwrap().end_timestamp;
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(end + 1);
+            assert_eq!(contract.execute_protocol_proposal(proposal_id), Ok(ProtocolProposalStatus::Defeated));
+            assert_eq!(contract.treasury_address, accounts.alice);
+        }
+
+        #[cfg(not(feature = "nft-collateral"))]
+        #[ink::test]
+        fn test_nft_collateral_disabled() {
+            let (mut contract, accounts) = setup_protocol_dao();
+
+            // Builds sem NFTs recusam a listagem de coleções pela DAO
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let action = ProtocolAction::AddNftCollection {
+                contract_address: accounts.django,
+                name: [0u8; 32],
+                symbol: *b"NFT\0\0\0\0\0",
+                base_value: 1,
+                valuation_method: NFTValuationMethod::TraitBased,
+            };
+            assert_eq!(contract.propose_protocol_change(action, Vec::new()), Err(SafeguardError::FeatureDisabled));
+        }
+
+        #[ink::test]
+        fn test_protocol_proposal_validation() {
+            let (mut contract, accounts) = setup_protocol_dao();
+
+            // Parâmetros fora da faixa são rejeitados na criação
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let invalid = ScoreParameters { gamma: Some(0), ..Default::default() };
+            assert_eq!(
+                contract.propose_protocol_change(ProtocolAction::SetScoreParameters(invalid), Vec::new()),
+                Err(SafeguardError::InvalidInput)
+            );
+
+            // Sem LUNES travados não é possível propor
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(
+                contract.propose_protocol_change(ProtocolAction::SetDepositFees { lunes_fee: 0, lusdt_fee: 0 }, Vec::new()),
+                Err(SafeguardError::NotEligibleToVote)
+            );
+        }
+
+        #[cfg(feature = "nft-collateral")]
+        #[ink::test]
+        fn test_protocol_governance_restricts_owner_setters() {
+            let (mut contract, accounts) = setup_protocol_dao();
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.enable_protocol_governance(), Err(SafeguardError::NotOwner));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.enable_protocol_governance().unwrap();
+            assert_eq!(contract.enable_protocol_governance(), Err(SafeguardError::InvalidInput));
+            assert!(contract.get_protocol_governance_config().0);
+
+            assert_eq!(contract.set_deposit_fees(1, 1), Err(SafeguardError::GovernanceRequired));
+            assert_eq!(contract.set_treasury_address(accounts.bob), Err(SafeguardError::GovernanceRequired));
+            assert_eq!(
+                contract.set_score_parameters(None, None, None, None, None, None, None, None),
+                Err(SafeguardError::GovernanceRequired)
+            );
+            assert_eq!(
+                contract.add_supported_token(accounts.django, *b"NEW\0\0\0\0\0", 18, 1),
+                Err(SafeguardError::GovernanceRequired)
+            );
+            assert_eq!(
+                contract.add_nft_collection(accounts.django, [0u8; 32], *b"NFT\0\0\0\0\0", 1, NFTValuationMethod::TraitBased),
+                Err(SafeguardError::GovernanceRequired)
+            );
+
+            // Funções de emergência continuam com o owner
+            contract.pause(0).unwrap();
+            contract.unpause().unwrap();
+        }
+
+        #[ink::test]
+        fn test_owner_reports_council() {
+            let (contract, accounts) = setup();
+            assert_eq!(contract.owner(), CouncilConfig { signers: vec![accounts.alice], threshold: 1 });
+        }
+
+        fn setup_council() -> (Safeguard, ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment>) {
+            let (mut contract, accounts) = setup();
+            // 1-de-1: as propostas executam imediatamente
+            contract.propose_council_action(CouncilAction::AddSigner(accounts.bob)).unwrap();
+            contract.propose_council_action(CouncilAction::AddSigner(accounts.charlie)).unwrap();
+            contract.propose_council_action(CouncilAction::SetThreshold(2)).unwrap();
+            (contract, accounts)
+        }
+
+        #[ink::test]
+        fn test_council_threshold_gates_privileged_actions() {
+            let (mut contract, accounts) = setup_council();
+            assert_eq!(
+                contract.owner(),
+                CouncilConfig { signers: vec![accounts.alice, accounts.bob, accounts.charlie], threshold: 2 }
+            );
+
+            // Direct calls now need council approval
+            assert_eq!(contract.pause(0), Err(SafeguardError::CouncilApprovalRequired));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.pause(0), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.propose_council_action(CouncilAction::Pause(0)), Err(SafeguardError::NotOwner));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let proposal_id = contract.propose_council_action(CouncilAction::Pause(0)).unwrap();
+            assert_eq!(contract.get_council_approvals(proposal_id), 1);
             assert!(!contract.is_contract_paused());
-            assert_eq!(contract.get_paused_at(), 0);
-            
-            // Pause the contract
-            assert!(contract.pause().is_ok());
+            assert_eq!(contract.approve_council_proposal(proposal_id), Err(SafeguardError::AlreadyVoted));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.approve_council_proposal(proposal_id).unwrap();
             assert!(contract.is_contract_paused());
-            // In test environment, timestamp may be 0, so we just check it was set
-            // (paused_at is set to block_timestamp which is 0 in tests)
-            
-            // Cannot pause again
-            assert_eq!(contract.pause(), Err(SafeguardError::ContractPaused));
-            
-            // Unpause the contract
-            assert!(contract.unpause().is_ok());
-            assert!(!contract.is_contract_paused());
-            
-            // Cannot unpause again
-            assert_eq!(contract.unpause(), Err(SafeguardError::ContractNotPaused));
+            assert!(contract.get_council_proposal(proposal_id).unwrap().is_executed);
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.approve_council_proposal(proposal_id), Err(SafeguardError::ProposalAlreadyExecuted));
+            assert_eq!(contract.approve_council_proposal(99), Err(SafeguardError::ProposalNotFound));
         }
 
         #[ink::test]
-        fn test_pause_only_owner() {
-            let (mut contract, accounts) = setup();
-            
-            // Switch to non-owner
+        fn test_council_membership_changes_need_approval() {
+            let (mut contract, accounts) = setup_council();
+
+            // Invalid changes fail when executed
+            let proposal_id = contract.propose_council_action(CouncilAction::SetThreshold(4)).unwrap();
             set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
-            
-            // Non-owner cannot pause
-            assert_eq!(contract.pause(), Err(SafeguardError::NotOwner));
-            
-            // Switch back to owner
+            assert_eq!(contract.approve_council_proposal(proposal_id), Err(SafeguardError::InvalidInput));
+
             set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
-            
-            // Owner can pause
-            assert!(contract.pause().is_ok());
-            
-            // Switch to non-owner
+            let proposal_id = contract.propose_council_action(CouncilAction::RemoveSigner(accounts.charlie)).unwrap();
             set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
-            
-            // Non-owner cannot unpause
-            assert_eq!(contract.unpause(), Err(SafeguardError::NotOwner));
-        }
+            contract.approve_council_proposal(proposal_id).unwrap();
+            assert_eq!(contract.owner().signers, vec![accounts.alice, accounts.bob]);
 
-        // ==================== TIMELOCK TESTS ====================
+            // Removed signers lose their approvals and rights
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.propose_council_action(CouncilAction::Unpause), Err(SafeguardError::NotOwner));
+
+            // Threshold cannot become unreachable
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let proposal_id = contract.propose_council_action(CouncilAction::RemoveSigner(accounts.bob)).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.approve_council_proposal(proposal_id), Err(SafeguardError::InvalidInput));
+        }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_schedule_operation() {
+        fn test_contract_ownership_transfer_needs_council() {
             let (mut contract, accounts) = setup();
+            contract.grant_role(Role::Admin, accounts.django).unwrap();
+            contract.grant_role(Role::Keeper, accounts.django).unwrap();
+            contract.propose_council_action(CouncilAction::AddSigner(accounts.bob)).unwrap();
+            contract.propose_council_action(CouncilAction::SetThreshold(2)).unwrap();
+            
+            // Admin and Keeper roles cannot take over the council through op 0
+            let data = scale::Encode::encode(&accounts.eve);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.schedule_operation(0, data.clone(), Hash::default()), Err(SafeguardError::NotOwner));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.schedule_operation(0, data.clone(), Hash::default()), Err(SafeguardError::CouncilApprovalRequired));
             
-            // Schedule an operation
-            let result = contract.schedule_operation(0, Vec::new()); // TransferContractOwnership
-            assert!(result.is_ok());
-            let operation_id = result.unwrap();
-            assert_eq!(operation_id, 0);
+            let proposal_id = contract.propose_council_action(CouncilAction::ScheduleOperation {
+                function_type: 0,
+                data: data.clone(),
+                salt: Hash::default(),
+            }).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.approve_council_proposal(proposal_id).unwrap();
+            let operation_id = contract.hash_operation(0, data, Hash::default());
+            let op = contract.get_operation(operation_id).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(op.execute_after);
             
-            // Verify operation was stored
-            let operation = contract.get_operation(operation_id);
-            assert!(operation.is_some());
-            let op = operation.unwrap();
-            assert_eq!(op.function_type, TimelockFunction::TransferContractOwnership);
-            assert!(!op.is_executed);
-            assert!(!op.is_cancelled);
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.execute_operation(operation_id), Err(SafeguardError::NotOwner));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let proposal_id = contract.propose_council_action(CouncilAction::ExecuteOperation(operation_id)).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.approve_council_proposal(proposal_id).unwrap();
+            
+            // Accepting adds a signer; the rest of the council and the threshold stay
+            set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            contract.accept_contract_ownership().unwrap();
+            assert_eq!(
+                contract.owner(),
+                CouncilConfig { signers: vec![accounts.alice, accounts.bob, accounts.eve], threshold: 2 }
+            );
         }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_schedule_operation_only_owner() {
+        fn test_high_impact_operations_need_council() {
             let (mut contract, accounts) = setup();
-            
-            // Switch to non-owner
+            contract.grant_role(Role::Admin, accounts.django).unwrap();
+            contract.propose_council_action(CouncilAction::AddSigner(accounts.bob)).unwrap();
+            contract.propose_council_action(CouncilAction::SetThreshold(2)).unwrap();
+            let withdraw = scale::Encode::encode(&(0u64, 100u128, accounts.eve));
+            let upgrade = scale::Encode::encode(&Hash::from([7u8; 32]));
+
+            // Admin agenda parâmetros de rotina, mas não custódia nem código
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            contract.schedule_operation(1, scale::Encode::encode(&accounts.eve), Hash::default()).unwrap();
+            assert_eq!(contract.schedule_operation(4, withdraw.clone(), Hash::default()), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.schedule_operation(10, upgrade.clone(), Hash::default()), Err(SafeguardError::NotOwner));
+
+            // Um signatário sozinho também não basta
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                contract.schedule_operation(10, upgrade.clone(), Hash::default()),
+                Err(SafeguardError::CouncilApprovalRequired)
+            );
+
+            let proposal_id = contract.propose_council_action(CouncilAction::ScheduleOperation {
+                function_type: 10,
+                data: upgrade.clone(),
+                salt: Hash::default(),
+            }).unwrap();
             set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
-            
-            // Non-owner cannot schedule
-            assert_eq!(contract.schedule_operation(0, Vec::new()), Err(SafeguardError::NotOwner));
+            contract.approve_council_proposal(proposal_id).unwrap();
+            assert!(contract.get_operation(contract.hash_operation(10, upgrade, Hash::default())).is_some());
         }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_cancel_operation() {
-            let (mut contract, accounts) = setup();
-            
-            // Schedule an operation
-            let operation_id = contract.schedule_operation(0, Vec::new()).unwrap();
-            
-            // Cancel the operation
-            assert!(contract.cancel_operation(operation_id).is_ok());
-            
-            // Verify operation was cancelled
+        fn test_council_drives_timelock() {
+            let (mut contract, accounts) = setup_council();
+            let data = scale::Encode::encode(&accounts.django);
+
+            let proposal_id = contract.propose_council_action(CouncilAction::ScheduleOperation {
+                function_type: 1,
+                data: data.clone(),
+                salt: Hash::default(),
+            }).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.approve_council_proposal(proposal_id).unwrap();
+
+            let operation_id = contract.hash_operation(1, data, Hash::default());
             let operation = contract.get_operation(operation_id).unwrap();
-            assert!(operation.is_cancelled);
-            
-            // Cannot cancel again
-            assert_eq!(contract.cancel_operation(operation_id), Err(SafeguardError::OperationNotFound));
+            assert_eq!(operation.scheduled_by, accounts.charlie);
+            assert_eq!(contract.execute_operation(operation_id), Err(SafeguardError::CouncilApprovalRequired));
         }
 
         #[ink::test]
-        fn test_execute_operation_not_ready() {
+        fn test_grant_revoke_renounce_roles() {
             let (mut contract, accounts) = setup();
             
-            // Schedule an operation
-            let operation_id = contract.schedule_operation(0, Vec::new()).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.pause(0), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.grant_role(Role::Pauser, accounts.bob), Err(SafeguardError::NotOwner));
             
-            // Try to execute immediately (should fail - not ready)
-            assert_eq!(contract.execute_operation(operation_id), Err(SafeguardError::OperationNotReady));
-        }
-
-        #[ink::test]
-        fn test_timelock_delay() {
-            let (mut contract, accounts) = setup();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.grant_role(Role::Pauser, accounts.bob).unwrap();
+            assert!(contract.has_role(Role::Pauser, accounts.bob));
             
-            // Get default delay
-            let default_delay = contract.get_timelock_delay();
-            assert_eq!(default_delay, 48 * 60 * 60 * 1000); // 48 hours
+            // Pauser can pause but holds no other privilege
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            contract.pause(0).unwrap();
+            contract.unpause().unwrap();
+            assert_eq!(contract.update_lunes_supply(1), Err(SafeguardError::NotOwner));
+            assert_eq!(contract.set_vote_fees(1, 1), Err(SafeguardError::NotOwner));
             
-            // Set new delay (2 hours)
-            let new_delay = 2 * 60 * 60 * 1000;
-            assert!(contract.set_timelock_delay(new_delay).is_ok());
-            assert_eq!(contract.get_timelock_delay(), new_delay);
+            contract.renounce_role(Role::Pauser).unwrap();
+            assert!(!contract.has_role(Role::Pauser, accounts.bob));
+            assert_eq!(contract.renounce_role(Role::Pauser), Err(SafeguardError::MissingRole));
             
-            // Invalid delay (too short)
-            assert_eq!(contract.set_timelock_delay(30 * 60 * 1000), Err(SafeguardError::InvalidInput));
+            // Admins manage roles
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.grant_role(Role::Admin, accounts.charlie).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.grant_role(Role::ScoreOracle, accounts.django).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            contract.update_lunes_supply(150_000_000).unwrap();
+            assert_eq!(contract.get_score_parameters().8, 150_000_000);
             
-            // Invalid delay (too long)
-            assert_eq!(contract.set_timelock_delay(8 * 24 * 60 * 60 * 1000), Err(SafeguardError::InvalidInput));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.revoke_role(Role::ScoreOracle, accounts.django).unwrap();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.update_lunes_supply(1), Err(SafeguardError::NotOwner));
         }
 
+        #[cfg(feature = "timelock")]
         #[ink::test]
-        fn test_timelock_function_types() {
+        fn test_timelock_roles() {
             let (mut contract, accounts) = setup();
+            contract.grant_role(Role::TokenLister, accounts.bob).unwrap();
+            contract.grant_role(Role::Keeper, accounts.charlie).unwrap();
             
-            // Test all valid function types
-            for func_type in 0..=6 {
-                let result = contract.schedule_operation(func_type, Vec::new());
-                assert!(result.is_ok(), "Failed for function type {}", func_type);
-            }
+            // Token listers only queue listings
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let listing = scale::Encode::encode(&(accounts.django, *b"NEW\0\0\0\0\0", 18u8, 1u128));
+            let operation_id = contract.schedule_operation(5, listing, Hash::default()).unwrap();
+            assert_eq!(
+                contract.schedule_operation(2, scale::Encode::encode(&(1u128, 1u128)), Hash::default()),
+                Err(SafeguardError::NotOwner)
+            );
+            assert_eq!(contract.cancel_operation(operation_id), Err(SafeguardError::NotOwner));
             
-            // Invalid function type
-            assert_eq!(contract.schedule_operation(7, Vec::new()), Err(SafeguardError::InvalidInput));
-            assert_eq!(contract.schedule_operation(255, Vec::new()), Err(SafeguardError::InvalidInput));
+            // Keepers execute released operations
+            let op = contract.get_operation(operation_id).unwrap();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(op.execute_after);
+            assert_eq!(contract.execute_operation(operation_id), Err(SafeguardError::NotOwner));
+            set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            contract.execute_operation(operation_id).unwrap();
+            assert_eq!(contract.get_token_info(0).unwrap().contract_address, accounts.django);
         }
 
         #[ink::test]
-        fn test_operation_not_found() {
-            let (mut contract, accounts) = setup();
-            
-            // Try to cancel non-existent operation
-            assert_eq!(contract.cancel_operation(999), Err(SafeguardError::OperationNotFound));
-            
-            // Try to execute non-existent operation
-            assert_eq!(contract.execute_operation(999), Err(SafeguardError::OperationNotFound));
-            
-            // Get non-existent operation
-            assert!(contract.get_operation(999).is_none());
+        fn test_isqrt() {
+            assert_eq!(safe_math::isqrt(0), 0);
+            assert_eq!(safe_math::isqrt(1), 1);
+            assert_eq!(safe_math::isqrt(3), 1);
+            assert_eq!(safe_math::isqrt(4), 2);
+            assert_eq!(safe_math::isqrt(1_000_000), 1_000);
+            assert_eq!(safe_math::isqrt(u128::MAX), u64::MAX as u128);
+        }
+
+        #[ink::test]
+        fn test_mul_div() {
+            assert_eq!(safe_math::mul_div(10, 3, 4), 7);
+            assert_eq!(safe_math::mul_div(10, 3, 0), 0);
+            assert_eq!(safe_math::mul_div(u128::MAX, u128::MAX, u128::MAX), u128::MAX);
+            assert_eq!(safe_math::mul_div(u128::MAX, 2, 4), u128::MAX / 2);
+            assert_eq!(safe_math::mul_div(1 << 100, 1 << 100, 1 << 90), 1 << 110);
+            assert_eq!(safe_math::mul_div(u128::MAX, 3, 2), u128::MAX);
+            // Peso normalizado de 1e33 sobre 4e33 de um cofre com 1e24
+            let weight: u128 = 1_000_000_000_000_000_000_000_000_000_000_000;
+            assert_eq!(safe_math::mul_div(1_000_000_000_000_000_000_000_000, weight, weight.saturating_mul(4)), 250_000_000_000_000_000_000_000);
         }
     }
 }
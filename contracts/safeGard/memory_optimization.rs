@@ -10,11 +10,14 @@ mod memory_optimization_tests {
     fn setup() -> (Safeguard, ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment>) {
         let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
         set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+        // Endereço próprio do core, separado das contas de teste
+        ink::env::test::set_callee::<ink::env::DefaultEnvironment>(ink::primitives::AccountId::from([0x40; 32]));
         let contract = Safeguard::new();
         (contract, accounts)
     }
 
     /// Análise de uso de storage - verificar eficiência dos Mappings
+    #[cfg(feature = "legacy-api")]
     #[ink::test]
     fn test_storage_efficiency_analysis() {
         let (mut contract, accounts) = setup();
@@ -297,6 +300,7 @@ mod memory_optimization_tests {
     }
 
     /// Teste de otimização de estruturas de dados
+    #[cfg(feature = "legacy-api")]
     #[ink::test]
     fn test_data_structure_optimization() {
         let (mut contract, accounts) = setup();
@@ -0,0 +1,139 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Motor de score do Safeguard.
+///
+/// Recebe do core os saldos custodiados de um projeto e devolve o score (0-100).
+/// Não guarda fundos nem estado de projetos; o owner (governança do protocolo)
+/// pode trocar o código para publicar uma nova versão da fórmula.
+#[ink::contract]
+pub mod score_engine_contract {
+    use score_engine::{v1, ScoreEngine, ScoreInputs, ScoreParams};
+
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum EngineError {
+        /// Apenas o owner pode administrar o motor
+        NotOwner,
+        ZeroAddress,
+        /// `set_code_hash` recusou o novo código
+        UpgradeFailed,
+    }
+
+    #[ink(event)]
+    pub struct OwnerChanged {
+        #[ink(topic)]
+        pub new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct EngineUpgraded {
+        #[ink(topic)]
+        pub code_hash: Hash,
+    }
+
+    #[ink(storage)]
+    pub struct ScoreEngineContract {
+        owner: AccountId, // Normalmente o timelock ou a DAO do protocolo
+    }
+
+    impl ScoreEngineContract {
+        #[ink(constructor)]
+        pub fn new(owner: AccountId) -> Self {
+            Self { owner }
+        }
+
+        /// Troca o código do motor mantendo o endereço registrado no core (somente owner)
+        #[ink(message)]
+        pub fn upgrade(&mut self, code_hash: Hash) -> Result<(), EngineError> {
+            self._ensure_owner()?;
+            self.env().set_code_hash(&code_hash).map_err(|_| EngineError::UpgradeFailed)?;
+            self.env().emit_event(EngineUpgraded { code_hash });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), EngineError> {
+            self._ensure_owner()?;
+            if new_owner == AccountId::from([0u8; 32]) {
+                return Err(EngineError::ZeroAddress);
+            }
+            self.owner = new_owner;
+            self.env().emit_event(OwnerChanged { new_owner });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_owner(&self) -> AccountId {
+            self.owner
+        }
+
+        fn _ensure_owner(&self) -> Result<(), EngineError> {
+            if self.env().caller() != self.owner {
+                return Err(EngineError::NotOwner);
+            }
+            Ok(())
+        }
+    }
+
+    impl ScoreEngine for ScoreEngineContract {
+        #[ink(message)]
+        fn compute_score(&self, inputs: ScoreInputs, params: ScoreParams) -> u8 {
+            v1::compute_score(&inputs, &params)
+        }
+
+        #[ink(message)]
+        fn formula_version(&self) -> u32 {
+            v1::VERSION
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{default_accounts, set_caller};
+        use ink::prelude::vec;
+        use score_engine::ONE;
+
+        #[ink::test]
+        fn test_engine_scores_and_admin() {
+            let accounts = default_accounts::<ink::env::DefaultEnvironment>();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut engine = ScoreEngineContract::new(accounts.alice);
+            assert_eq!(engine.formula_version(), v1::VERSION);
+
+            let params = ScoreParams {
+                alpha: 500_000 * ONE,
+                gamma: 120,
+                delta: 100,
+                t_min: 100_000 * ONE,
+                theta: 20,
+                s_ref: 1_000_000_000 * ONE,
+                floor_f: 50_000_000 * ONE,
+                kappa: 0,
+                epsilon: 1,
+            };
+            let mut inputs = ScoreInputs {
+                lunes_collateral: 0,
+                other_token_collateral: vec![1_000],
+                nft_collateral: vec![],
+                project_supply: 1_000_000 * ONE,
+                current_lunes_supply: 200_000_000 * ONE,
+            };
+            // LUNES é obrigatório
+            assert_eq!(engine.compute_score(inputs.clone(), params.clone()), 0);
+            // Colateral igual ao alvo mínimo (parâmetros padrão do core)
+            inputs.lunes_collateral = 100_000 * ONE;
+            assert_eq!(engine.compute_score(inputs.clone(), params.clone()), 95);
+            assert_eq!(engine.compute_score(inputs.clone(), params.clone()), v1::compute_score(&inputs, &params));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(engine.upgrade(Hash::from([1u8; 32])), Err(EngineError::NotOwner));
+            assert_eq!(engine.transfer_ownership(accounts.bob), Err(EngineError::NotOwner));
+
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(engine.transfer_ownership(AccountId::from([0u8; 32])), Err(EngineError::ZeroAddress));
+            engine.transfer_ownership(accounts.bob).unwrap();
+            assert_eq!(engine.get_owner(), accounts.bob);
+        }
+    }
+}
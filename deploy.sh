@@ -131,7 +131,7 @@ build_contract() {
     
     # Build release version for production
     echo -e "${CYAN}Building optimized release version...${NC}"
-    if cargo contract build --release --quiet; then
+    if cargo contract build --release --features legacy-api,nft-collateral,timelock,builtin-score --quiet; then
         # Get build info (updated for lowercase package name)
         WASM_SIZE=$(ls -lh ../../target/ink/safeguard/safeguard.wasm | awk '{print $5}')
         print_success "Contract built successfully - WASM size: $WASM_SIZE"
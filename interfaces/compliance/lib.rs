@@ -0,0 +1,43 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink::primitives::AccountId;
+use ink::prelude::string::String;
+use scale::{Decode, Encode};
+
+pub type Balance = u128;
+
+/// Operations of the Safeguard contract that are screened by a compliance hook
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum ComplianceAction {
+    /// Depositing a guarantee into a project vault
+    AddGuarantee,
+    /// Casting a ballot in a project voting
+    Vote,
+    /// Claiming a share of a liquidated project
+    Claim,
+}
+
+/// Answer returned by a compliance hook
+#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum ComplianceDecision {
+    Allow,
+    Deny,
+    /// Denied with a human readable reason shown to the user
+    DenyWithReason(String),
+}
+
+/// Compliance hook consulted by the Safeguard contract
+///
+/// Issuers and the protocol can plug their own screening (sanctions lists,
+/// jurisdiction rules, ...) without the core contract taking a position.
+#[ink::trait_definition]
+pub trait ComplianceHook {
+    /// Decides whether `account` may perform `action` involving `amount`
+    ///
+    /// `amount` is the deposited amount, the raw voting weight or the
+    /// project tokens held, depending on the action
+    #[ink(message)]
+    fn check(&self, account: AccountId, action: ComplianceAction, amount: Balance) -> ComplianceDecision;
+}
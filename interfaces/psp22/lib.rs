@@ -6,6 +6,7 @@ use ink::prelude::{string::String, vec::Vec};
 use scale::{Decode, Encode};
 
 pub type Balance = u128;
+pub type BlockNumber = u32;
 
 /// PSP22 Error types based on the official standard
 #[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
@@ -195,3 +196,19 @@ pub trait PSP22Burnable {
     #[ink(message)]
     fn burn_from_caller(&mut self, amount: Balance) -> Result<(), PSP22Error>;
 }
+
+/// PSP22 Votes trait for tokens that keep historical balance checkpoints
+#[ink::trait_definition]
+pub trait PSP22Votes {
+    /// Returns the balance of `account` at the end of `block`
+    ///
+    /// `block` must be a block that has already been sealed
+    #[ink(message)]
+    fn get_past_balance(&self, account: AccountId, block: BlockNumber) -> Balance;
+
+    /// Returns the total token supply at the end of `block`
+    ///
+    /// `block` must be a block that has already been sealed
+    #[ink(message)]
+    fn get_past_total_supply(&self, block: BlockNumber) -> Balance;
+}
@@ -0,0 +1,208 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink::prelude::vec::Vec;
+use scale::{Decode, Encode};
+
+pub type Balance = u128;
+
+/// 1.0 in the 18-decimal fixed point used by the formula
+pub const ONE: Balance = 1_000_000_000_000_000_000;
+
+/// Vault balances fed by the Safeguard core for one project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ScoreInputs {
+    /// LUNES locked for the project (mandatory collateral)
+    pub lunes_collateral: Balance,
+    /// Totals of the other active PSP22 tokens, before haircuts
+    pub other_token_collateral: Vec<Balance>,
+    /// Estimated value per NFT collection, before haircuts
+    pub nft_collateral: Vec<Balance>,
+    pub project_supply: Balance,
+    pub current_lunes_supply: Balance,
+}
+
+/// Governance parameters of Score v1.1 (percentages scaled by 100)
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ScoreParams {
+    pub alpha: Balance,
+    pub gamma: u32,
+    pub delta: u32,
+    pub t_min: Balance,
+    pub theta: u32,
+    pub s_ref: Balance,
+    pub floor_f: Balance,
+    pub kappa: u32,
+    pub epsilon: Balance,
+}
+
+/// Score engine consulted by the Safeguard core
+///
+/// Custody stays in the core; the engine only turns balances into a 0-100
+/// score, so the formula can be versioned and upgraded on its own.
+#[ink::trait_definition]
+pub trait ScoreEngine {
+    /// Score (0-100) of a project with the given balances
+    #[ink(message)]
+    fn compute_score(&self, inputs: ScoreInputs, params: ScoreParams) -> u8;
+
+    /// Version of the formula implemented by the engine
+    #[ink(message)]
+    fn formula_version(&self) -> u32;
+}
+
+/// Reference implementation of Score v1.1
+///
+/// Used by the engine contract and as the core's built-in fallback.
+pub mod v1 {
+    use super::{Balance, ScoreInputs, ScoreParams, ONE};
+
+    pub const VERSION: u32 = 1;
+
+    /// LUNES component (up to 95 points) plus other collateral (up to 5 points)
+    pub fn compute_score(inputs: &ScoreInputs, params: &ScoreParams) -> u8 {
+        let lunes_collateral = inputs.lunes_collateral;
+
+        // If no Lunes collateral, score is 0 (Lunes is mandatory)
+        if lunes_collateral == 0 {
+            return 0;
+        }
+
+        // Calculate burn progress (π)
+        let pi = burn_progress(inputs.current_lunes_supply, params);
+
+        // Calculate effective Lunes supply
+        let s_l_eff = effective_lunes_supply(inputs.current_lunes_supply, pi, params);
+
+        // Calculate project relative size for base target calculation
+        let r_proj = inputs.project_supply.saturating_div(params.s_ref.max(params.epsilon));
+
+        // Calculate base target T_base
+        let t_base = base_target(r_proj, params);
+
+        // Apply temporal uplift
+        let uplift = uplift(pi, params);
+        let t_prime = t_base.saturating_div(uplift.max(params.epsilon));
+
+        // Calculate Lunes component (up to 95 points)
+        let ratio = lunes_collateral.saturating_mul(95).saturating_div(t_prime.max(params.epsilon));
+        let s_lunes = ratio.min(95);
+
+        // Calculate other tokens component (up to 5 points)
+        let s_other = other_tokens_score(inputs, s_l_eff, params);
+
+        // Final score (capped at 100)
+        s_lunes.saturating_add(s_other).min(100) as u8
+    }
+
+    /// Burn progress (π) - normalized between 0 and 100
+    pub fn burn_progress(current_lunes_supply: Balance, params: &ScoreParams) -> u32 {
+        let initial_supply = 200_000_000_u128.saturating_mul(ONE); // 200M Lunes
+        let floor_supply = params.floor_f;
+
+        if current_lunes_supply <= floor_supply {
+            100 // π = 1.0 (represented as 100)
+        } else if current_lunes_supply >= initial_supply {
+            0 // π = 0.0
+        } else {
+            // π = (200M - S_L_atual) / (200M - floor_f)
+            let numerator = initial_supply.saturating_sub(current_lunes_supply);
+            let denominator = initial_supply.saturating_sub(floor_supply);
+            if denominator > 0 {
+                numerator.saturating_mul(100).saturating_div(denominator).min(100) as u32
+            } else {
+                0
+            }
+        }
+    }
+
+    /// Effective Lunes supply S_L_eff = (1 - π) * S_L_atual + π * floor_f
+    pub fn effective_lunes_supply(current_lunes_supply: Balance, pi: u32, params: &ScoreParams) -> Balance {
+        let pi_balance = pi as Balance;
+        let one_minus_pi = 100_u32.saturating_sub(pi) as Balance;
+
+        let term1 = one_minus_pi.saturating_mul(current_lunes_supply).saturating_div(100);
+        let term2 = pi_balance.saturating_mul(params.floor_f).saturating_div(100);
+
+        term1.saturating_add(term2)
+    }
+
+    /// Base target T_base = max(T_min, α * r_proj^γ)
+    pub fn base_target(r_proj: Balance, params: &ScoreParams) -> Balance {
+        let gamma_factor = power_approximation(r_proj, params.gamma);
+        let alpha_times_gamma = params.alpha.saturating_mul(gamma_factor).saturating_div(ONE); // Normalize
+
+        alpha_times_gamma.max(params.t_min)
+    }
+
+    /// Temporal uplift u(π) = 1 + θ * π
+    pub fn uplift(pi: u32, params: &ScoreParams) -> Balance {
+        // θ and π are scaled by 100
+        let theta_times_pi = (params.theta as Balance).saturating_mul(pi as Balance).saturating_div(10000);
+        let uplift_scaled = theta_times_pi.saturating_mul(ONE).saturating_div(100);
+
+        ONE.saturating_add(uplift_scaled)
+    }
+
+    /// Other collateral component (up to 5 points)
+    pub fn other_tokens_score(inputs: &ScoreInputs, s_l_eff: Balance, params: &ScoreParams) -> Balance {
+        if inputs.lunes_collateral == 0 {
+            return 0; // No score for other tokens if no Lunes
+        }
+
+        // PSP22 tokens take a 90% haircut, NFTs 50%
+        let token_value = inputs.other_token_collateral.iter()
+            .fold(0 as Balance, |acc, total| acc.saturating_add(total.saturating_mul(90).saturating_div(100)));
+        let other_tokens_value = inputs.nft_collateral.iter()
+            .fold(token_value, |acc, total| acc.saturating_add(total.saturating_mul(50).saturating_div(100)));
+
+        if other_tokens_value == 0 {
+            return 0;
+        }
+
+        // q = E_other / max(C_L, ε)
+        let q = other_tokens_value.saturating_div(inputs.lunes_collateral.max(params.epsilon));
+
+        // r_extra for the penalty
+        let r_extra = inputs.project_supply.saturating_div(s_l_eff.max(params.epsilon));
+
+        // g(r_extra) = 1 / (1 + r_extra^δ)
+        let r_extra_delta = power_approximation(r_extra, params.delta);
+        let denominator = ONE.saturating_add(r_extra_delta);
+        let g = ONE.saturating_div(denominator.max(params.epsilon));
+
+        // S_other = 5 * min(1, q * g)
+        let q_times_g = q.saturating_mul(g).saturating_div(ONE);
+        let score_factor = q_times_g.min(ONE);
+
+        score_factor.saturating_mul(5).saturating_div(ONE)
+    }
+
+    /// Integer power approximation; `exponent` scaled by 100 (120 = 1.2)
+    pub fn power_approximation(base: Balance, exponent: u32) -> Balance {
+        if exponent == 0 {
+            return ONE;
+        }
+
+        let exp_whole = exponent.saturating_div(100);
+        let exp_frac = exponent.checked_rem(100).unwrap_or(0);
+
+        let mut result: Balance = ONE;
+
+        // Handle whole part of exponent
+        for _ in 0..exp_whole {
+            result = result.saturating_mul(base).saturating_div(ONE);
+        }
+
+        // Fractional part, linear approximation: x^0.2 ≈ 1 + 0.2*(x-1)
+        if exp_frac > 0 {
+            let base_minus_one = base.saturating_sub(ONE);
+            let frac_adjustment = base_minus_one.saturating_mul(exp_frac as Balance).saturating_div(100);
+            let frac_result = ONE.saturating_add(frac_adjustment);
+            result = result.saturating_mul(frac_result).saturating_div(ONE);
+        }
+
+        result
+    }
+}